@@ -1,11 +1,18 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
 use pytrees::algorithms::algorithm_trait::Algorithm;
+use pytrees::algorithms::dl85::DL85;
+use pytrees::algorithms::dl85_utils::leaf_objective::Misclassification;
+use pytrees::algorithms::dl85_utils::structs_enums::{
+    BranchingType, CacheInit, CacheType, LowerBoundHeuristic, Specialization,
+};
 use pytrees::algorithms::info_gain::InfoGain;
 use pytrees::algorithms::lgdt::LGDT;
 use pytrees::algorithms::murtree::MurTree;
 use pytrees::dataset::binary_dataset::BinaryDataset;
 use pytrees::dataset::data_trait::Dataset;
+use pytrees::heuristics::{Heuristic, NoHeuristic};
 use pytrees::structures::bitsets_structure::BitsetStructure;
+use pytrees::structures::caching::trie::Data;
 use pytrees::structures::horizontal_binary_structure::HorizontalBinaryStructure;
 use pytrees::structures::raw_binary_structure::RawBinaryStructure;
 use pytrees::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
@@ -17,7 +24,7 @@ pub fn anneal_horiz_benchmark(c: &mut Criterion) {
     let bitset_data = HorizontalBinaryStructure::format_input_data(&dataset);
     let mut structure = HorizontalBinaryStructure::new(&bitset_data);
     c.bench_function("hz_anneal", |b| {
-        b.iter(|| LGDT::fit(&mut structure, 1, 5, InfoGain::fit))
+        b.iter(|| LGDT::fit(&mut structure, 1., 5, InfoGain::fit))
     });
 }
 
@@ -27,7 +34,7 @@ pub fn anneal_bitset_benchmark(c: &mut Criterion) {
     let bitset_data = BitsetStructure::format_input_data(&dataset);
     let mut structure = BitsetStructure::new(&bitset_data);
     c.bench_function("bit_anneal", |b| {
-        b.iter(|| LGDT::fit(&mut structure, 1, 5, InfoGain::fit))
+        b.iter(|| LGDT::fit(&mut structure, 1., 5, InfoGain::fit))
     });
 }
 
@@ -37,7 +44,7 @@ pub fn anneal_rsparse_benchmark(c: &mut Criterion) {
     let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
     let mut structure = RSparseBitsetStructure::new(&bitset_data);
     c.bench_function("rsparse_anneal", |b| {
-        b.iter(|| LGDT::fit(&mut structure, 1, 5, InfoGain::fit))
+        b.iter(|| LGDT::fit(&mut structure, 1., 5, InfoGain::fit))
     });
 }
 
@@ -54,7 +61,7 @@ fn compare_struct_on_dataset(c: &mut Criterion) {
     // group.sampling_mode(SamplingMode::Flat);
 
     for depth in [2, 3, 5, 7] {
-        for minsup in [5] {
+        for minsup in [5.] {
             let parameter = (minsup, depth);
             let parameter_string = format!("s_{}_d_{}", minsup, depth);
 
@@ -119,10 +126,58 @@ fn compare_struct_on_dataset(c: &mut Criterion) {
     group.finish();
 }
 
+fn compare_cache_on_dataset(c: &mut Criterion) {
+    let mut group = c.benchmark_group("DL85_cache");
+    group.sample_size(10);
+
+    for (name, depth) in [
+        ("anneal", 3),
+        ("ionosphere", 3),
+        ("mushroom", 3),
+        ("splice-1", 2),
+    ] {
+        let dataset = BinaryDataset::load(&format!("test_data/{name}.txt"), false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let parameter_string = format!("{name}_d_{depth}");
+
+        for (cache_name, cache_type) in [("trie", CacheType::Trie), ("hash", CacheType::Hash)] {
+            group.bench_with_input(
+                BenchmarkId::new(cache_name, &parameter_string),
+                &depth,
+                |b, depth| {
+                    b.iter(|| {
+                        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+                        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+                        let mut algo: DL85<'_, _, Data> = DL85::new(
+                            1.,
+                            black_box(*depth),
+                            <f64>::MAX,
+                            <usize>::MAX,
+                            Specialization::None,
+                            LowerBoundHeuristic::Similarity,
+                            BranchingType::Dynamic,
+                            CacheInit::Normal,
+                            0,
+                            false,
+                            heuristic.as_mut(),
+                            Misclassification,
+                        );
+                        algo.set_cache_type(cache_type);
+                        algo.fit(&mut structure).unwrap();
+                        algo.statistics.tree_error
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
 criterion_group!(
     name = benches;
     config = Criterion::default().sample_size(30);
-    targets = compare_struct_on_dataset
+    targets = compare_struct_on_dataset,
+    compare_cache_on_dataset
     //anneal_horiz_benchmark,
     //anneal_bitset_benchmark,
     //anneal_rsparse_benchmark
@@ -1,38 +1,16 @@
 from enum import IntEnum
 
-
-class Specialization(IntEnum):
-    None_ = 0
-    MurTree = 1
-
-
-class LowerBound(IntEnum):
-    None_ = 0
-    Similarity = 1
-
-
-class Branching(IntEnum):
-    None_ = 0
-    Dynamic = 1
-
-
-class CacheInit(IntEnum):
-    None_ = 0
-    Dynamic = 1
-    FromUser = 2
-
-
-class Heuristic(IntEnum):
-    None_ = 0
-    InformationGain = 1
-    InformationGainRatio = 2
-    GiniIndex = 3
-
-
-class DiscrepancyStrategy(IntEnum):
-    None_ = 0
-    Incremental = 1
-    Double = 2
+# The enums of the optimal searches are the ones of the Rust crate
+from pytrees_internal.optimal import (
+    Specialization,
+    LowerBound,
+    Branching,
+    CacheInit,
+    CacheType,
+    Heuristic,
+    DiscrepancyStrategy,
+    CustomFunctionDataType,
+)
 
 
 class FitMethod(IntEnum):
@@ -45,8 +23,3 @@ class DataStructure(IntEnum):
     Horizontal = (1,)
     Bitset = (2,)
     ReversibleBitset = (3,)
-
-
-class CustomFunctionDataType(IntEnum):
-    ClassSupports = 0
-    Tids = 1
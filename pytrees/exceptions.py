@@ -2,5 +2,7 @@ class TreeNotFoundError(Exception):
     pass
 
 
-class SearchFailedError(Exception):
+# Raised when the internal search fails to run to its end. Invalid parameters and unsupported
+# combinations of options raise ValueError, a cache that cannot be allocated MemoryError.
+class SearchFailedError(RuntimeError):
     pass
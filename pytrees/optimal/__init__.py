@@ -1,2 +1,2 @@
-from .dl85 import DL85Classifier
+from .dl85 import DL85Classifier, DL85Regressor
 from .lds_dl85 import LDSDL85Classifier as LDS_Classifier
@@ -1,10 +1,11 @@
-from sklearn.base import BaseEstimator, ClassifierMixin
+from sklearn.base import BaseEstimator, ClassifierMixin, RegressorMixin
 from pytrees.predictor import Predictor
 from pytrees.enum_params import (
     Specialization,
     LowerBound,
     Branching,
     CacheInit,
+    CacheType,
     Heuristic,
     DiscrepancyStrategy,
 )
@@ -25,8 +26,25 @@ class DL85Classifier(Predictor, BaseEstimator, ClassifierMixin):
         branching=Branching.Dynamic,
         cache_init=CacheInit.Dynamic,
         cache_init_size=0,
+        cache_type=CacheType.Trie,
+        cover_cache=False,
         custom_function=None,
         custom_function_type=None,
+        cost_matrix=None,
+        leaf_penalty=0.0,
+        max_leaves=None,
+        all_depths=False,
+        anytime=False,
+        incumbent_callback=None,
+        memory_limit=None,
+        warm_start_file=None,
+        cache_file=None,
+        num_threads=1,
+        initial_tree=None,
+        top_k=None,
+        rashomon_epsilon=None,
+        progress_callback=None,
+        progress_interval=1.0,
     ):
         super().__init__()
         self.min_sup = min_sup
@@ -40,9 +58,97 @@ class DL85Classifier(Predictor, BaseEstimator, ClassifierMixin):
         self.branching = branching
         self.cache_init = cache_init
         self.cache_init_size = cache_init_size
+        self.cache_type = cache_type
+        self.cover_cache = cover_cache
         self.one_time_sort = one_time_sort
         self.heuristic = heuristic
         self.custom_function = custom_function
         self.custom_function_type = custom_function_type
+        self.cost_matrix = cost_matrix
+        self.leaf_penalty = leaf_penalty
+        self.max_leaves = max_leaves
+        self.all_depths = all_depths
+        self.anytime = anytime
+        self.incumbent_callback = incumbent_callback
+        self.memory_limit = memory_limit
+        self.warm_start_file = warm_start_file
+        self.cache_file = cache_file
+        self.num_threads = num_threads
+        self.initial_tree = initial_tree
+        self.top_k = top_k
+        self.rashomon_epsilon = rashomon_epsilon
+        self.progress_callback = progress_callback
+        self.progress_interval = progress_interval
+
+        self.set_internal_class(Dl85InternalClassifier)
+
+
+class DL85Regressor(Predictor, BaseEstimator, RegressorMixin):
+    """Optimal regression tree, leaves predict the mean target and the error is the sum of
+    squared deviations from it. The MurTree specialization and the similarity lower bound
+    only apply to classification."""
+
+    def __init__(
+        self,
+        min_sup=1,
+        max_depth=1,
+        max_error=-1,
+        max_time=-1,
+        one_time_sort=True,
+        heuristic=Heuristic.None_,
+        branching=Branching.Dynamic,
+        cache_init=CacheInit.Dynamic,
+        cache_init_size=0,
+        cache_type=CacheType.Trie,
+        cover_cache=False,
+        leaf_penalty=0.0,
+        max_leaves=None,
+        all_depths=False,
+        anytime=False,
+        incumbent_callback=None,
+        memory_limit=None,
+        warm_start_file=None,
+        cache_file=None,
+        num_threads=1,
+        initial_tree=None,
+        top_k=None,
+        rashomon_epsilon=None,
+        progress_callback=None,
+        progress_interval=1.0,
+    ):
+        super().__init__()
+        self.min_sup = min_sup
+        self.max_depth = max_depth
+        self.discrepancy_budget = 0
+        self.discrepancy_strategy = DiscrepancyStrategy.None_
+        self.max_error = max_error
+        self.max_time = max_time
+        self.specialization = Specialization.None_
+        self.lower_bound = LowerBound.None_
+        self.branching = branching
+        self.cache_init = cache_init
+        self.cache_init_size = cache_init_size
+        self.cache_type = cache_type
+        self.cover_cache = cover_cache
+        self.one_time_sort = one_time_sort
+        self.heuristic = heuristic
+        self.custom_function = None
+        self.custom_function_type = None
+        self.cost_matrix = None
+        self.leaf_penalty = leaf_penalty
+        self.max_leaves = max_leaves
+        self.all_depths = all_depths
+        self.anytime = anytime
+        self.incumbent_callback = incumbent_callback
+        self.memory_limit = memory_limit
+        self.warm_start_file = warm_start_file
+        self.cache_file = cache_file
+        self.num_threads = num_threads
+        self.initial_tree = initial_tree
+        self.top_k = top_k
+        self.rashomon_epsilon = rashomon_epsilon
+        self.progress_callback = progress_callback
+        self.progress_interval = progress_interval
+        self.is_regression_ = True
 
         self.set_internal_class(Dl85InternalClassifier)
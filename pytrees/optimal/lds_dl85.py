@@ -5,6 +5,7 @@ from pytrees.enum_params import (
     LowerBound,
     Branching,
     CacheInit,
+    CacheType,
     Heuristic,
     DiscrepancyStrategy,
 )
@@ -27,6 +28,7 @@ class LDSDL85Classifier(Predictor, BaseEstimator, ClassifierMixin):
         branching=Branching.None_,
         cache_init=CacheInit.Dynamic,
         cache_init_size=0,
+        cache_type=CacheType.Trie,
     ):
         super().__init__()
         self.is_optimal_ = True
@@ -41,7 +43,26 @@ class LDSDL85Classifier(Predictor, BaseEstimator, ClassifierMixin):
         self.branching = branching
         self.cache_init = cache_init
         self.cache_init_size = cache_init_size
+        self.cache_type = cache_type
         self.one_time_sort = one_time_sort
         self.heuristic = heuristic
+        self.custom_function = None
+        self.custom_function_type = None
+        self.cost_matrix = None
+        self.leaf_penalty = 0.0
+        self.max_leaves = None
+        self.all_depths = False
+        self.anytime = False
+        self.incumbent_callback = None
+        self.memory_limit = None
+        self.cover_cache = False
+        self.warm_start_file = None
+        self.cache_file = None
+        self.num_threads = 1
+        self.initial_tree = None
+        self.top_k = None
+        self.rashomon_epsilon = None
+        self.progress_callback = None
+        self.progress_interval = 1.0
 
         self.set_internal_class(Dl85InternalClassifier)
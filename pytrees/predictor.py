@@ -1,6 +1,8 @@
 import json
 import uuid
 
+import numpy as np
+
 from sklearn.utils import check_array, check_X_y, assert_all_finite
 from sklearn.exceptions import NotFittedError
 from .exceptions import TreeNotFoundError, SearchFailedError
@@ -21,8 +23,25 @@ class Predictor:
         "heuristic",
         "cache_init",
         "cache_init_size",
+        "leaf_penalty",
+        "all_depths",
+        "anytime",
+        "cache_type",
+        "cover_cache",
         "custom_function",
         "custom_function_type",
+        "cost_matrix",
+        "max_leaves",
+        "memory_limit",
+        "incumbent_callback",
+        "warm_start_file",
+        "cache_file",
+        "num_threads",
+        "initial_tree",
+        "top_k",
+        "rashomon_epsilon",
+        "progress_callback",
+        "progress_interval",
     ]
 
     LESS_GREEDY_ARGS = ["min_sup", "max_depth", "data_structure", "fit_method"]
@@ -45,7 +64,9 @@ class Predictor:
         self.__internal_class = None
         self.is_optimal_ = True
         self.is_parallel_ = False
+        self.is_regression_ = False
         self.statistics = None
+        self.classes_ = None
 
     # def set_classifier(self, clf):
     #     self.__internal_classifier = clf
@@ -56,7 +77,7 @@ class Predictor:
     def load_classifier(self):
         # print("Loading classifier...")
         # print("Parallel ? ", self.is_parallel_)
-        args = list()
+        kwargs = dict()
         params = None
         if self.is_optimal_:  # optimal
             params = self.OPTIMAL_ARGS
@@ -67,17 +88,36 @@ class Predictor:
                 params = self.LESS_GREEDY_ARGS
         # print("Params: ", params)
         for arg in params:
-            args.append(getattr(self, arg))
+            value = getattr(self, arg)
+            if arg == "cost_matrix" and value is not None:
+                value = np.asarray(value, dtype="float64")
+            if arg == "initial_tree" and value is not None and value != "lgdt":
+                # A tree_ of another predictor, or any tree in the same format
+                value = json.dumps(value)
+            kwargs[arg] = value
 
-        self.__internal_classifier = self.__internal_class(*args)
+        self.__internal_classifier = self.__internal_class(**kwargs)
 
-    def fit(self, X, y=None):
+    def fit(self, X, y=None, sample_weight=None):
 
         target_is_need = True if y is not None else False
 
         if target_is_need:  # target-needed tasks (eg: classification, regression, etc.)
             # Check that X and y have correct shape and raise ValueError if not
-            X, y = check_X_y(X, y, dtype="float64")
+            X, y = check_X_y(X, y, dtype="float64", y_numeric=self.is_regression_)
+            if not self.is_regression_:
+                # Labels are encoded as 0..K-1 for the internal classifiers
+                self.classes_, y = np.unique(y, return_inverse=True)
+                y = y.astype("float64")
+                # Rows and columns of the cost matrix follow the sorted classes
+                cost_matrix = getattr(self, "cost_matrix", None)
+                if cost_matrix is not None and np.shape(cost_matrix) != (
+                    len(self.classes_),
+                    len(self.classes_),
+                ):
+                    raise ValueError(
+                        "cost_matrix must have one row and one column per class"
+                    )
             # if opt_func is None and opt_pred_func is None:
             #     print("No optimization criterion defined. Misclassification error is used by default.")
         else:  # target-less tasks (clustering, etc.)
@@ -85,12 +125,33 @@ class Predictor:
             assert_all_finite(X)
             X = check_array(X, dtype="float64")
 
-        self.load_classifier()
-        self.__internal_classifier.train(X, y)
-
-        tree = json.loads(self.__internal_classifier.tree)
+        if sample_weight is not None:
+            # Weights act as row multiplicities, fractional ones included, and min_sup
+            # bounds the weighted support
+            sample_weight = np.asarray(sample_weight, dtype="float64")
+            if sample_weight.shape != (X.shape[0],):
+                raise ValueError("sample_weight must have one value per sample")
+            if np.any(sample_weight < 0) or not np.all(np.isfinite(sample_weight)):
+                raise ValueError("sample_weight must be finite and non-negative")
 
-        if len(tree["tree"]) == 1 and tree["tree"][0]["value"]["out"] not in [0, 1]:
+        self.load_classifier()
+        try:
+            if self.is_regression_:
+                self.__internal_classifier.train_regression(X, y, sample_weight)
+            else:
+                self.__internal_classifier.train(X, y, sample_weight)
+        except RuntimeError as error:
+            raise SearchFailedError(str(error)) from error
+
+        # The trees and the statistics come as dicts, the durations in seconds
+        tree = self.__internal_classifier.tree
+
+        root = tree["tree"][0]["value"]
+        if len(tree["tree"]) == 1 and (
+            root["out"] is None
+            or (self.classes_ is not None and root["out"] >= len(self.classes_))
+            or (self.is_regression_ and root["prediction"] is None)
+        ):
             self.tree_ = None
         else:
             self.tree_ = tree
@@ -98,8 +159,18 @@ class Predictor:
             self.tree_error_ = tree["tree"][0]["value"]["error"]
             self.compute_max_depth()
             self.compute_size()
-            self.compute_accuracy(len(X))
-        self.statistics = json.loads(self.__internal_classifier.statistics)
+            if not self.is_regression_ and target_is_need:
+                self.compute_accuracy(X, y, sample_weight)
+        self.statistics = self.__internal_classifier.statistics
+        if getattr(self, "all_depths", False):
+            # Tree of depth d at index d - 1, the empty trees have no solution
+            self.depth_trees_ = self.__internal_classifier.depth_trees
+        if (
+            getattr(self, "top_k", None) is not None
+            or getattr(self, "rashomon_epsilon", None) is not None
+        ):
+            # Best trees by increasing objective, listed in statistics["top_errors"]
+            self.top_trees_ = self.__internal_classifier.top_trees
 
     def compute_max_depth(self):
         def recursion(subtree_index):
@@ -117,9 +188,17 @@ class Predictor:
         if self.is_fitted_:
             self.size_ = len(self.tree_["tree"])
 
-    def compute_accuracy(self, train_size):
+    def compute_accuracy(self, X, y, sample_weight=None):
+        # From the predictions, tree_error_ is a total cost with a cost matrix or a
+        # custom function, not a number of misclassified samples
         if self.is_fitted_:
-            self.accuracy_ = round(1 - self.tree_error_ / train_size, 5)
+            predictions = np.array([self.pred_value_on_dict(row) for row in X])
+            correct = predictions == y
+            if sample_weight is not None and np.sum(sample_weight) > 0:
+                accuracy = np.average(correct, weights=sample_weight)
+            else:
+                accuracy = np.mean(correct)
+            self.accuracy_ = round(float(accuracy), 5)
 
     @staticmethod
     def is_leaf_node(node):
@@ -169,7 +248,8 @@ class Predictor:
         pred = []
 
         for i in range(X.shape[0]):
-            pred.append(self.pred_value_on_dict(X[i, :]))
+            out = self.pred_value_on_dict(X[i, :])
+            pred.append(self.classes_[out] if self.classes_ is not None else out)
 
         return pred
 
@@ -180,6 +260,8 @@ class Predictor:
                 node = self.tree_["tree"][node["right"]]
             else:
                 node = self.tree_["tree"][node["left"]]
+        if self.is_regression_:
+            return node["value"]["prediction"]
         return node["value"]["out"]
 
     def get_dot_body_rec(self, node, parent=None, left=0):
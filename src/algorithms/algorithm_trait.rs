@@ -1,6 +1,7 @@
+use crate::algorithms::dl85_utils::leaf_objective::expected_cost;
 use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::{Attribute, Depth, Index, Item, Support};
+use crate::structures::structures_types::{Attribute, CostMatrix, Depth, Index, Support};
 
 pub trait Algorithm {
     fn build_depth_one_tree<S>(structure: &mut S, min_sup: Support) -> Tree<NodeData>
@@ -38,7 +39,7 @@ pub trait Algorithm {
     fn build_depth_two_matrix<S>(
         structure: &mut S,
         candidates: &Vec<Attribute>,
-    ) -> Vec<Vec<Vec<usize>>>
+    ) -> Vec<Vec<Vec<Support>>>
     where
         S: Structure,
     {
@@ -100,12 +101,15 @@ pub trait Algorithm {
         }
     }
 
-    fn create_leaves(leaf_ref: &mut TreeNode<NodeData>, data: &[usize], error: usize) {
+    fn create_leaves(leaf_ref: &mut TreeNode<NodeData>, data: &[Support], error: f64) {
         leaf_ref.value.error = error;
-        leaf_ref.value.out = match data[1] > data[0] {
-            true => Some(1),
-            false => Some(0),
-        };
+        let mut top_class = 0;
+        for (class, support) in data.iter().enumerate() {
+            if *support > data[top_class] {
+                top_class = class;
+            }
+        }
+        leaf_ref.value.out = Some(top_class);
     }
 }
 
@@ -114,7 +118,7 @@ pub trait Basic {
         node.left == node.right
     }
 
-    fn get_diff_errors(main_errors: &[usize], sub_errors: &[usize]) -> Vec<usize> {
+    fn get_diff_errors(main_errors: &[Support], sub_errors: &[Support]) -> Vec<Support> {
         let mut errors = vec![];
         for i in main_errors.iter().zip(sub_errors.iter()) {
             errors.push(i.0 - i.1);
@@ -122,26 +126,34 @@ pub trait Basic {
         errors
     }
 
-    fn get_leaf_error(classes_support: &[usize]) -> (usize, usize) {
+    // Supports are weighted when the structure carries sample weights, so is the error
+    fn get_leaf_error(classes_support: &[Support]) -> (f64, usize) {
         let mut max_idx = 0;
-        let mut max_value = 0;
-        let mut total = 0;
+        let mut max_value = 0.;
+        let mut total = 0.;
         for (idx, value) in classes_support.iter().enumerate() {
             total += value;
-            if *value >= max_value {
+            if *value > max_value {
                 max_value = *value;
                 max_idx = idx;
             }
         }
-        let error = total - max_value;
-        (error, max_idx)
+        (total - max_value, max_idx)
     }
 
-    fn get_misclassification_error(classes_support: &[usize]) -> usize {
-        classes_support.iter().sum::<usize>() - classes_support.iter().max().unwrap()
+    // With a cost matrix the leaf predicts the class of minimum expected cost
+    fn get_leaf_cost(classes_support: &[Support], costs: Option<&CostMatrix>) -> (f64, usize) {
+        match costs {
+            None => Self::get_leaf_error(classes_support),
+            Some(costs) => expected_cost(classes_support, costs),
+        }
     }
 
-    fn get_top_class(classes_support: &[usize]) -> usize {
+    fn get_misclassification_error(classes_support: &[Support]) -> Support {
+        classes_support.iter().sum::<Support>() - classes_support.iter().copied().fold(0., f64::max)
+    }
+
+    fn get_top_class(classes_support: &[Support]) -> usize {
         classes_support
             .iter()
             .enumerate()
@@ -155,11 +167,11 @@ pub trait Basic {
             .0
     }
 
-    fn get_tree_error(tree: &Tree<NodeData>) -> usize {
+    fn get_tree_error(tree: &Tree<NodeData>) -> f64 {
         if let Some(root) = tree.get_node(tree.get_root_index()) {
             return root.value.error;
         }
-        <usize>::MAX
+        <f64>::MAX
     }
 
     fn create_child(tree: &mut Tree<NodeData>, parent: Index, is_left: bool) -> Index {
@@ -173,13 +185,26 @@ pub trait Basic {
         structure: &mut S,
         parent: Index,
         is_left: bool,
-    ) -> usize
+    ) -> f64
+    where
+        S: Structure,
+    {
+        Self::create_leaf_with_costs(tree, structure, parent, is_left, None)
+    }
+
+    fn create_leaf_with_costs<S>(
+        tree: &mut Tree<NodeData>,
+        structure: &mut S,
+        parent: Index,
+        is_left: bool,
+        costs: Option<&CostMatrix>,
+    ) -> f64
     where
         S: Structure,
     {
         let leaf_index = Self::create_child(tree, parent, is_left);
         let classes_support = structure.labels_support();
-        let error = Self::get_leaf_error(classes_support);
+        let error = Self::get_leaf_cost(classes_support, costs);
         if let Some(leaf) = tree.get_node_mut(leaf_index) {
             leaf.value.error = error.0;
             leaf.value.out = Some(error.1)
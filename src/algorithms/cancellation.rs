@@ -0,0 +1,129 @@
+use rayon::ThreadPool;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// How often the calling thread of `run_on_pool` checks its token while the workers run
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Asks a running fit to stop, the clones of a token share its state. The searches check it along
+/// their recursion and keep the best tree found so far.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    watcher: Option<Arc<Watcher>>,
+}
+
+// Polled by the checks of the token, at most once per interval
+struct Watcher {
+    poll: Box<dyn Fn() -> bool + Send + Sync>,
+    interval: Duration,
+    start: Instant,
+    next_poll: AtomicU64, // Nanoseconds since `start`
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token also cancelled once `poll` returns true. `poll` runs from the threads checking the
+    /// token, at most once per `interval`, to notice a Ctrl-C from Python for instance.
+    pub fn with_watcher<F>(poll: F, interval: Duration) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            watcher: Some(Arc::new(Watcher {
+                poll: Box::new(poll),
+                interval,
+                start: Instant::now(),
+                next_poll: AtomicU64::new(0),
+            })),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether the token is cancelled, without polling the watcher. Once a fit is over, tells
+    /// whether the fit saw the cancellation.
+    pub fn was_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        if let Some(watcher) = &self.watcher {
+            let now = watcher.start.elapsed().as_nanos() as u64;
+            let next_poll = watcher.next_poll.load(Ordering::Relaxed);
+            // A single thread wins the poll of each interval
+            if now >= next_poll
+                && watcher
+                    .next_poll
+                    .compare_exchange(
+                        next_poll,
+                        now.saturating_add(watcher.interval.as_nanos() as u64),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+                && (watcher.poll)()
+            {
+                self.cancel();
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Runs `work` on each job on `pool` and returns the results in the order of the jobs. The workers
+/// get a token of their own, which the calling thread cancels once `token` is. A watcher polling
+/// the Python signals then runs on the training thread, the only one that sees them.
+pub(crate) fn run_on_pool<J, R, F>(
+    pool: &ThreadPool,
+    jobs: Vec<J>,
+    token: Option<&CancellationToken>,
+    work: F,
+) -> Vec<R>
+where
+    J: Send,
+    R: Send,
+    F: Fn(J, &CancellationToken) -> R + Sync,
+{
+    let workers_token = CancellationToken::new();
+    let (sender, receiver) = channel();
+    let mut results = Vec::with_capacity(jobs.len());
+    pool.in_place_scope(|scope| {
+        for (index, job) in jobs.into_iter().enumerate() {
+            let (sender, workers_token, work) = (sender.clone(), &workers_token, &work);
+            scope.spawn(move |_| {
+                let _ = sender.send((index, work(job, workers_token)));
+            });
+        }
+        // The channel disconnects once every job is done, or has panicked
+        drop(sender);
+        loop {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(result) => results.push(result),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if token.is_some_and(|token| token.is_cancelled()) {
+                workers_token.cancel();
+            }
+        }
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
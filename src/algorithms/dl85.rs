@@ -1,50 +1,89 @@
 use crate::algorithms::algorithm_trait::{Algorithm, Basic};
+use crate::algorithms::cancellation::CancellationToken;
+use crate::algorithms::dl85_utils::cache_snapshot::CacheSnapshot;
+use crate::algorithms::dl85_utils::leaf_objective::{
+    expected_cost, LeafObjective, Misclassification,
+};
+use crate::algorithms::dl85_utils::shared_cache::SharedCache;
 use crate::algorithms::dl85_utils::slb::{SimilarDatasets, Similarity};
-use crate::algorithms::dl85_utils::stop_conditions::StopConditions;
+use crate::algorithms::dl85_utils::stop_conditions::{NodeState, StopConditions};
 use crate::algorithms::dl85_utils::structs_enums::{
-    Branching, BranchingType, CacheInit, Constraints, DiscrepancyStrategy, HasIntersected,
-    LowerBoundHeuristic, PythonFunctionData, ReturnCondition, Specialization, Statistics,
+    Branching, BranchingType, CacheInit, CacheType, Constraints, DiscrepancyStrategy,
+    HasIntersected, Incumbent, LowerBoundHeuristic, Progress, ReturnCondition, SearchCounters,
+    SearchStatus, Specialization, Statistics,
 };
 use crate::algorithms::lgdt::LGDT;
 use crate::algorithms::murtree::MurTree;
+use crate::errors::{PyTreesError, PyTreesResult};
 use crate::heuristics::Heuristic;
 use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
+use crate::structures::caching::cache_trait::{Cache, CacheBackend};
+use crate::structures::caching::hash_cache::HashCache;
 use crate::structures::caching::trie::{DataTrait, Trie, TrieNode};
-use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::{Attribute, Depth, Index, Item, Support};
-use pyo3::{PyObject, Python};
-use std::cmp::{max, min};
-use std::collections::BTreeSet;
+use crate::structures::structures_types::{
+    Attribute, CostMatrix, Depth, Index, Item, RegressionStatistics, Support,
+};
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-// TODO : Is not working for generic types because of the trait bounds and the use of external methods
+// Pseudo attributes ending the cache keys, they sort after every attribute of the itemset
+pub(crate) const DEPTH_ATTRIBUTE: Attribute = Attribute::MAX - 1;
+pub(crate) const BUDGET_ATTRIBUTE: Attribute = Attribute::MAX;
 
-pub struct DL85<'heur, H, T>
+pub struct DL85<'heur, H, T, O = Misclassification>
 where
     H: Heuristic + ?Sized,
     T: DataTrait + Default + Debug,
+    O: LeafObjective,
 {
-    constraints: Constraints,
-    heuristic: &'heur mut H,
-    custom_function: Option<PyObject>,
-    cache: Trie<T>,
+    pub(crate) constraints: Constraints,
+    pub(crate) heuristic: &'heur H,
+    pub objective: O,
+    pub(crate) cache: CacheBackend<T>,
     stop_conditions: StopConditions<T>,
     pub statistics: Statistics,
     pub tree: Tree<NodeData>,
-    run_time: Instant,
+    pub depth_trees: Vec<Tree<NodeData>>, // Tree of depth `d` at `d - 1` when all depths are searched
+    pub top_trees: Vec<Tree<NodeData>>,   // Best trees in increasing objective when enumerated
+    incumbent_callback: Option<IncumbentCallback>,
+    pub(crate) progress_callback: Option<(Duration, ProgressCallback)>, // Called at most once per interval
+    next_progress: Duration,
+    pub(crate) interruption: Option<SearchStatus>, // Why the search must end early, if it must
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) unsettled: Vec<Index>, // Entries left with partial results by the interruption
+    pinned: Vec<Index>, // Cache entries whose solutions the search in progress still needs
+    covers: HashMap<(u64, u64, Depth, Depth), Index>, // First entry of each cover, remaining depth and depth
+    twins: HashMap<Index, Index>, // Entries solved by copy, to the entry their solution comes from
+    pub(crate) warm_start: Option<CacheSnapshot<T>>,
+    pub(crate) depth_keyed: bool, // Entries keyed by their remaining depth, for all depths or a warm start
+    pub(crate) initial_tree: Option<Tree<NodeData>>,
+    initial_solution: Option<(f64, Depth, Tree<NodeData>)>, // Error, depth and leaves of the initial tree
+    pub(crate) root_share: Vec<Attribute>, // Root candidates of a worker of the parallel search, all when empty
+    pub(crate) shared_bound: Option<Arc<AtomicU64>>, // Bits of the best root error of all the workers
+    pub(crate) shared_cache: Option<Arc<SharedCache>>, // Solutions settled by all the workers
+    pub(crate) run_time: Instant,
 }
 
-impl<'heur, H, T> DL85<'heur, H, T>
+/// Called with each improvement of the root solution, the search stops when it returns false.
+pub type IncumbentCallback = Box<dyn FnMut(&Incumbent) -> bool>;
+
+/// Called along the search with the elapsed time, the root upper bound and the cache size.
+pub type ProgressCallback = Box<dyn FnMut(&Progress)>;
+
+impl<'heur, H, T, O> DL85<'heur, H, T, O>
 where
     H: Heuristic + ?Sized,
     T: DataTrait + Default + Debug,
+    O: LeafObjective,
 {
     pub fn new(
         min_sup: Support,
         max_depth: Depth,
-        max_error: usize,
+        max_error: f64,
         max_time: usize,
         specialization: Specialization,
         lower_bound: LowerBoundHeuristic,
@@ -53,8 +92,7 @@ where
         cache_init_size: usize,
         one_time_sort: bool,
         heuristic: &'heur mut H,
-        custom_function: Option<PyObject>, // This will be used for fast computation of the error using support classes
-        python_function_data: Option<PythonFunctionData>,
+        objective: O,
     ) -> Self {
         let constraints = Constraints {
             max_depth,
@@ -67,56 +105,427 @@ where
             branching,
             cache_init,
             cache_init_size,
+            cache_type: CacheType::Trie,
             discrepancy_budget: 0,
             discrepancy_strategy: DiscrepancyStrategy::None,
-            python_function_data,
+            python_function_data: None,
+            cost_matrix: None,
+            leaf_penalty: 0.,
+            max_leaves: <usize>::MAX,
+            all_depths: false,
+            anytime: false,
+            memory_limit: <usize>::MAX,
+            cover_cache: false,
+            num_threads: 1,
+            top_trees: 0,
+            rashomon_bound: <f64>::MAX,
         };
+        Self::with_constraints(constraints, heuristic, objective)
+    }
+
+    pub(crate) fn with_constraints(
+        constraints: Constraints,
+        heuristic: &'heur H,
+        objective: O,
+    ) -> Self {
         Self {
-            constraints,
+            constraints: constraints.clone(),
             heuristic,
-            custom_function,
-            cache: Trie::default(),
+            objective,
+            cache: CacheBackend::default(),
             stop_conditions: StopConditions::default(),
             statistics: Statistics {
                 num_attributes: 0,
-                num_samples: 0,
-                train_distribution: [0, 0],
+                num_samples: 0.,
+                train_distribution: vec![],
                 constraints,
                 cache_size: 0,
-                tree_error: 0,
+                tree_error: 0.,
+                depth_errors: vec![],
+                top_errors: vec![],
+                incumbents: vec![],
+                status: SearchStatus::Optimal,
+                lower_bound: 0.,
+                gap: 0.,
+                counters: SearchCounters::default(),
                 duration: Duration::default(),
             },
             tree: Tree::default(),
+            depth_trees: vec![],
+            top_trees: vec![],
+            incumbent_callback: None,
+            progress_callback: None,
+            next_progress: Duration::ZERO,
+            interruption: None,
+            cancellation: None,
+            unsettled: vec![],
+            pinned: vec![],
+            covers: HashMap::new(),
+            twins: HashMap::new(),
+            warm_start: None,
+            depth_keyed: false,
+            initial_tree: None,
+            initial_solution: None,
+            root_share: vec![],
+            shared_bound: None,
+            shared_cache: None,
             run_time: Instant::now(),
         }
     }
 
-    pub fn fit(&mut self, structure: &mut RSparseBitsetStructure) {
+    /// Misclassifying a transaction of class `k` as `c` costs `costs[k][c]`, the leaves predict
+    /// the class of minimum expected cost.
+    pub fn set_cost_matrix(&mut self, costs: CostMatrix) -> PyTreesResult<()> {
+        if costs.iter().flatten().any(|cost| *cost < 0.) {
+            return Err(PyTreesError::InvalidParameter(
+                "Misclassification costs must be non-negative".to_string(),
+            ));
+        }
+        self.constraints.cost_matrix = Some(costs);
+        self.statistics.constraints = self.constraints.clone();
+        Ok(())
+    }
+
+    /// Minimise the error plus `penalty` times the number of leaves. The errors of the generated
+    /// tree exclude the penalty, `Statistics::tree_error` is the penalised objective.
+    pub fn set_leaf_penalty(&mut self, penalty: f64) -> PyTreesResult<()> {
+        if penalty < 0. {
+            return Err(PyTreesError::InvalidParameter(
+                "The leaf penalty must be non-negative".to_string(),
+            ));
+        }
+        self.constraints.leaf_penalty = penalty;
+        self.statistics.constraints = self.constraints.clone();
+        Ok(())
+    }
+
+    /// Only keep trees with at most `leaves` leaves, in addition to the depth limit.
+    pub fn set_max_leaves(&mut self, leaves: usize) -> PyTreesResult<()> {
+        if leaves == 0 {
+            return Err(PyTreesError::InvalidParameter(
+                "A tree has at least one leaf".to_string(),
+            ));
+        }
+        self.constraints.max_leaves = leaves;
+        self.statistics.constraints = self.constraints.clone();
+        Ok(())
+    }
+
+    /// Only keep trees with at most `nodes` nodes, a binary tree with `n` leaves has `2n - 1` nodes.
+    pub fn set_max_nodes(&mut self, nodes: usize) -> PyTreesResult<()> {
+        self.set_max_leaves(nodes.saturating_add(1) / 2)
+    }
+
+    /// Search the optimal trees of every depth up to the maximum depth in one run, they end up in
+    /// `depth_trees` and their errors in `Statistics::depth_errors`. The cache is keyed by the
+    /// remaining depth so the depths share it, and the tree of a depth bounds the next one.
+    pub fn set_all_depths(&mut self, all_depths: bool) {
+        self.constraints.all_depths = all_depths;
+        self.statistics.constraints = self.constraints.clone();
+    }
+
+    /// Record each improvement of the root solution with its time and tree in
+    /// `Statistics::incumbents`.
+    pub fn set_anytime(&mut self, anytime: bool) {
+        self.constraints.anytime = anytime;
+        self.statistics.constraints = self.constraints.clone();
+    }
+
+    /// Store the cache entries in a trie, the default, or in a hash map keyed by the itemset.
+    pub fn set_cache_type(&mut self, cache_type: CacheType) {
+        self.constraints.cache_type = cache_type;
+        self.statistics.constraints = self.constraints.clone();
+    }
+
+    /// Share the solutions and lower bounds between the itemsets selecting the same transactions.
+    /// The entries stay keyed by itemset, a new entry reuses what is known of the first entry with
+    /// the same cover hash and remaining depth. Leaves budgets are not shared.
+    pub fn set_cover_cache(&mut self, cover_cache: bool) {
+        self.constraints.cover_cache = cover_cache;
+        self.statistics.constraints = self.constraints.clone();
+    }
+
+    /// Number of workers of `fit_parallel`, each explores a share of the root candidates with its
+    /// own cache. The workers start each candidate from the best root error found so far.
+    pub fn set_num_threads(&mut self, num_threads: usize) {
+        self.constraints.num_threads = num_threads.max(1);
+        self.statistics.constraints = self.constraints.clone();
+    }
+
+    /// Enumerate the `count` best trees once the search ends, they end up in `top_trees` and their
+    /// objectives in `Statistics::top_errors`. The first one is optimal but may differ from `tree`
+    /// when several trees tie, the trees differ by at least one split.
+    pub fn set_top_trees(&mut self, count: usize) -> PyTreesResult<()> {
+        if count == 0 {
+            return Err(PyTreesError::InvalidParameter(
+                "At least one tree must be enumerated".to_string(),
+            ));
+        }
+        self.constraints.top_trees = count;
+        self.statistics.constraints = self.constraints.clone();
+        Ok(())
+    }
+
+    /// Enumerate the trees whose objective is at most `epsilon` above the one of `tree`, the
+    /// Rashomon set, and no more than the count of `set_top_trees` when it is set.
+    pub fn set_rashomon_bound(&mut self, epsilon: f64) -> PyTreesResult<()> {
+        if epsilon < 0. {
+            return Err(PyTreesError::InvalidParameter(
+                "The Rashomon bound must be non-negative".to_string(),
+            ));
+        }
+        self.constraints.rashomon_bound = epsilon;
+        if self.constraints.top_trees == 0 {
+            self.constraints.top_trees = <usize>::MAX;
+        }
+        self.statistics.constraints = self.constraints.clone();
+        Ok(())
+    }
+
+    /// Bound the cache to `size` bytes. Once full, the entries out of the solutions in progress are
+    /// evicted and searched again when needed. The search ends with `SearchStatus::MemoryLimit`
+    /// when an eviction leaves less than half of the cache free.
+    pub fn set_memory_limit(&mut self, size: usize) -> PyTreesResult<()> {
+        if size == 0 {
+            return Err(PyTreesError::InvalidParameter(
+                "The memory limit must be positive".to_string(),
+            ));
+        }
+        self.constraints.memory_limit = size;
+        self.statistics.constraints = self.constraints.clone();
+        Ok(())
+    }
+
+    /// Start the next `fit` from the entries of a previous search on the same dataset, with the
+    /// same minimum support and leaf errors. `fit` fails when they do not match, or when none of
+    /// the entries fits the depths of the search, those of a deeper search for instance. The
+    /// solutions of a shallower search bound the deeper subtrees of the same itemsets.
+    pub fn set_warm_start(&mut self, snapshot: CacheSnapshot<T>) -> PyTreesResult<()> {
+        if self.constraints.cover_cache {
+            return Err(PyTreesError::Unsupported(
+                "The cover cache does not support warm starts".to_string(),
+            ));
+        }
+        self.warm_start = Some(snapshot);
+        Ok(())
+    }
+
+    /// Starts the search from `tree`, from `LGDT::fit` for instance. Its error on the data is the
+    /// first upper bound of the root, and it is kept when the search finds no better tree. `fit`
+    /// fails when the tree breaks the depth, support or leaves constraints.
+    pub fn set_initial_tree(&mut self, tree: Tree<NodeData>) {
+        self.initial_tree = Some(tree);
+    }
+
+    /// Stops the search once `token` is cancelled, like a time limit, with the best tree found so
+    /// far and `SearchStatus::Cancelled`.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = Some(token);
+    }
+
+    /// Turns the anytime mode on and calls `callback` with each new incumbent. Returning false
+    /// stops the search, the best tree found so far is kept.
+    pub fn set_incumbent_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Incumbent) -> bool + 'static,
+    {
+        self.set_anytime(true);
+        self.incumbent_callback = Some(Box::new(callback));
+    }
+
+    /// Calls `callback` along the search, at most once per `interval`. `fit_parallel` rejects it
+    /// with more than one thread.
+    pub fn set_progress_callback<F>(&mut self, interval: Duration, callback: F)
+    where
+        F: FnMut(&Progress) + 'static,
+    {
+        self.progress_callback = Some((interval, Box::new(callback)));
+    }
+
+    /// Fails before searching when the options do not apply to the data or to the leaf error, or
+    /// when the cache cannot be allocated. The specialization and the similarity bound are turned
+    /// off for the leaf penalty, regression and the objectives other than the misclassification
+    /// error for this run only, `statistics.constraints` reports the options the search used.
+    pub fn fit<S>(&mut self, structure: &mut S) -> PyTreesResult<()>
+    where
+        S: Structure,
+    {
+        let requested = (
+            self.constraints.specialization,
+            self.constraints.lower_bound,
+        );
+        let result = self.fit_with_effective_options(structure);
+        (
+            self.constraints.specialization,
+            self.constraints.lower_bound,
+        ) = requested;
+        result
+    }
+
+    fn fit_with_effective_options<S>(&mut self, structure: &mut S) -> PyTreesResult<()>
+    where
+        S: Structure,
+    {
         // BEGIN STEP: Setup everything in the statist Update Statistics structures
         self.statistics.num_attributes = structure.num_attributes();
         let distribution = structure.labels_support();
         println!("Distribution: {distribution:?}");
-        self.statistics.train_distribution = [distribution[0], distribution[1]];
+        self.statistics.train_distribution = distribution.to_vec();
         self.statistics.num_samples = structure.support();
 
         // END STEP : Setup everything in the statistics structures
 
+        // BEGIN STEP: Check the options available for the leaf error
+        self.objective.check_labels(structure.num_labels())?;
+        if let Some(costs) = &self.constraints.cost_matrix {
+            let num_labels = structure.num_labels();
+            if costs.len() != num_labels || costs.iter().any(|row| row.len() != num_labels) {
+                return Err(PyTreesError::InvalidParameter(
+                    "The cost matrix must have one row and one column per class".to_string(),
+                ));
+            }
+            if structure.regression_statistics().is_some() || !self.objective.is_misclassification()
+            {
+                return Err(PyTreesError::Unsupported(
+                    "The cost matrix only applies to the misclassification error".to_string(),
+                ));
+            }
+        }
+        // The specialization and the similarity bound only hold for the misclassification error:
+        // they are turned off for the other objectives instead of failing on the defaults
+        if self.constraints.leaf_penalty > 0.
+            || structure.regression_statistics().is_some()
+            || !self.objective.is_misclassification()
+        {
+            self.constraints.specialization = Specialization::None;
+        }
+        if structure.regression_statistics().is_some() || !self.objective.is_misclassification() {
+            self.constraints.lower_bound = LowerBoundHeuristic::None;
+        }
+        self.statistics.constraints = self.constraints.clone();
+        // END STEP: Check the options available for the leaf error
+
+        // BEGIN STEP: Check the options available for the enumeration of the best trees
+        if self.constraints.top_trees > 0 {
+            if self.constraints.max_leaves < <usize>::MAX {
+                return Err(PyTreesError::Unsupported(
+                    "The top trees do not support the maximum number of leaves".to_string(),
+                ));
+            }
+            if self.constraints.all_depths {
+                return Err(PyTreesError::Unsupported(
+                    "The top trees do not support searching all depths".to_string(),
+                ));
+            }
+        }
+        // END STEP: Check the options available for the enumeration of the best trees
+
         // BEGIN STEP: Setup the cache
 
-        self.cache = match self.constraints.cache_init {
-            CacheInit::Normal => Trie::default(),
-            CacheInit::WithMemoryDynamic => {
-                Trie::with_capacity(structure.num_attributes(), self.constraints.max_depth)
+        let (features, depth) = (structure.num_attributes(), self.constraints.max_depth);
+        self.cache = match self.constraints.cache_type {
+            CacheType::Trie => CacheBackend::Trie(match self.constraints.cache_init {
+                CacheInit::Normal => Trie::default(),
+                CacheInit::WithMemoryDynamic => Trie::with_capacity(features, depth),
+                CacheInit::WithMemoryFromUser => {
+                    Trie::from_user_memory(self.constraints.cache_init_size)?
+                }
+            }),
+            CacheType::Hash => CacheBackend::Hash(match self.constraints.cache_init {
+                CacheInit::Normal => HashCache::default(),
+                CacheInit::WithMemoryDynamic => HashCache::with_capacity(features, depth),
+                CacheInit::WithMemoryFromUser => {
+                    HashCache::from_user_memory(self.constraints.cache_init_size)?
+                }
+            }),
+        };
+        if self.constraints.memory_limit < <usize>::MAX {
+            self.cache.set_memory_limit(self.constraints.memory_limit);
+        }
+        // END STEP: Setup the cache
+
+        // BEGIN STEP: Load and sort candidates
+        let candidates = self.root_candidates(structure);
+        // END STEP: Load and sort candidates
+
+        // BEGIN STEP: Evaluate the initial tree with the leaves of the search
+        self.initial_solution = None;
+        if let Some(source) = &self.initial_tree {
+            let mut tree = Tree::new();
+            let (error, depth, leaves) =
+                self.evaluate_tree(structure, source, source.get_root_index(), &mut tree, None)?;
+            if depth > self.constraints.max_depth || leaves > self.constraints.max_leaves {
+                return Err(Self::invalid_initial_tree());
             }
-            CacheInit::WithMemoryFromUser => {
-                Trie::from_user_memory(self.constraints.cache_init_size)
+            if self.constraints.leaf_penalty > 0. {
+                let root = tree.get_root_index();
+                Self::remove_leaf_penalty(&mut tree, root, self.constraints.leaf_penalty);
             }
+            self.initial_solution = Some((error, depth, tree));
+        }
+        // END STEP: Evaluate the initial tree with the leaves of the search
+
+        // BEGIN STEP: Setup the root
+        self.cache.add_root(TrieNode::new(T::new()));
+        // END STEP: Setup the root
+
+        // BEGIN STEP: Load the entries of the warm start
+        self.depth_keyed = self.constraints.all_depths;
+        if let Some(snapshot) = self.warm_start.take() {
+            self.load_snapshot(snapshot, structure)?;
+        }
+        // END STEP: Load the entries of the warm start
+
+        // BEGIN STEP: Run the algorithm for the depths to search
+        self.run_time = Instant::now();
+        self.next_progress = Duration::ZERO;
+        self.interruption = None;
+        self.unsettled = vec![];
+        self.pinned = vec![];
+        self.covers = HashMap::new();
+        self.twins = HashMap::new();
+        self.depth_trees = vec![];
+        self.statistics.depth_errors = vec![];
+        self.statistics.incumbents = vec![];
+        self.statistics.counters = SearchCounters::default();
+        let max_depth = self.constraints.max_depth;
+        let first_depth = match self.constraints.all_depths {
+            true => max_depth.min(1),
+            false => max_depth,
         };
-        // END STEP: Setup the cache
+        for depth in first_depth..=max_depth {
+            self.constraints.max_depth = depth;
+            let error = self.search(structure, &candidates);
+            if self.constraints.all_depths {
+                self.statistics.depth_errors.push(error);
+                self.depth_trees.push(self.tree.clone());
+            }
+            if self.interruption.is_some() {
+                break;
+            }
+        }
+        self.constraints.max_depth = max_depth;
+        // END STEP: Run the algorithm for the depths to search
+
+        // BEGIN STEP: Enumerate the best trees
+        self.top_trees = vec![];
+        self.statistics.top_errors = vec![];
+        if self.constraints.top_trees > 0 {
+            self.enumerate_top_trees(structure, &candidates);
+        }
+        // END STEP: Enumerate the best trees
+
+        self.update_statistics();
+        Ok(())
+    }
 
-        // BEGIN STEP: Load candidates
+    // Attributes splitting the root with enough support on both sides, sorted by the heuristic
+    pub(crate) fn root_candidates<S>(&self, structure: &mut S) -> Vec<Attribute>
+    where
+        S: Structure,
+    {
         let mut candidates = Vec::new();
-        if self.constraints.min_sup == 1 {
+        if self.constraints.min_sup == 1. {
             candidates = (0..structure.num_attributes()).collect();
         } else {
             for i in 0..structure.num_attributes() {
@@ -127,86 +536,206 @@ where
                 }
             }
         }
-        // END STEP: Load candidates
-
-        // BEGIN STEP: Sort candidates
         self.heuristic.compute(structure, &mut candidates);
-        // END STEP: Sort candidates
+        candidates
+    }
+
+    // Optimal tree for the current maximum depth, returns its error
+    fn search<S>(&mut self, structure: &mut S, candidates: &[usize]) -> f64
+    where
+        S: Structure,
+    {
+        let mut itemset = BTreeSet::new();
 
         // BEGIN STEP: Setup the root
-        let mut root_data = T::new();
-        let root_leaf_error = self.leaf_error(structure);
-        root_data.set_node_error(root_leaf_error.0);
-        root_data.set_leaf_error(root_leaf_error.0);
-        let root = TrieNode::new(root_data);
-        self.cache.add_root(root);
-        let root_index = self.cache.get_root_index();
+        let (_, root_index) = self.find_or_create_entry(&itemset, None);
+        self.init_data(structure, root_index);
+        if let Some(root) = self.cache.get_node_mut(root_index) {
+            root.value.set_node_error(root.value.get_leaf_error());
+        }
+        if let Some(root) = self.cache.get_node(root_index) {
+            self.record_incumbent(root.value.get_leaf_error(), true);
+        }
         // END STEP: Setup the root
 
-        let mut similarity_data = SimilarDatasets::new();
-        let mut itemset = BTreeSet::new();
+        // BEGIN STEP: The initial tree bounds the search when it fits the depth
+        let initial = match &self.initial_solution {
+            Some((error, depth, tree)) if *depth <= self.constraints.max_depth => {
+                Some((*error, tree.clone()))
+            }
+            _ => None,
+        };
+        let mut upper_bound = self.constraints.max_error;
+        if let Some((initial_error, tree)) = &initial {
+            upper_bound = upper_bound.min(*initial_error);
+            if self.improves_incumbent(*initial_error) {
+                self.push_incumbent(*initial_error, tree.clone());
+            }
+        }
+        // END STEP: The initial tree bounds the search when it fits the depth
 
         // BEGIN STEP: Run the algorithm
-        self.run_time = Instant::now();
-        self.recursion(
-            structure,
-            0,
-            self.constraints.max_error,
-            (Attribute::MAX, 0),
-            &mut itemset,
-            &candidates,
-            root_index,
-            true,
-            &mut similarity_data,
-        );
+        let leaves_budget = self.leaves_budget(0, self.constraints.max_leaves);
+        let mut error = <f64>::MAX;
+        match leaves_budget {
+            Some(leaves) => {
+                error = self.budget_recursion(
+                    structure,
+                    upper_bound,
+                    Attribute::MAX,
+                    &mut itemset,
+                    candidates,
+                    leaves,
+                );
+            }
+            None => {
+                let return_infos = self.recursion(
+                    structure,
+                    0,
+                    upper_bound,
+                    (Attribute::MAX, 0),
+                    &mut itemset,
+                    candidates,
+                    root_index,
+                    true,
+                    &mut SimilarDatasets::new(),
+                );
+                self.count_return(return_infos.1);
+                if let Some(root) = self.cache.get_node(root_index) {
+                    error = root.value.get_node_error();
+                }
+            }
+        }
         // END STEP: Run the algorithm
 
-        // BEGIN STEP: Update the statistics
-        self.update_statistics();
-        if let Some(root) = self.cache.get_node(self.cache.get_root_index()) {
-            if root.value.get_node_error() < <usize>::MAX {
-                self.generate_tree();
+        // BEGIN STEP: Generate the tree, the initial one when nothing better was found
+        self.tree = Tree::default();
+        match initial {
+            Some((initial_error, tree)) if initial_error < error => {
+                error = initial_error;
+                self.tree = tree;
             }
+            _ if error < <f64>::MAX => {
+                // The specialized algorithm solves small roots without going through the recursion
+                self.record_incumbent(error, false);
+                self.tree = self.build_tree(false);
+            }
+            _ => {}
+        }
+        self.statistics.tree_error = error;
+        self.update_status(error, root_index);
+        // END STEP: Generate the tree, the initial one when nothing better was found
+        error
+    }
+
+    // Copies the subtree of `source` at `index` under `parent` of `tree`, with the leaves and
+    // errors the search would give them. Returns the error, the depth and the number of leaves.
+    fn evaluate_tree<S>(
+        &self,
+        structure: &mut S,
+        source: &Tree<NodeData>,
+        index: Index,
+        tree: &mut Tree<NodeData>,
+        parent: Option<(Index, bool)>,
+    ) -> PyTreesResult<(f64, Depth, usize)>
+    where
+        S: Structure,
+    {
+        let split = match source.get_node(index) {
+            Some(node) if node.left != node.right => node.value.test.map(|test| (test, node)),
+            Some(_) => None,
+            None => return Err(Self::invalid_initial_tree()),
+        };
+        if parent.is_some() && structure.support() < self.constraints.min_sup {
+            return Err(Self::invalid_initial_tree());
+        }
+        let (leaf_error, class) = self.leaf_error(structure);
+        let prediction = structure.regression_statistics().map(Self::mean_value);
+        let node_data = match split {
+            Some((test, _)) => self.create_node_data(test, leaf_error, class, None, false),
+            None => self.create_node_data(Attribute::MAX, leaf_error, class, prediction, true),
+        };
+        let node_index = match parent {
+            Some((parent, is_left)) => tree.add_node(parent, is_left, TreeNode::new(node_data)),
+            None => tree.add_root(TreeNode::new(node_data)),
+        };
+        let (test, node) = match split {
+            Some(split) => split,
+            None => return Ok((leaf_error, 0, 1)),
+        };
+
+        let (mut error, mut depth, mut leaves) = (0., 0, 0);
+        for (branch, child) in [node.left, node.right].into_iter().enumerate() {
+            let _ = structure.push((test, branch));
+            let child = self.evaluate_tree(
+                structure,
+                source,
+                child,
+                tree,
+                Some((node_index, branch == 0)),
+            );
+            structure.backtrack();
+            let child = child?;
+            error += child.0;
+            depth = depth.max(child.1 + 1);
+            leaves += child.2;
         }
-        // END STEP: Update the statistics
+        if let Some(node) = tree.get_node_mut(node_index) {
+            node.value.error = error;
+        }
+        Ok((error, depth, leaves))
+    }
+
+    fn invalid_initial_tree() -> PyTreesError {
+        PyTreesError::InvalidParameter(
+            "The initial tree does not satisfy the constraints of the search".to_string(),
+        )
     }
 
-    fn recursion(
+    fn recursion<S>(
         &mut self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         depth: Depth,
-        upper_bound: usize,
+        upper_bound: f64,
         parent_item: Item,
         itemset: &mut BTreeSet<Item>,
         candidates: &[usize],
         parent_index: Index,
         parent_is_new: bool,
         similarity_data: &mut SimilarDatasets<T>,
-    ) -> (usize, ReturnCondition, HasIntersected) {
+    ) -> (f64, ReturnCondition, HasIntersected)
+    where
+        S: Structure,
+    {
         // TODO: Check if there is not enough time left (Maybe this can be done outside of the recursion)
 
         let mut child_upper_bound = upper_bound;
         let current_support = structure.support();
 
         // BEGIN STEP: Check if we should stop
+        self.check_cancellation();
+        self.report_progress();
+        let max_time = self.max_time();
         if let Some(node) = self.cache.get_node_mut(parent_index) {
             let return_condition = self.stop_conditions.check(
                 node,
-                current_support,
-                self.constraints.min_sup,
-                depth,
-                self.constraints.max_depth,
-                self.run_time.elapsed(),
-                self.constraints.max_time,
-                child_upper_bound,
+                &NodeState {
+                    support: current_support,
+                    depth,
+                    upper_bound: child_upper_bound,
+                    elapsed: self.run_time.elapsed(),
+                    max_time,
+                },
+                &self.constraints,
             );
 
             if return_condition.0 {
-                return (
-                    node.value.get_node_error(),
-                    return_condition.1,
-                    HasIntersected::No,
-                );
+                let error = node.value.get_node_error();
+                if let ReturnCondition::TimeLimitReached = return_condition.1 {
+                    self.interrupt(SearchStatus::TimeLimit);
+                    self.unsettled.push(parent_index);
+                }
+                return (error, return_condition.1, HasIntersected::No);
             }
         }
         // END STEP: Check if we should stop
@@ -215,20 +744,32 @@ where
             let _ = structure.push(parent_item);
         }
 
+        // BEGIN STEP: With the cover cache, reuse what is known of an itemset with the same cover
+        if self.constraints.cover_cache {
+            if let Some(return_infos) =
+                self.reuse_cover(structure, depth, parent_index, child_upper_bound)
+            {
+                return return_infos;
+            }
+        }
+        // END STEP: With the cover cache, reuse what is known of an itemset with the same cover
+
         // TODO : Add the option to use it only when similarity branching is used
         // BEGIN STEP: Check if we should use the similarity lower bound to stop
         if let LowerBoundHeuristic::Similarity = self.constraints.lower_bound {
+            let max_cost = self.max_transaction_cost();
             if let Some(node) = self.cache.get_node_mut(parent_index) {
-                let lower_bound = max(
-                    node.value.get_lower_bound(),
-                    similarity_data.compute_similarity(structure),
-                );
+                let lower_bound = node
+                    .value
+                    .get_lower_bound()
+                    .max(similarity_data.compute_similarity(structure, max_cost));
                 node.value.set_lower_bound(lower_bound);
 
                 let return_condition = self
                     .stop_conditions
                     .stop_from_lower_bound(node, child_upper_bound);
                 if return_condition.0 {
+                    self.statistics.counters.similarity_prunings += 1;
                     return (
                         node.value.get_node_error(),
                         return_condition.1,
@@ -256,6 +797,28 @@ where
         }
         // END STEP: Check if we should use the murtree specialization and if yes use it
 
+        // BEGIN STEP: With a leaf penalty, a split must do better than the node as a leaf
+        if self.constraints.leaf_penalty > 0. {
+            if let Some(node) = self.cache.get_node(parent_index) {
+                child_upper_bound = child_upper_bound.min(node.value.get_leaf_error());
+            }
+        }
+        // END STEP: With a leaf penalty, a split must do better than the node as a leaf
+
+        // BEGIN STEP: When entries are kept for several depths, the deepest shallower tree is an upper bound
+        if self.depth_keyed && self.constraints.max_depth > depth {
+            if let Some(index) = self.shallower_entry(itemset, depth) {
+                if let Some(node) = self.cache.get_node(index) {
+                    let error = node.value.get_node_error();
+                    // The same tree must still be found, so the bound is the next float
+                    if error < <f64>::MAX {
+                        child_upper_bound = child_upper_bound.min(error.next_up());
+                    }
+                }
+            }
+        }
+        // END STEP: When entries are kept for several depths, the deepest shallower tree is an upper bound
+
         // BEGIN STEP: Get the node candidates
         let mut node_candidates = vec![];
         node_candidates = self.get_node_candidates(structure, parent_item.0, candidates);
@@ -280,13 +843,27 @@ where
         }
         // END STEP: Sort the candidates according to the heuristic
 
+        self.statistics.counters.nodes_expanded += 1;
+
         // BEGIN STEP: Setup the node similarity data
         let mut child_similarity_data = SimilarDatasets::new();
-        let mut min_lower_bound = <usize>::MAX;
+        let mut min_lower_bound = <f64>::MAX;
         // END STEP: Setup the node similarity data
 
         // BEGIN STEP: Explore the candidates
+        self.pinned.push(parent_index);
         for child in node_candidates.iter() {
+            // BEGIN STEP: A worker of the parallel search keeps to its share of the root
+            if depth == 0 && self.shared_bound.is_some() {
+                if !self.root_share.contains(child) {
+                    continue;
+                }
+                child_upper_bound = child_upper_bound.min(self.shared_upper_bound());
+            }
+            // END STEP: A worker of the parallel search keeps to its share of the root
+
+            self.make_room();
+
             // BEGIN STEP: Choose where to branch first
             let branching_data = self.find_where_to_branch_first(
                 *child,
@@ -305,7 +882,7 @@ where
 
             // TODO: Check if this is the best place to do this
 
-            let (is_new, child_index) = self.cache.find_or_create(itemset.iter());
+            let (is_new, child_index) = self.find_or_create_entry(itemset, None);
             if is_new {
                 let _ = structure.push(item);
                 self.init_data(structure, child_index);
@@ -317,17 +894,25 @@ where
             // END STEP: Setup the first child node
 
             // BEGIN STEP: Explore the first child node
-            let return_infos = self.recursion(
-                structure,
-                depth + 1,
-                child_upper_bound,
-                item,
-                itemset,
-                &node_candidates,
-                child_index,
-                is_new,
-                &mut child_similarity_data,
-            );
+            let return_infos = match self.shared_solution(itemset, is_new) {
+                Some(return_infos) => return_infos,
+                None => {
+                    let return_infos = self.recursion(
+                        structure,
+                        depth + 1,
+                        child_upper_bound,
+                        item,
+                        itemset,
+                        &node_candidates,
+                        child_index,
+                        is_new,
+                        &mut child_similarity_data,
+                    );
+                    self.share_solution(itemset, return_infos.0);
+                    return_infos
+                }
+            };
+            self.count_return(return_infos.1);
             let left_error = return_infos.0;
             // END STEP: Explore the first child node
 
@@ -340,21 +925,19 @@ where
                 &item,
                 &return_infos,
                 child_index,
-                similarity_data,
+                &mut child_similarity_data,
             );
 
             // END STEP: will backtrack if the node is new and update the similarity data if needed
 
             // BEGIN STEP: If the error is too high, we don't need to explore the right part of the node
-            if left_error as f64 >= child_upper_bound as f64 - second.lower_bound as f64 {
-                // TODO: Ugly
+            if left_error >= child_upper_bound - second.lower_bound {
                 if let Some(node) = self.cache.get_node_mut(child_index) {
-                    min_lower_bound = match left_error == <usize>::MAX {
-                        true => min(
-                            min_lower_bound,
-                            node.value.get_lower_bound() + second.lower_bound,
-                        ),
-                        false => min(min_lower_bound, left_error + second.lower_bound),
+                    min_lower_bound = match left_error == <f64>::MAX {
+                        true => {
+                            min_lower_bound.min(node.value.get_lower_bound() + second.lower_bound)
+                        }
+                        false => min_lower_bound.min(left_error + second.lower_bound),
                     }
                 }
 
@@ -363,11 +946,12 @@ where
             // END STEP: If the error is too high, we don't need to explore the right part of the node
 
             // BEGIN STEP: Setup the second child node
+            self.pinned.push(child_index);
             let right_upper_bound = child_upper_bound - left_error;
             let item = (*child, second.branch);
             itemset.insert(item);
 
-            let (is_new, child_index) = self.cache.find_or_create(itemset.iter());
+            let (is_new, child_index) = self.find_or_create_entry(itemset, None);
             if is_new {
                 let _ = structure.push(item);
                 self.init_data(structure, child_index);
@@ -379,17 +963,25 @@ where
             // END STEP: Setup the second child node
 
             // BEGIN STEP: Explore the second child node
-            let return_infos = self.recursion(
-                structure,
-                depth + 1,
-                right_upper_bound,
-                item,
-                itemset,
-                &node_candidates,
-                child_index,
-                is_new,
-                similarity_data,
-            );
+            let return_infos = match self.shared_solution(itemset, is_new) {
+                Some(return_infos) => return_infos,
+                None => {
+                    let return_infos = self.recursion(
+                        structure,
+                        depth + 1,
+                        right_upper_bound,
+                        item,
+                        itemset,
+                        &node_candidates,
+                        child_index,
+                        is_new,
+                        &mut child_similarity_data,
+                    );
+                    self.share_solution(itemset, return_infos.0);
+                    return_infos
+                }
+            };
+            self.count_return(return_infos.1);
             let right_error = return_infos.0;
             // END STEP: Explore the second child node
 
@@ -401,11 +993,12 @@ where
                 &item,
                 &return_infos,
                 child_index,
-                similarity_data,
+                &mut child_similarity_data,
             );
+            self.pinned.pop();
             // END STEP: Will backtrack if the node is new and update the similarity data if needed
 
-            if right_error == <usize>::MAX || left_error == <usize>::MAX {
+            if right_error == <f64>::MAX || left_error == <f64>::MAX {
                 continue;
             }
 
@@ -414,28 +1007,48 @@ where
             if feature_error < child_upper_bound {
                 child_upper_bound = feature_error;
 
+                let mut reached_lower_bound = false;
                 if let Some(parent_node) = self.cache.get_node_mut(parent_index) {
                     parent_node.value.set_node_error(child_upper_bound);
 
                     parent_node.value.set_test(*child);
 
-                    if parent_node.value.get_lower_bound() == child_upper_bound {
-                        break;
-                    }
+                    reached_lower_bound = parent_node.value.get_lower_bound() == child_upper_bound;
+                }
+                if depth == 0 {
+                    self.record_incumbent(child_upper_bound, false);
+                    self.share_upper_bound(child_upper_bound);
+                }
+                if reached_lower_bound {
+                    break;
                 }
             } else {
-                min_lower_bound = min(feature_error, min_lower_bound);
+                min_lower_bound = min_lower_bound.min(feature_error);
             }
             // END STEP: Update the node error if possible based on the upper bound and the branches error
         }
+        self.pinned.pop();
+
+        // BEGIN STEP: With a leaf penalty, keep the node as a leaf when no split pays for itself
+        if self.constraints.leaf_penalty > 0. {
+            if let Some(node) = self.cache.get_node_mut(parent_index) {
+                let leaf_error = node.value.get_leaf_error();
+                if node.value.get_node_error() >= leaf_error && leaf_error < upper_bound {
+                    node.value.set_as_leaf();
+                    return (leaf_error, ReturnCondition::Done, HasIntersected::Yes);
+                }
+            }
+        }
+        // END STEP: With a leaf penalty, keep the node as a leaf when no split pays for itself
 
         // BEGIN STEP: If the node error is still MAX, we need to update the lower bound
         if let Some(node) = self.cache.get_node_mut(parent_index) {
-            if node.value.get_node_error() == <usize>::MAX {
-                node.value.set_lower_bound(max(
-                    node.value.get_lower_bound(),
-                    max(min_lower_bound, upper_bound),
-                ));
+            if node.value.get_node_error() == <f64>::MAX {
+                node.value.set_lower_bound(
+                    node.value
+                        .get_lower_bound()
+                        .max(min_lower_bound.max(upper_bound)),
+                );
                 return (
                     node.value.get_node_error(),
                     ReturnCondition::LowerBoundConstrained,
@@ -455,82 +1068,352 @@ where
         // END STEP: If the node error is still MAX, we need to update the lower bound
     }
 
-    fn use_custom_error(&self) -> bool {
-        self.custom_function.is_some()
-    }
-
-    fn compute_custom_error(&self, classes_support: &[usize]) -> (usize, usize) {
-        let mut error: (usize, usize) = (0, 0);
-        if let Some(ref function) = self.custom_function {
-            Python::with_gil(|py| {
-                let cls_support = classes_support.to_vec();
-                error = function
-                    .call1(py, (cls_support,))
-                    .unwrap()
-                    .extract(py)
-                    .unwrap();
-            });
-        }
-        error
-    }
-
-    fn get_node_candidates(
-        &self,
-        structure: &mut RSparseBitsetStructure,
-        last_candidate: Attribute,
-        candidates: &[Attribute],
-    ) -> Vec<Attribute> {
-        let mut node_candidates = Vec::new();
-        let support = structure.support();
-        for potential_candidate in candidates {
-            if *potential_candidate == last_candidate {
-                continue;
+    // Search under a leaves budget smaller than what the remaining depth allows. The results are
+    // cached on the itemset followed by a budget item, subtrees with enough leaves for their depth
+    // go back to the usual recursion and its specialization. Structure is already at the itemset.
+    fn budget_recursion<S>(
+        &mut self,
+        structure: &mut S,
+        upper_bound: f64,
+        parent_attribute: Attribute,
+        itemset: &mut BTreeSet<Item>,
+        candidates: &[usize],
+        leaves: usize,
+    ) -> f64
+    where
+        S: Structure,
+    {
+        // BEGIN STEP: Find the node matching the budget
+        let depth = itemset.len();
+        let leaves = match self.leaves_budget(depth, leaves) {
+            Some(leaves) => leaves,
+            None => {
+                let (is_new, index) = self.find_or_create_entry(itemset, None);
+                if is_new {
+                    self.init_data(structure, index);
+                } else if let Some(node) = self.cache.get_node(index) {
+                    if node.value.get_node_error() < <f64>::MAX {
+                        return node.value.get_node_error();
+                    }
+                }
+                let return_infos = self.recursion(
+                    structure,
+                    depth,
+                    upper_bound,
+                    (parent_attribute, 0),
+                    itemset,
+                    candidates,
+                    index,
+                    true,
+                    &mut SimilarDatasets::new(),
+                );
+                self.count_return(return_infos.1);
+                return return_infos.0;
             }
-            let left_support = structure.temp_push((*potential_candidate, 0));
-            let right_support = support - left_support;
+        };
 
-            if left_support >= self.constraints.min_sup && right_support >= self.constraints.min_sup
-            {
-                node_candidates.push(*potential_candidate);
+        if leaves == 1 {
+            let (is_new, index) = self.find_or_create_entry(itemset, None);
+            if is_new {
+                self.init_data(structure, index);
             }
+            return match self.cache.get_node(index) {
+                Some(node) => node.value.get_leaf_error(),
+                None => <f64>::MAX,
+            };
         }
-        node_candidates
-    }
 
-    fn init_data(&mut self, structure: &mut RSparseBitsetStructure, index: Index) {
-        let (leaf_error, class) = self.leaf_error(structure);
-        if let Some(node) = self.cache.get_node_mut(index) {
-            node.value.set_leaf_error(leaf_error);
-            node.value.set_class(class)
+        let (is_new, index) = self.find_or_create_entry(itemset, Some((BUDGET_ATTRIBUTE, leaves)));
+        if is_new {
+            self.init_data(structure, index);
         }
-    }
+        // END STEP: Find the node matching the budget
 
-    fn compute_lower_bounds(
-        &self,
-        attribute: Attribute,
-        structure: &mut RSparseBitsetStructure,
-        itemset: &mut BTreeSet<Item>,
-        similarities: &mut SimilarDatasets<T>,
-        option: LowerBoundHeuristic,
-    ) -> (usize, usize) {
-        let mut lower_bounds: [usize; 2] = [0, 0];
-
-        for (i, lower_bound) in lower_bounds.iter_mut().enumerate() {
-            itemset.insert((attribute, i));
-            if let Some(index) = self.cache.find(itemset.iter()) {
-                if let Some(node) = self.cache.get_node(index) {
-                    *lower_bound = match node.value.get_node_error() == <usize>::MAX {
-                        true => node.value.get_lower_bound(),
-                        false => node.value.get_node_error(),
-                    }
+        // BEGIN STEP: Check if we should stop
+        let mut child_upper_bound = upper_bound;
+        self.check_cancellation();
+        self.report_progress();
+        let max_time = self.max_time();
+        if let Some(node) = self.cache.get_node_mut(index) {
+            if node.value.get_node_error() < <f64>::MAX {
+                return node.value.get_node_error();
+            }
+            let return_condition = self.stop_conditions.check(
+                node,
+                &NodeState {
+                    support: structure.support(),
+                    depth,
+                    upper_bound: child_upper_bound,
+                    elapsed: self.run_time.elapsed(),
+                    max_time,
+                },
+                &self.constraints,
+            );
+            if return_condition.0 {
+                let error = node.value.get_node_error();
+                if let ReturnCondition::TimeLimitReached = return_condition.1 {
+                    self.interrupt(SearchStatus::TimeLimit);
+                    self.unsettled.push(index);
                 }
+                return error;
             }
-            itemset.remove(&(attribute, i));
+            if self.constraints.leaf_penalty > 0. {
+                child_upper_bound = child_upper_bound.min(node.value.get_leaf_error());
+            }
+        }
+        // END STEP: Check if we should stop
+
+        // BEGIN STEP: Solve the budgets of the last two levels with the specialized algorithm
+        if self.constraints.max_depth - depth <= 2 {
+            if let Specialization::Murtree = self.constraints.specialization {
+                return self.run_specialized_budget(structure, index, upper_bound, itemset, leaves);
+            }
+        }
+        // END STEP: Solve the budgets of the last two levels with the specialized algorithm
+
+        // BEGIN STEP: Get the node candidates
+        let mut node_candidates = self.get_node_candidates(structure, parent_attribute, candidates);
+        if node_candidates.is_empty() {
+            if let Some(node) = self.cache.get_node_mut(index) {
+                node.value.set_as_leaf();
+                return node.value.get_node_error();
+            }
+        }
+        if !self.constraints.one_time_sort {
+            self.heuristic.compute(structure, &mut node_candidates);
+        }
+        // END STEP: Get the node candidates
+
+        self.statistics.counters.nodes_expanded += 1;
+
+        // BEGIN STEP: Explore the candidates and the ways to share the budget between the children
+        self.pinned.push(index);
+        for child in node_candidates.iter() {
+            for left_leaves in 1..leaves {
+                self.make_room();
+                let item = (*child, 0);
+                itemset.insert(item);
+                let _ = structure.push(item);
+                let left_error = self.budget_recursion(
+                    structure,
+                    child_upper_bound,
+                    *child,
+                    itemset,
+                    &node_candidates,
+                    left_leaves,
+                );
+                if left_error >= child_upper_bound {
+                    structure.backtrack();
+                    itemset.remove(&item);
+                    continue;
+                }
+                let left_index = self.budget_entry(itemset, left_leaves);
+                self.pinned.extend(left_index);
+                structure.backtrack();
+                itemset.remove(&item);
+
+                let item = (*child, 1);
+                itemset.insert(item);
+                let _ = structure.push(item);
+                let right_error = self.budget_recursion(
+                    structure,
+                    child_upper_bound - left_error,
+                    *child,
+                    itemset,
+                    &node_candidates,
+                    leaves - left_leaves,
+                );
+                structure.backtrack();
+                itemset.remove(&item);
+                if left_index.is_some() {
+                    self.pinned.pop();
+                }
+                if right_error == <f64>::MAX {
+                    continue;
+                }
+
+                let feature_error = left_error + right_error;
+                if feature_error < child_upper_bound {
+                    child_upper_bound = feature_error;
+                    if let Some(node) = self.cache.get_node_mut(index) {
+                        node.value.set_node_error(feature_error);
+                        node.value.set_test(*child);
+                        node.value.set_left_leaves(left_leaves);
+                    }
+                    if depth == 0 {
+                        self.record_incumbent(feature_error, false);
+                    }
+                }
+            }
+        }
+        self.pinned.pop();
+        // END STEP: Explore the candidates and the ways to share the budget between the children
+
+        // BEGIN STEP: Keep the node as a leaf or update its lower bound when nothing was found
+        if let Some(node) = self.cache.get_node_mut(index) {
+            let leaf_error = node.value.get_leaf_error();
+            if self.constraints.leaf_penalty > 0.
+                && node.value.get_node_error() >= leaf_error
+                && leaf_error < upper_bound
+            {
+                node.value.set_as_leaf();
+            } else if node.value.get_node_error() == <f64>::MAX {
+                node.value
+                    .set_lower_bound(node.value.get_lower_bound().max(upper_bound));
+            }
+            return node.value.get_node_error();
+        }
+        <f64>::MAX
+        // END STEP: Keep the node as a leaf or update its lower bound when nothing was found
+    }
+
+    // Budget of a node at this depth, None when the depth alone bounds the number of leaves
+    fn leaves_budget(&self, depth: Depth, leaves: usize) -> Option<usize> {
+        let remaining = (self.constraints.max_depth - depth) as u32;
+        let depth_leaves = 1usize.checked_shl(remaining).unwrap_or(<usize>::MAX);
+        match leaves < depth_leaves {
+            true => Some(leaves),
+            false => None,
+        }
+    }
+
+    pub(crate) fn get_node_candidates<S>(
+        &self,
+        structure: &mut S,
+        last_candidate: Attribute,
+        candidates: &[Attribute],
+    ) -> Vec<Attribute>
+    where
+        S: Structure,
+    {
+        let mut node_candidates = Vec::new();
+        let support = structure.support();
+        for potential_candidate in candidates {
+            if *potential_candidate == last_candidate {
+                continue;
+            }
+            let left_support = structure.temp_push((*potential_candidate, 0));
+            let right_support = support - left_support;
+
+            if left_support >= self.constraints.min_sup && right_support >= self.constraints.min_sup
+            {
+                node_candidates.push(*potential_candidate);
+            }
+        }
+        node_candidates
+    }
+
+    fn init_data<S>(&mut self, structure: &mut S, index: Index)
+    where
+        S: Structure,
+    {
+        let (leaf_error, class) = self.leaf_error(structure);
+        let prediction = structure.regression_statistics().map(Self::mean_value);
+        if let Some(node) = self.cache.get_node_mut(index) {
+            node.value.set_leaf_error(leaf_error);
+            node.value.set_class(class);
+            if let Some(prediction) = prediction {
+                node.value.set_prediction(prediction);
+            }
+        }
+    }
+
+    // Copies the solution or the lower bound of the first entry with the same cover and remaining
+    // depth, or registers the entry as the first one. Structure is already at the entry.
+    fn reuse_cover<S>(
+        &mut self,
+        structure: &S,
+        depth: Depth,
+        index: Index,
+        upper_bound: f64,
+    ) -> Option<(f64, ReturnCondition, HasIntersected)>
+    where
+        S: Structure,
+    {
+        match self.cache.get_node(index) {
+            Some(node) if node.value.get_node_error() == <f64>::MAX => {}
+            _ => return None,
+        }
+        let (first, second) = structure.cover_hash();
+        // The trees of the copies are read from their source at their own depth, searching all
+        // depths the sources of the previous depths are not at the same depth
+        let remaining_depth = self.constraints.max_depth - depth;
+        let source = *self
+            .covers
+            .entry((first, second, remaining_depth, depth))
+            .or_insert(index);
+        if source == index {
+            return None;
+        }
+
+        // The entries on the way of the search have different depths, so the source is done
+        let (error, test, lower_bound, is_leaf) = match self.cache.get_node(source) {
+            Some(node) => (
+                node.value.get_node_error(),
+                node.value.get_test(),
+                node.value.get_lower_bound(),
+                node.value.is_leaf(),
+            ),
+            None => return None,
+        };
+        let node = self.cache.get_node_mut(index)?;
+        node.value
+            .set_lower_bound(node.value.get_lower_bound().max(lower_bound));
+        if error < <f64>::MAX {
+            node.value.set_node_error(error);
+            node.value.set_test(test);
+            if is_leaf {
+                node.value.to_leaf();
+            }
+            self.twins.insert(index, source);
+            self.statistics.counters.cover_reuses += 1;
+            return Some((error, ReturnCondition::FromSameCover, HasIntersected::Yes));
+        }
+        let return_condition = self
+            .stop_conditions
+            .stop_from_lower_bound(node, upper_bound);
+        match return_condition.0 {
+            true => Some((
+                node.value.get_node_error(),
+                return_condition.1,
+                HasIntersected::Yes,
+            )),
+            false => None,
+        }
+    }
+
+    fn compute_lower_bounds<S>(
+        &self,
+        attribute: Attribute,
+        structure: &mut S,
+        itemset: &mut BTreeSet<Item>,
+        similarities: &mut SimilarDatasets<T>,
+        option: LowerBoundHeuristic,
+    ) -> (f64, f64)
+    where
+        S: Structure,
+    {
+        // Each child is at least a leaf
+        let penalty = self.constraints.leaf_penalty;
+        let mut lower_bounds: [f64; 2] = [penalty, penalty];
+
+        for (i, lower_bound) in lower_bounds.iter_mut().enumerate() {
+            itemset.insert((attribute, i));
+            if let Some(index) = self.find_entry(itemset, None) {
+                if let Some(node) = self.cache.get_node(index) {
+                    *lower_bound = match node.value.get_node_error() == <f64>::MAX {
+                        true => node.value.get_lower_bound().max(penalty),
+                        false => node.value.get_node_error(),
+                    }
+                }
+            }
+            itemset.remove(&(attribute, i));
 
             if let LowerBoundHeuristic::Similarity = option {
                 structure.push((attribute, i));
-                let sim_lb = similarities.compute_similarity(structure);
-                *lower_bound = max(*lower_bound, sim_lb);
+                let sim_lb =
+                    similarities.compute_similarity(structure, self.max_transaction_cost());
+                *lower_bound = lower_bound.max(sim_lb);
                 structure.backtrack();
             }
         }
@@ -538,22 +1421,25 @@ where
         (lower_bounds[0], lower_bounds[1])
     }
 
-    fn find_where_to_branch_first(
+    fn find_where_to_branch_first<S>(
         &self,
         child: Attribute,
         itemset: &mut BTreeSet<Item>,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         similarity_dataset: &mut SimilarDatasets<T>,
-    ) -> [Branching; 2] {
-        let mut lower_bounds = [0, 0];
+    ) -> [Branching; 2]
+    where
+        S: Structure,
+    {
+        let mut lower_bounds = [0., 0.];
         // If Dynamic branching is enabled, we check where to move first
         if let BranchingType::Dynamic = self.constraints.branching {
             for (i, lower_bound) in lower_bounds.iter_mut().enumerate() {
                 itemset.insert((child, i));
-                if let Some(index) = self.cache.find(itemset.iter()) {
+                if let Some(index) = self.find_entry(itemset, None) {
                     if let Some(node) = self.cache.get_node(index) {
                         let error = node.value.get_node_error();
-                        *lower_bound = match error < <usize>::MAX {
+                        *lower_bound = match error < <f64>::MAX {
                             true => error,
                             false => node.value.get_lower_bound(),
                         }
@@ -571,8 +1457,8 @@ where
                     self.constraints.lower_bound,
                 );
 
-                lower_bounds[0] = max(lower_bounds[0], similarity_lower_bounds.0);
-                lower_bounds[1] = max(lower_bounds[1], similarity_lower_bounds.1);
+                lower_bounds[0] = lower_bounds[0].max(similarity_lower_bounds.0);
+                lower_bounds[1] = lower_bounds[1].max(similarity_lower_bounds.1);
             }
         }
 
@@ -604,16 +1490,18 @@ where
         [first_data, second_data]
     }
 
-    fn reset_after_branching(
+    fn reset_after_branching<S>(
         &self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         itemset: &mut BTreeSet<Item>,
         is_new: bool,
         item: &Item,
-        return_infos: &(usize, ReturnCondition, HasIntersected),
+        return_infos: &(f64, ReturnCondition, HasIntersected),
         child_index: usize,
         child_similarity_data: &mut SimilarDatasets<T>,
-    ) {
+    ) where
+        S: Structure,
+    {
         let has_intersected = match return_infos.2 {
             HasIntersected::Yes => true,
             HasIntersected::No => false,
@@ -641,13 +1529,16 @@ where
         }
     }
 
-    fn update_similarity_data(
+    fn update_similarity_data<S>(
         &self,
         similarity_dataset: &mut SimilarDatasets<T>,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         child_index: Index,
         condition: ReturnCondition,
-    ) -> bool {
+    ) -> bool
+    where
+        S: Structure,
+    {
         match condition {
             ReturnCondition::LowerBoundConstrained => false,
             _ => {
@@ -660,49 +1551,56 @@ where
         }
     }
 
-    fn leaf_error(&self, structure: &mut RSparseBitsetStructure) -> (usize, usize) {
-        match self.use_custom_error() {
-            true => {
-                let error = match self.constraints.python_function_data {
-                    None => {
-                        panic!("Python function data is None. It should not have come here.")
-                    }
-                    Some(PythonFunctionData::ClassSupports) => {
-                        self.compute_custom_error(structure.labels_support())
-                    }
-                    Some(PythonFunctionData::Tids) => {
-                        self.compute_custom_error(&structure.get_tids())
-                    }
-                };
-                error
-            }
-            false => Self::misclassification_error(structure.labels_support()),
+    pub(crate) fn leaf_error<S>(&self, structure: &mut S) -> (f64, usize)
+    where
+        S: Structure,
+    {
+        let (error, class) = match structure.regression_statistics() {
+            Some(statistics) => (Self::squared_error(statistics), 0),
+            None => match &self.constraints.cost_matrix {
+                Some(costs) => expected_cost(structure.labels_support(), costs),
+                None => self.objective.leaf_error(structure),
+            },
+        };
+        (error + self.constraints.leaf_penalty, class)
+    }
+
+    // Largest error a single transaction can add to a leaf
+    fn max_transaction_cost(&self) -> f64 {
+        match &self.constraints.cost_matrix {
+            Some(costs) => costs.iter().flatten().fold(0., |max, cost| cost.max(max)),
+            None => 1.,
         }
     }
 
-    fn misclassification_error(classes_support: &[usize]) -> (usize, usize) {
-        let mut max_idx = 0;
-        let mut max_value = 0;
-        let mut total = 0;
-        for (idx, value) in classes_support.iter().enumerate() {
-            total += value;
-            if *value >= max_value {
-                max_value = *value;
-                max_idx = idx;
-            }
+    // Sum of squared deviations from the mean, computed from (count, sum, sum of squares)
+    fn squared_error(statistics: RegressionStatistics) -> f64 {
+        let (count, sum, sum_of_squares) = statistics;
+        if count == 0. {
+            return 0.;
         }
-        let error = total - max_value;
-        (error, max_idx)
+        (sum_of_squares - sum * sum / count).max(0.)
     }
 
-    fn run_specialized_algorithm(
+    pub(crate) fn mean_value(statistics: RegressionStatistics) -> f64 {
+        let (count, sum, _) = statistics;
+        if count == 0. {
+            return 0.;
+        }
+        sum / count
+    }
+
+    fn run_specialized_algorithm<S>(
         &mut self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         index: Index,
-        upper_bound: usize,
+        upper_bound: f64,
         itemset: &mut BTreeSet<Item>,
         depth: Depth,
-    ) -> (usize, ReturnCondition, HasIntersected) {
+    ) -> (f64, ReturnCondition, HasIntersected)
+    where
+        S: Structure,
+    {
         if let Some(node) = self.cache.get_node(index) {
             if upper_bound < node.value.get_lower_bound() {
                 return (
@@ -713,9 +1611,22 @@ where
             }
         }
 
-        let mut tree = LGDT::fit(structure, self.constraints.min_sup, depth, MurTree::fit);
+        self.statistics.counters.specialized_calls += 1;
+        let min_sup = self.constraints.min_sup;
+        let tree = match &self.constraints.cost_matrix {
+            Some(costs) => LGDT::fit_with_costs(
+                structure,
+                min_sup,
+                depth,
+                |structure, min_sup, depth| {
+                    MurTree::fit_with_costs(structure, min_sup, depth, costs)
+                },
+                costs,
+            ),
+            None => LGDT::fit(structure, min_sup, depth, MurTree::fit),
+        };
         let error = LGDT::get_tree_error(&tree);
-        self.stitch_to_cache(index, &tree, tree.get_root_index(), itemset);
+        self.stitch_to_cache(structure, index, &tree, tree.get_root_index(), itemset);
         (
             error,
             ReturnCondition::FromSpecializedAlgorithm,
@@ -723,129 +1634,609 @@ where
         )
     }
 
-    fn stitch_to_cache(
+    // Size constrained version of the specialized algorithm, for the entry of the itemset under a
+    // budget of `leaves`. The children are the entries without budget the budget search reads.
+    fn run_specialized_budget<S>(
+        &mut self,
+        structure: &mut S,
+        index: Index,
+        upper_bound: f64,
+        itemset: &mut BTreeSet<Item>,
+        leaves: usize,
+    ) -> f64
+    where
+        S: Structure,
+    {
+        if let Some(node) = self.cache.get_node(index) {
+            if upper_bound < node.value.get_lower_bound() {
+                return node.value.get_node_error();
+            }
+        }
+
+        self.statistics.counters.specialized_calls += 1;
+        let min_sup = self.constraints.min_sup;
+        let costs = self.constraints.cost_matrix.as_ref();
+        let tree = MurTree::fit_with_leaves(structure, min_sup, leaves, costs);
+        let root = match tree.get_node(tree.get_root_index()) {
+            Some(root) => *root,
+            None => return <f64>::MAX,
+        };
+        let test = match root.value.test {
+            Some(test) if root.left != root.right => test,
+            _ => {
+                return match self.cache.get_node_mut(index) {
+                    Some(node) => {
+                        node.value.set_as_leaf();
+                        node.value.get_node_error()
+                    }
+                    None => <f64>::MAX,
+                };
+            }
+        };
+        for (branch, child) in [root.left, root.right].into_iter().enumerate() {
+            let item = (test, branch);
+            itemset.insert(item);
+            let _ = structure.push(item);
+            let (is_new, child_index) = self.find_or_create_entry(itemset, None);
+            if is_new {
+                self.init_data(structure, child_index);
+            }
+            // A single leaf is read from the leaf error of the entry, whatever its solution is
+            if tree
+                .get_node(child)
+                .is_some_and(|child| child.left != child.right)
+            {
+                self.stitch_to_cache(structure, child_index, &tree, child, itemset);
+            }
+            structure.backtrack();
+            itemset.remove(&item);
+        }
+        let left_leaves = match tree.get_node(root.left) {
+            Some(left) if left.left != left.right => 2,
+            _ => 1,
+        };
+        if let Some(node) = self.cache.get_node_mut(index) {
+            node.value.set_node_error(root.value.error);
+            node.value.set_test(test);
+            node.value.set_left_leaves(left_leaves);
+        }
+        root.value.error
+    }
+
+    // Copies the tree of the specialized algorithm into the cache. The entries keep their own leaf
+    // errors, the budget search reads them. Structure is already at the itemset.
+    fn stitch_to_cache<S>(
         &mut self,
+        structure: &mut S,
         cache_index: Index,
         tree: &Tree<NodeData>,
         source_index: usize,
         itemset: &mut BTreeSet<Item>,
-    ) {
+    ) where
+        S: Structure,
+    {
         if let Some(source_root) = tree.get_node(source_index) {
+            // A node without test is a leaf, or the empty subtree of a split without enough support
+            let test = match source_root.value.test {
+                Some(test) if source_root.left != source_root.right => test,
+                _ => {
+                    if let Some(cache_node) = self.cache.get_node_mut(cache_index) {
+                        if let Some(class) = source_root.value.out {
+                            cache_node.value.set_leaf_error(source_root.value.error);
+                            cache_node.value.set_class(class);
+                        }
+                        cache_node.value.set_as_leaf();
+                    }
+                    return;
+                }
+            };
             if let Some(cache_node) = self.cache.get_node_mut(cache_index) {
                 cache_node.value.set_node_error(source_root.value.error);
-                cache_node.value.set_leaf_error(source_root.value.error);
+                cache_node.value.set_test(test);
+            }
 
-                if source_root.left == source_root.right {
-                    // Case when the rode is a leaf
-                    cache_node.value.set_as_leaf();
-                    cache_node
-                        .value
-                        .set_class(source_root.value.out.unwrap_or(<usize>::MAX));
-                } else {
-                    cache_node
-                        .value
-                        .set_test(source_root.value.test.unwrap_or(Attribute::MAX));
+            for (branch, source_child) in [source_root.left, source_root.right]
+                .into_iter()
+                .enumerate()
+            {
+                let item = (test, branch);
+                itemset.insert(item);
+                let _ = structure.push(item);
+                let (is_new, child_index) = self.find_or_create_entry(itemset, None);
+                if is_new {
+                    self.init_data(structure, child_index);
                 }
+                self.stitch_to_cache(structure, child_index, tree, source_child, itemset);
+                structure.backtrack();
+                itemset.remove(&item);
             }
+        }
+    }
 
-            let source_left_index = source_root.left;
-            if source_left_index > 0 {
-                itemset.insert((source_root.value.test.unwrap_or(Attribute::MAX), 0));
-                let (_, left_index) = self.cache.find_or_create(itemset.iter());
-                self.stitch_to_cache(left_index, tree, source_left_index, itemset);
-                itemset.remove(&(source_root.value.test.unwrap_or(Attribute::MAX), 0));
+    pub(crate) fn check_cancellation(&mut self) {
+        if let Some(token) = &self.cancellation {
+            if token.is_cancelled() {
+                self.interrupt(SearchStatus::Cancelled);
             }
+        }
+    }
+
+    fn report_progress(&mut self) {
+        let time = self.run_time.elapsed();
+        let interval = match &self.progress_callback {
+            Some((interval, _)) if time >= self.next_progress => *interval,
+            _ => return,
+        };
+        self.next_progress = time + interval;
+        let progress = Progress {
+            time,
+            upper_bound: self.root_upper_bound(),
+            cache_size: self.cache.len(),
+        };
+        if let Some((_, callback)) = self.progress_callback.as_mut() {
+            callback(&progress);
+        }
+    }
 
-            let source_right_index = source_root.right;
-            if source_right_index > 0 {
-                itemset.insert((source_root.value.test.unwrap_or(Attribute::MAX), 1));
-                let (_, right_index) = self.cache.find_or_create(itemset.iter());
-                self.stitch_to_cache(right_index, tree, source_right_index, itemset);
-                itemset.remove(&(source_root.value.test.unwrap_or(Attribute::MAX), 1));
+    // Best root objective of the current depth, from the root entry or the initial tree
+    fn root_upper_bound(&self) -> f64 {
+        let root = self.budget_entry(&BTreeSet::new(), self.constraints.max_leaves);
+        let error = root
+            .and_then(|index| self.cache.get_node(index))
+            .map_or(<f64>::MAX, |root| root.value.get_node_error());
+        match &self.initial_solution {
+            Some((initial_error, depth, _)) if *depth <= self.constraints.max_depth => {
+                error.min(*initial_error)
             }
+            _ => error,
         }
     }
 
-    fn update_statistics(&mut self) {
-        self.statistics.cache_size = self.cache.len();
-        self.statistics.duration = self.run_time.elapsed();
-        if let Some(node) = self.cache.get_node(self.cache.get_root_index()) {
-            self.statistics.tree_error = node.value.get_node_error();
+    fn max_time(&self) -> usize {
+        match self.interruption {
+            Some(_) => 0,
+            None => self.constraints.max_time,
+        }
+    }
+
+    // The frames in progress end with partial results, as the nodes stopped from now on
+    pub(crate) fn interrupt(&mut self, status: SearchStatus) {
+        if self.interruption.is_none() {
+            self.interruption = Some(status);
+            self.unsettled.extend(self.pinned.iter());
+        }
+    }
+
+    fn record_incumbent(&mut self, error: f64, root_as_leaf: bool) {
+        if self.improves_incumbent(error) {
+            let tree = self.build_tree(root_as_leaf);
+            self.push_incumbent(error, tree);
+        }
+    }
+
+    fn improves_incumbent(&self, error: f64) -> bool {
+        if !self.constraints.anytime {
+            return false;
+        }
+        match self.statistics.incumbents.last() {
+            Some(last) => last.depth != self.constraints.max_depth || last.error > error,
+            None => true,
+        }
+    }
+
+    fn push_incumbent(&mut self, error: f64, tree: Tree<NodeData>) {
+        let incumbent = Incumbent {
+            depth: self.constraints.max_depth,
+            time: self.run_time.elapsed(),
+            error,
+            tree,
+        };
+        if let Some(callback) = self.incumbent_callback.as_mut() {
+            if !callback(&incumbent) {
+                self.interrupt(SearchStatus::Interrupted);
+            }
         }
+        self.statistics.incumbents.push(incumbent);
     }
 
-    fn generate_tree(&mut self) {
+    // Tree of the current root solution, the errors of its leaves exclude the penalty
+    fn build_tree(&self, root_as_leaf: bool) -> Tree<NodeData> {
         let mut tree = Tree::new();
         let mut path = BTreeSet::new();
+        if root_as_leaf {
+            let root_index = self
+                .find_entry(&path, None)
+                .unwrap_or(self.cache.get_root_index());
+            if let Some(root) = self.cache.get_node(root_index) {
+                let node_data = self.create_node_data(
+                    Attribute::MAX,
+                    root.value.get_leaf_error(),
+                    root.value.get_class(),
+                    root.value.get_prediction(),
+                    true,
+                );
+                let _ = tree.add_root(TreeNode::new(node_data));
+            }
+        } else {
+            match self.leaves_budget(0, self.constraints.max_leaves) {
+                Some(leaves) => self.generate_budget_tree(&mut path, &mut tree, None, 0, leaves),
+                None => self.generate_tree(&mut tree),
+            }
+        }
 
-        // Creating root node
-        if let Some(cache_node) = self.cache.get_node(self.cache.get_root_index()) {
-            let node_data = self.create_node_data(
-                cache_node.value.get_test(),
-                cache_node.value.get_node_error(),
-                cache_node.value.get_class(),
-                cache_node.value.is_leaf(),
-            );
-            let _ = tree.add_root(TreeNode::new(node_data));
-
-            // Creating the rest of the tree
+        if self.constraints.leaf_penalty > 0. {
             let root_index = tree.get_root_index();
-            self.generate_tree_rec(
-                cache_node.value.get_test(),
-                &mut path,
-                &mut tree,
-                root_index,
-            );
+            Self::remove_leaf_penalty(&mut tree, root_index, self.constraints.leaf_penalty);
         }
-        self.tree = tree;
+        tree
     }
 
-    fn generate_tree_rec(
-        &self,
-        attribute: Attribute,
-        path: &mut BTreeSet<Item>,
-        tree: &mut Tree<NodeData>,
-        parent_index: usize,
-    ) {
-        if attribute == Attribute::MAX {
+    // The proven root lower bound and the reason the search of the current depth ended
+    fn update_status(&mut self, error: f64, root_index: Index) {
+        let status = match self.interruption {
+            Some(status) => status,
+            None if error >= self.constraints.max_error => SearchStatus::ErrorLimit,
+            None => SearchStatus::Optimal,
+        };
+        let lower_bound = match status {
+            SearchStatus::Optimal => error,
+            SearchStatus::ErrorLimit => self.constraints.max_error,
+            _ => match self.cache.get_node(root_index) {
+                Some(root) => root.value.get_lower_bound().min(error),
+                None => 0.,
+            },
+        };
+        self.statistics.status = status;
+        self.statistics.lower_bound = lower_bound;
+        self.statistics.gap = match error < <f64>::MAX {
+            true => error - lower_bound,
+            false => <f64>::MAX,
+        };
+    }
+
+    // Once the cache is full, evicts what the pinned entries and their solutions do not use
+    fn make_room(&mut self) {
+        if !self.cache.is_full() || self.interruption.is_some() {
             return;
         }
+        let mut keep = vec![];
+        for index in self.pinned.iter() {
+            keep.push(*index);
+            let (mut itemset, leaves) = self.entry_key(*index);
+            self.protect_solution(&mut itemset, leaves, &mut keep);
+        }
+        self.cache.evict(&keep);
+        self.statistics.counters.evictions += 1;
+
+        // The copied solutions kept their source, the first entries of the covers may be gone
+        self.covers.clear();
+        let cache = &self.cache;
+        let is_entry = |index: &Index| cache.get_node(*index).is_some_and(|node| node.is_entry);
+        self.twins
+            .retain(|copy, source| is_entry(copy) && is_entry(source));
+
+        // Evicting again and again would take over the search
+        if self.cache.len() * 2 > self.cache.max_nodes() {
+            self.interrupt(SearchStatus::MemoryLimit);
+        }
+    }
 
-        for i in 0..2 {
-            // Creating children
-            path.insert((attribute, i));
-
-            if let Some(cache_node_index) = self.cache.find(path.iter()) {
-                if let Some(cache_node) = self.cache.get_node(cache_node_index) {
-                    let node_data = self.create_node_data(
-                        cache_node.value.get_test(),
-                        cache_node.value.get_node_error(),
-                        cache_node.value.get_class(),
-                        cache_node.value.is_leaf(),
-                    );
-                    let node_index = tree.add_node(parent_index, i == 0, TreeNode::new(node_data));
+    // Adds the entries the tree of the itemset is generated from, as generate_budget_tree reads them
+    fn protect_solution(&self, itemset: &mut BTreeSet<Item>, leaves: usize, keep: &mut Vec<Index>) {
+        let index = match self.budget_entry(itemset, leaves) {
+            Some(index) => index,
+            None => return,
+        };
+        keep.push(index);
+        if let Some(source) = self.twins.get(&index) {
+            let (mut source_itemset, _) = self.entry_key(*source);
+            return self.protect_solution(&mut source_itemset, leaves, keep);
+        }
+        let (attribute, left_leaves) = match self.cache.get_node(index) {
+            // Until a split is found, the test of an entry is the attribute of its last item
+            Some(node) if !node.value.is_leaf() && node.value.get_node_error() < <f64>::MAX => {
+                (node.value.get_test(), node.value.get_left_leaves())
+            }
+            _ => return,
+        };
+        let children_leaves = match self.leaves_budget(itemset.len(), leaves) {
+            Some(1) => return,
+            Some(leaves) => [left_leaves, leaves - left_leaves],
+            None => [<usize>::MAX, <usize>::MAX],
+        };
+        for (i, child_leaves) in children_leaves.iter().enumerate() {
+            itemset.insert((attribute, i));
+            self.protect_solution(itemset, *child_leaves, keep);
+            itemset.remove(&(attribute, i));
+        }
+    }
 
-                    if !cache_node.value.is_leaf() {
-                        self.generate_tree_rec(cache_node.value.get_test(), path, tree, node_index);
-                    }
+    // Itemset of the entry at `index` and its leaves budget, <usize>::MAX without budget
+    pub(crate) fn entry_key(&self, index: Index) -> (BTreeSet<Item>, usize) {
+        let mut itemset = BTreeSet::new();
+        let mut leaves = <usize>::MAX;
+        for item in self.cache.path(index) {
+            match item.0 {
+                BUDGET_ATTRIBUTE => leaves = item.1,
+                DEPTH_ATTRIBUTE => {}
+                _ => {
+                    itemset.insert(item);
                 }
             }
-            path.remove(&(attribute, i));
         }
+        (itemset, leaves)
+    }
+
+    fn count_return(&mut self, condition: ReturnCondition) {
+        *self
+            .statistics
+            .counters
+            .return_conditions
+            .entry(condition)
+            .or_insert(0) += 1;
     }
 
-    fn create_node_data(
+    fn update_statistics(&mut self) {
+        self.statistics.cache_size = self.cache.len();
+        self.statistics.counters.cache_hits = self.cache.hits();
+        self.statistics.counters.cache_misses = self.cache.misses();
+        self.statistics.duration = self.run_time.elapsed();
+    }
+
+    // The itemset, then the remaining depth when all depths share the cache, then the budget item
+    pub(crate) fn find_entry(
         &self,
-        test: Attribute,
-        error: usize,
-        out: usize,
-        is_leaf: bool,
+        itemset: &BTreeSet<Item>,
+        budget_item: Option<Item>,
+    ) -> Option<Index> {
+        let depth_item = self.depth_item(itemset.len());
+        self.cache.find(
+            itemset
+                .iter()
+                .chain(depth_item.iter())
+                .chain(budget_item.iter()),
+        )
+    }
+
+    pub(crate) fn find_or_create_entry(
+        &mut self,
+        itemset: &BTreeSet<Item>,
+        budget_item: Option<Item>,
+    ) -> (bool, Index) {
+        let depth_item = self.depth_item(itemset.len());
+        self.cache.find_or_create(
+            itemset
+                .iter()
+                .chain(depth_item.iter())
+                .chain(budget_item.iter()),
+        )
+    }
+
+    // Entry of the itemset with the most remaining depth below the one of the search, if any
+    fn shallower_entry(&self, itemset: &BTreeSet<Item>, depth: Depth) -> Option<Index> {
+        (0..self.constraints.max_depth - depth)
+            .rev()
+            .filter_map(|remaining_depth| {
+                let shallower = (DEPTH_ATTRIBUTE, remaining_depth);
+                self.cache
+                    .find(itemset.iter().chain(std::iter::once(&shallower)))
+            })
+            .find(|index| {
+                self.cache
+                    .get_node(*index)
+                    .is_some_and(|node| node.is_entry)
+            })
+    }
+
+    fn depth_item(&self, depth: Depth) -> Option<Item> {
+        match self.depth_keyed {
+            true => Some((DEPTH_ATTRIBUTE, self.constraints.max_depth - depth)),
+            false => None,
+        }
+    }
+
+    // Returns the error of the subtree without the penalty of its leaves
+    pub(crate) fn remove_leaf_penalty(
+        tree: &mut Tree<NodeData>,
+        index: Index,
+        penalty: f64,
+    ) -> f64 {
+        let mut children = None;
+        if let Some(node) = tree.get_node(index) {
+            if node.left != node.right {
+                children = Some((node.left, node.right));
+            }
+        }
+        let error = match children {
+            Some((left, right)) => {
+                Self::remove_leaf_penalty(tree, left, penalty)
+                    + Self::remove_leaf_penalty(tree, right, penalty)
+            }
+            None => match tree.get_node(index) {
+                Some(node) => (node.value.error - penalty).max(0.),
+                None => 0.,
+            },
+        };
+        if let Some(node) = tree.get_node_mut(index) {
+            node.value.error = error;
+        }
+        error
+    }
+
+    // Entry holding the solution of the itemset under a leaves budget, a single leaf is read from
+    // the node without budget
+    fn budget_entry(&self, itemset: &BTreeSet<Item>, leaves: usize) -> Option<Index> {
+        match self.leaves_budget(itemset.len(), leaves) {
+            Some(leaves) if leaves > 1 => {
+                self.find_entry(itemset, Some((BUDGET_ATTRIBUTE, leaves)))
+            }
+            _ => self.find_entry(itemset, None),
+        }
+    }
+
+    fn generate_budget_tree(
+        &self,
+        path: &mut BTreeSet<Item>,
+        tree: &mut Tree<NodeData>,
+        parent: Option<(Index, bool)>,
+        depth: Depth,
+        leaves: usize,
+    ) {
+        let budget = self.leaves_budget(depth, leaves);
+        let cache_index = self.budget_entry(path, leaves);
+        let cache_node = match cache_index.and_then(|index| self.cache.get_node(index)) {
+            Some(cache_node) => cache_node,
+            None => return,
+        };
+
+        // A single leaf is left, the node is read as a leaf whatever its subtree is
+        let node_data = match budget {
+            Some(1) => self.create_node_data(
+                Attribute::MAX,
+                cache_node.value.get_leaf_error(),
+                cache_node.value.get_class(),
+                cache_node.value.get_prediction(),
+                true,
+            ),
+            _ => self.create_node_data(
+                cache_node.value.get_test(),
+                cache_node.value.get_node_error(),
+                cache_node.value.get_class(),
+                cache_node.value.get_prediction(),
+                cache_node.value.is_leaf(),
+            ),
+        };
+        let node_index = match parent {
+            Some((parent_index, is_left)) => {
+                tree.add_node(parent_index, is_left, TreeNode::new(node_data))
+            }
+            None => tree.add_root(TreeNode::new(node_data)),
+        };
+
+        if cache_node.value.is_leaf() {
+            return;
+        }
+        match budget {
+            Some(1) => {}
+            Some(leaves) => {
+                let attribute = cache_node.value.get_test();
+                let left_leaves = cache_node.value.get_left_leaves();
+                for (i, child_leaves) in [left_leaves, leaves - left_leaves].iter().enumerate() {
+                    path.insert((attribute, i));
+                    self.generate_budget_tree(
+                        path,
+                        tree,
+                        Some((node_index, i == 0)),
+                        depth + 1,
+                        *child_leaves,
+                    );
+                    path.remove(&(attribute, i));
+                }
+            }
+            None => {
+                if let Some(cache_index) = cache_index {
+                    self.generate_children(cache_index, path, tree, node_index);
+                }
+            }
+        }
+    }
+
+    fn generate_tree(&self, tree: &mut Tree<NodeData>) {
+        let mut path = BTreeSet::new();
+
+        // Creating root node
+        let root_index = self
+            .find_entry(&path, None)
+            .unwrap_or(self.cache.get_root_index());
+        if let Some(cache_node) = self.cache.get_node(root_index) {
+            let node_data = self.create_node_data(
+                cache_node.value.get_test(),
+                cache_node.value.get_node_error(),
+                cache_node.value.get_class(),
+                cache_node.value.get_prediction(),
+                cache_node.value.is_leaf(),
+            );
+            let _ = tree.add_root(TreeNode::new(node_data));
+
+            // Creating the rest of the tree
+            let tree_root_index = tree.get_root_index();
+            self.generate_children(root_index, &mut path, tree, tree_root_index);
+        }
+    }
+
+    // The children of a copied solution are those of the entry with the same cover it comes from
+    fn generate_children(
+        &self,
+        index: Index,
+        path: &mut BTreeSet<Item>,
+        tree: &mut Tree<NodeData>,
+        node_index: Index,
+    ) {
+        match self.twins.get(&index) {
+            Some(source) => {
+                let (mut source_path, _) = self.entry_key(*source);
+                if let Some(cache_node) = self.cache.get_node(*source) {
+                    let test = cache_node.value.get_test();
+                    self.generate_tree_rec(test, &mut source_path, tree, node_index);
+                }
+            }
+            None => {
+                if let Some(cache_node) = self.cache.get_node(index) {
+                    self.generate_tree_rec(cache_node.value.get_test(), path, tree, node_index);
+                }
+            }
+        }
+    }
+
+    fn generate_tree_rec(
+        &self,
+        attribute: Attribute,
+        path: &mut BTreeSet<Item>,
+        tree: &mut Tree<NodeData>,
+        parent_index: usize,
+    ) {
+        if attribute == Attribute::MAX {
+            return;
+        }
+
+        for i in 0..2 {
+            // Creating children
+            path.insert((attribute, i));
+
+            if let Some(cache_node_index) = self.find_entry(path, None) {
+                if let Some(cache_node) = self.cache.get_node(cache_node_index) {
+                    let node_data = self.create_node_data(
+                        cache_node.value.get_test(),
+                        cache_node.value.get_node_error(),
+                        cache_node.value.get_class(),
+                        cache_node.value.get_prediction(),
+                        cache_node.value.is_leaf(),
+                    );
+                    let node_index = tree.add_node(parent_index, i == 0, TreeNode::new(node_data));
+
+                    if !cache_node.value.is_leaf() {
+                        self.generate_children(cache_node_index, path, tree, node_index);
+                    }
+                }
+            }
+            path.remove(&(attribute, i));
+        }
+    }
+
+    pub(crate) fn create_node_data(
+        &self,
+        test: Attribute,
+        error: f64,
+        out: usize,
+        prediction: Option<f64>,
+        is_leaf: bool,
     ) -> NodeData {
         if is_leaf {
             return NodeData {
                 test: None,
                 error,
                 out: Some(out),
+                prediction,
                 metric: None,
             };
         }
@@ -853,6 +2244,7 @@ where
             test: Some(test),
             error,
             out: None,
+            prediction: None,
             metric: None,
         }
     }
@@ -860,17 +2252,33 @@ where
 
 #[cfg(test)]
 mod dl85_test {
+    use crate::algorithms::algorithm_trait::Algorithm;
+    use crate::algorithms::cancellation::CancellationToken;
     use crate::algorithms::dl85::DL85;
+    use crate::algorithms::dl85_utils::leaf_objective::{
+        LeafObjective, Misclassification, WeightedCost,
+    };
     use crate::algorithms::dl85_utils::structs_enums::{
-        BranchingType, CacheInit, LowerBoundHeuristic, Specialization,
+        BranchingType, CacheInit, CacheType, LowerBoundHeuristic, ReturnCondition, SearchStatus,
+        Specialization,
     };
+    use crate::algorithms::lgdt::LGDT;
+    use crate::algorithms::murtree::MurTree;
     use crate::dataset::binary_dataset::BinaryDataset;
     use crate::dataset::data_trait::Dataset;
+    use crate::errors::PyTreesError;
     use crate::heuristics::{Heuristic, InformationGain, NoHeuristic};
-    use crate::structures::caching::trie::Data;
+    use crate::structures::binary_tree::{NodeData, Tree};
+    use crate::structures::caching::trie::{Data, TrieNode};
     use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
     use crate::structures::structure_trait::Structure;
     use itertools::MinMaxResult::NoElements;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
 
     #[test]
     fn run_dl85() {
@@ -878,12 +2286,1439 @@ mod dl85_test {
         let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
         let mut structure = RSparseBitsetStructure::new(&bitset_data);
 
-        let mut heuristic: Box<dyn Heuristic> = Box::new(NoHeuristic::default());
+        let mut heuristic: Box<dyn Heuristic> = Box::new(NoHeuristic);
 
         let mut algo: DL85<'_, _, Data> = DL85::new(
-            1,
+            1.,
             6,
-            <usize>::MAX,
+            <f64>::MAX,
+            10,
+            Specialization::None,
+            LowerBoundHeuristic::None,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic.as_mut(),
+            Misclassification,
+        );
+        algo.fit(&mut structure).unwrap();
+    }
+
+    #[test]
+    fn run_dl85_with_uniform_weights() {
+        let mut dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+        let mut errors = vec![];
+
+        // The minimum support is weighted as well, it scales with the weights
+        for weight in [1., 3., 0.5] {
+            dataset
+                .set_sample_weights(vec![weight; dataset.train_size()])
+                .unwrap();
+            let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                weight,
+                2,
+                <f64>::MAX,
+                10,
+                Specialization::None,
+                LowerBoundHeuristic::None,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.fit(&mut structure).unwrap();
+            errors.push(algo.statistics.tree_error);
+        }
+        assert_eq!(errors[1], 3. * errors[0]);
+        assert_eq!(errors[2], 0.5 * errors[0]);
+    }
+
+    #[test]
+    fn run_dl85_similarity_with_skewed_weights() {
+        let mut dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+        // Half of the rows weigh 50, drawn from a fixed linear congruential sequence
+        let mut state = 3 * 2654435761 + 12345u64;
+        let weights = (0..dataset.train_size())
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                match (state >> 33) % 2 {
+                    0 => 50.,
+                    _ => 1.,
+                }
+            })
+            .collect::<Vec<f64>>();
+        dataset.set_sample_weights(weights).unwrap();
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+
+        for (depth, expected_error) in [(2, 2982.), (3, 2521.)] {
+            for lower_bound in [LowerBoundHeuristic::None, LowerBoundHeuristic::Similarity] {
+                let mut structure = RSparseBitsetStructure::new(&bitset_data);
+                let mut algo: DL85<'_, _, Data> = DL85::new(
+                    1.,
+                    depth,
+                    <f64>::MAX,
+                    600,
+                    Specialization::None,
+                    lower_bound,
+                    BranchingType::Dynamic,
+                    CacheInit::WithMemoryDynamic,
+                    0,
+                    true,
+                    heuristic.as_mut(),
+                    Misclassification,
+                );
+                algo.fit(&mut structure).unwrap();
+                assert_eq!(algo.statistics.tree_error, expected_error);
+            }
+        }
+    }
+
+    #[test]
+    fn run_dl85_with_unit_costs() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        let mut algo: DL85<'_, _, Data> = DL85::new(
+            1.,
+            2,
+            <f64>::MAX,
+            10,
+            Specialization::None,
+            LowerBoundHeuristic::None,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic.as_mut(),
+            Misclassification,
+        );
+        algo.fit(&mut structure).unwrap();
+        let expected_error = algo.statistics.tree_error;
+
+        structure.reset();
+        let mut algo: DL85<'_, _, Data, _> = DL85::new(
+            1.,
+            2,
+            <f64>::MAX,
+            10,
+            Specialization::None,
+            LowerBoundHeuristic::None,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic.as_mut(),
+            WeightedCost::new(vec![1., 1.]).unwrap(),
+        );
+        algo.fit(&mut structure).unwrap();
+        assert_eq!(algo.statistics.tree_error, expected_error);
+    }
+
+    #[test]
+    fn run_dl85_with_cost_matrix() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+        let costs = vec![vec![0., 5.], vec![1., 0.]];
+
+        // The specialization and the similarity lower bound must keep the optimal cost
+        let mut errors = vec![];
+        for (specialization, lower_bound) in [
+            (Specialization::None, LowerBoundHeuristic::None),
+            (Specialization::Murtree, LowerBoundHeuristic::Similarity),
+        ] {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                3,
+                <f64>::MAX,
+                100,
+                specialization,
+                lower_bound,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.set_cost_matrix(costs.clone()).unwrap();
+            algo.fit(&mut structure).unwrap();
+            errors.push(algo.statistics.tree_error);
+        }
+        assert_eq!(errors[0], errors[1]);
+    }
+
+    #[test]
+    fn run_dl85_with_leaf_penalty() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        let mut results = vec![];
+        for penalty in [0., 5., 1000.] {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                3,
+                <f64>::MAX,
+                100,
+                Specialization::None,
+                LowerBoundHeuristic::Similarity,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            assert!(algo.set_leaf_penalty(-1.).is_err());
+            algo.set_leaf_penalty(penalty).unwrap();
+            algo.fit(&mut structure).unwrap();
+
+            let leaves = (0..algo.tree.len())
+                .filter_map(|index| algo.tree.get_node(index))
+                .filter(|node| node.left == node.right)
+                .count();
+            let root = algo.tree.get_node(algo.tree.get_root_index()).unwrap();
+            // The tree keeps the misclassification error, the statistics the penalised one
+            assert_eq!(
+                algo.statistics.tree_error,
+                root.value.error + penalty * leaves as f64
+            );
+            results.push((root.value.error, leaves));
+        }
+
+        // Without penalty, the leaves of the depth 3 tree are all used
+        assert_eq!(results[0].1, 8);
+        assert!(results[1].1 < results[0].1);
+        assert!(results[1].0 + 5. * results[1].1 as f64 <= results[0].0 + 5. * 8.);
+        assert_eq!(results[2], (187., 1));
+    }
+
+    #[test]
+    fn leaf_penalty_turns_off_the_specialization_for_one_fit() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+        let mut algo: DL85<'_, _, Data> = DL85::new(
+            1.,
+            3,
+            <f64>::MAX,
+            100,
+            Specialization::Murtree,
+            LowerBoundHeuristic::Similarity,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic.as_mut(),
+            Misclassification,
+        );
+
+        algo.set_leaf_penalty(5.).unwrap();
+        algo.fit(&mut RSparseBitsetStructure::new(&bitset_data))
+            .unwrap();
+        assert!(matches!(
+            algo.statistics.constraints.specialization,
+            Specialization::None
+        ));
+        assert!(matches!(
+            algo.constraints.specialization,
+            Specialization::Murtree
+        ));
+
+        // The next fit without penalty specializes again
+        algo.set_leaf_penalty(0.).unwrap();
+        algo.fit(&mut RSparseBitsetStructure::new(&bitset_data))
+            .unwrap();
+        assert!(matches!(
+            algo.statistics.constraints.specialization,
+            Specialization::Murtree
+        ));
+        assert!(matches!(
+            algo.statistics.constraints.lower_bound,
+            LowerBoundHeuristic::Similarity
+        ));
+    }
+
+    fn best_depth_one_error(structure: &mut RSparseBitsetStructure) -> f64 {
+        let mut best = Misclassification.leaf_error(structure).0;
+        for feature in 0..structure.num_attributes() {
+            let mut error = 0.;
+            for value in 0..2 {
+                if structure.temp_push((feature, value)) == 0. {
+                    error = <f64>::MAX;
+                    break;
+                }
+                structure.push((feature, value));
+                error += Misclassification.leaf_error(structure).0;
+                structure.backtrack();
+            }
+            best = best.min(error);
+        }
+        best
+    }
+
+    #[test]
+    fn run_dl85_with_max_leaves() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        // Brute force over the trees with three leaves
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut three_leaves_error = best_depth_one_error(&mut structure);
+        for feature in 0..structure.num_attributes() {
+            let mut sides = [(0., 0.); 2];
+            for (value, side) in sides.iter_mut().enumerate() {
+                if structure.temp_push((feature, value)) == 0. {
+                    *side = (<f64>::MAX, <f64>::MAX);
+                    continue;
+                }
+                structure.push((feature, value));
+                *side = (
+                    Misclassification.leaf_error(&mut structure).0,
+                    best_depth_one_error(&mut structure),
+                );
+                structure.backtrack();
+            }
+            if sides[0].0 < <f64>::MAX && sides[1].0 < <f64>::MAX {
+                three_leaves_error = three_leaves_error
+                    .min(sides[0].0 + sides[1].1)
+                    .min(sides[0].1 + sides[1].0);
+            }
+        }
+
+        let mut specialization_errors = vec![];
+        for specialization in [Specialization::None, Specialization::Murtree] {
+            let mut errors = vec![];
+            for max_leaves in 1..=9 {
+                let mut structure = RSparseBitsetStructure::new(&bitset_data);
+                let mut algo: DL85<'_, _, Data> = DL85::new(
+                    1.,
+                    3,
+                    <f64>::MAX,
+                    100,
+                    specialization,
+                    LowerBoundHeuristic::None,
+                    BranchingType::Dynamic,
+                    CacheInit::Normal,
+                    0,
+                    false,
+                    heuristic.as_mut(),
+                    Misclassification,
+                );
+                algo.set_max_leaves(max_leaves).unwrap();
+                algo.fit(&mut structure).unwrap();
+
+                let leaves = (0..algo.tree.len())
+                    .filter_map(|index| algo.tree.get_node(index))
+                    .filter(|node| node.left == node.right)
+                    .count();
+                let root = algo.tree.get_node(algo.tree.get_root_index()).unwrap();
+                assert!(leaves <= max_leaves);
+                assert_eq!(root.value.error, algo.statistics.tree_error);
+                errors.push(algo.statistics.tree_error);
+                // The budgets of the last two levels go to the specialized algorithm too
+                if let (Specialization::Murtree, 3..=7) = (specialization, max_leaves) {
+                    assert!(algo.statistics.counters.specialized_calls > 0);
+                }
+            }
+
+            assert_eq!(errors[0], 187.);
+            assert_eq!(errors[1], best_depth_one_error(&mut structure));
+            assert_eq!(errors[2], three_leaves_error);
+            assert!(errors.windows(2).all(|pair| pair[1] <= pair[0]));
+            // Eight leaves are all a depth 3 tree can have
+            assert_eq!(errors[7], errors[8]);
+            specialization_errors.push(errors);
+
+            // The budget of a depth 2 root is solved by the specialized algorithm alone
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                2,
+                <f64>::MAX,
+                100,
+                specialization,
+                LowerBoundHeuristic::None,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.set_max_leaves(3).unwrap();
+            algo.fit(&mut structure).unwrap();
+            let root = algo.tree.get_root_index();
+            assert_eq!(algo.statistics.tree_error, three_leaves_error);
+            assert_eq!(
+                tree_error_on(&mut structure, &algo.tree, root),
+                three_leaves_error
+            );
+        }
+        assert_eq!(specialization_errors[0], specialization_errors[1]);
+    }
+
+    #[test]
+    fn run_dl85_with_murtree() {
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        // Some subtrees of the specialized algorithm have no split with enough support
+        let configurations = [
+            ("anneal", 1., 4, LowerBoundHeuristic::Similarity),
+            ("anneal", 5., 3, LowerBoundHeuristic::None),
+            ("anneal", 50., 3, LowerBoundHeuristic::None),
+            ("anneal", 150., 3, LowerBoundHeuristic::None),
+            ("mushroom", 100., 3, LowerBoundHeuristic::None),
+            ("mushroom", 1000., 3, LowerBoundHeuristic::None),
+        ];
+        let mut errors = vec![];
+        for (name, min_sup, max_depth, lower_bound) in configurations {
+            let dataset = BinaryDataset::load(&format!("test_data/{}.txt", name), false, 0.0);
+            let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                min_sup,
+                max_depth,
+                <f64>::MAX,
+                100,
+                Specialization::Murtree,
+                lower_bound,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.fit(&mut structure).unwrap();
+            let root = algo.tree.get_root_index();
+            assert_eq!(
+                tree_error_on(&mut structure, &algo.tree, root),
+                algo.statistics.tree_error
+            );
+            errors.push(algo.statistics.tree_error);
+        }
+        assert_eq!(errors[0], 91.);
+    }
+
+    #[test]
+    fn run_dl85_all_depths() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        for (specialization, lower_bound) in [
+            (Specialization::None, LowerBoundHeuristic::None),
+            (Specialization::Murtree, LowerBoundHeuristic::Similarity),
+        ] {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                3,
+                <f64>::MAX,
+                100,
+                specialization,
+                lower_bound,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.set_all_depths(true);
+            algo.fit(&mut structure).unwrap();
+            let depth_errors = algo.statistics.depth_errors.clone();
+            let depth_trees = algo.depth_trees.clone();
+            assert_eq!(depth_errors.len(), 3);
+            assert_eq!(algo.statistics.tree_error, depth_errors[2]);
+
+            for depth in 1..=3 {
+                let mut structure = RSparseBitsetStructure::new(&bitset_data);
+                let mut algo: DL85<'_, _, Data> = DL85::new(
+                    1.,
+                    depth,
+                    <f64>::MAX,
+                    100,
+                    specialization,
+                    lower_bound,
+                    BranchingType::Dynamic,
+                    CacheInit::Normal,
+                    0,
+                    false,
+                    heuristic.as_mut(),
+                    Misclassification,
+                );
+                algo.fit(&mut structure).unwrap();
+                assert_eq!(depth_errors[depth - 1], algo.statistics.tree_error);
+
+                let tree = &depth_trees[depth - 1];
+                let root = tree.get_node(tree.get_root_index()).unwrap();
+                assert_eq!(root.value.error, depth_errors[depth - 1]);
+                assert!(tree.actual_len() < 1 << (depth + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn run_dl85_anytime() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut algo: DL85<'_, _, Data> = DL85::new(
+            1.,
+            3,
+            <f64>::MAX,
+            100,
+            Specialization::None,
+            LowerBoundHeuristic::None,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic.as_mut(),
+            Misclassification,
+        );
+        algo.set_anytime(true);
+        algo.fit(&mut structure).unwrap();
+
+        let incumbents = &algo.statistics.incumbents;
+        assert!(incumbents.len() > 1);
+        assert_eq!(incumbents[0].error, 187.);
+        assert_eq!(incumbents.last().unwrap().error, algo.statistics.tree_error);
+        for pair in incumbents.windows(2) {
+            assert!(pair[1].error < pair[0].error);
+            assert!(pair[1].time >= pair[0].time);
+        }
+        for incumbent in incumbents {
+            let root = incumbent
+                .tree
+                .get_node(incumbent.tree.get_root_index())
+                .unwrap();
+            assert_eq!(root.value.error, incumbent.error);
+        }
+        let optimal_error = algo.statistics.tree_error;
+
+        // Stopping at the second incumbent keeps a tree at least as good
+        let calls = Rc::new(RefCell::new(vec![]));
+        let recorded_calls = calls.clone();
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut algo: DL85<'_, _, Data> = DL85::new(
+            1.,
+            3,
+            <f64>::MAX,
+            100,
+            Specialization::None,
+            LowerBoundHeuristic::None,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic.as_mut(),
+            Misclassification,
+        );
+        algo.set_incumbent_callback(move |incumbent| {
+            recorded_calls.borrow_mut().push(incumbent.error);
+            recorded_calls.borrow().len() < 2
+        });
+        algo.fit(&mut structure).unwrap();
+        let tree_error = algo.statistics.tree_error;
+
+        let calls = calls.borrow();
+        assert_eq!(calls.len(), 2);
+        assert!(tree_error <= calls[1]);
+        assert!(tree_error >= optimal_error);
+        assert_eq!(algo.statistics.status, SearchStatus::Interrupted);
+    }
+
+    #[test]
+    fn run_dl85_cancelled() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let (mut heuristic, mut other_heuristic) = (NoHeuristic, NoHeuristic);
+
+        // Cancelled from the second incumbent, the search keeps it
+        let token = CancellationToken::new();
+        let signal = token.clone();
+        let calls = Rc::new(RefCell::new(vec![]));
+        let recorded_calls = calls.clone();
+        let mut algo = anneal_dl85(&mut heuristic, 3);
+        algo.set_cancellation_token(token);
+        algo.set_incumbent_callback(move |incumbent| {
+            recorded_calls.borrow_mut().push(incumbent.error);
+            if recorded_calls.borrow().len() == 2 {
+                signal.cancel();
+            }
+            true
+        });
+        algo.fit(&mut structure).unwrap();
+        let calls = calls.borrow();
+        assert_eq!(algo.statistics.status, SearchStatus::Cancelled);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(algo.statistics.tree_error, calls[1]);
+        let root = algo.tree.get_root_index();
+        assert_eq!(tree_error_on(&mut structure, &algo.tree, root), calls[1]);
+
+        // A watcher cancelling at its first poll stops the search at the root
+        let mut algo = anneal_dl85(&mut other_heuristic, 3);
+        algo.set_cancellation_token(CancellationToken::with_watcher(|| true, Duration::ZERO));
+        algo.fit(&mut structure).unwrap();
+        assert_eq!(algo.statistics.status, SearchStatus::Cancelled);
+        assert_eq!(algo.statistics.tree_error, calls[0]);
+        assert_eq!(algo.tree.actual_len(), 1);
+    }
+
+    #[test]
+    fn run_dl85_progress() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+
+        for (interval, expected_calls) in
+            [(Duration::ZERO, None), (Duration::from_secs(100), Some(1))]
+        {
+            let reports = Rc::new(RefCell::new(vec![]));
+            let recorded_reports = reports.clone();
+            let mut algo = anneal_dl85(&mut heuristic, 3);
+            algo.set_progress_callback(interval, move |progress| {
+                recorded_reports.borrow_mut().push(*progress);
+            });
+            algo.fit(&mut structure).unwrap();
+
+            let reports = reports.borrow();
+            match expected_calls {
+                Some(calls) => assert_eq!(reports.len(), calls),
+                None => assert!(reports.len() > 1),
+            }
+            assert_eq!(reports[0].upper_bound, 187.);
+            for pair in reports.windows(2) {
+                assert!(pair[1].time >= pair[0].time);
+                assert!(pair[1].upper_bound <= pair[0].upper_bound);
+                assert!(pair[1].cache_size >= pair[0].cache_size);
+            }
+            assert!(reports.last().unwrap().upper_bound >= algo.statistics.tree_error);
+        }
+    }
+
+    #[test]
+    fn run_dl85_search_status() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        // (max_error, max_time) of a complete search, a search without time and an impossible bound
+        let runs = [(<f64>::MAX, 100), (<f64>::MAX, 0), (1., 100)];
+        let mut statistics = vec![];
+        for (max_error, max_time) in runs {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                2,
+                max_error,
+                max_time,
+                Specialization::None,
+                LowerBoundHeuristic::None,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.fit(&mut structure).unwrap();
+            statistics.push(algo.statistics.clone());
+        }
+
+        assert_eq!(statistics[0].status, SearchStatus::Optimal);
+        assert_eq!(statistics[0].lower_bound, statistics[0].tree_error);
+        assert_eq!(statistics[0].gap, 0.);
+
+        assert_eq!(statistics[1].status, SearchStatus::TimeLimit);
+        assert_eq!(statistics[1].tree_error, 187.);
+        assert!(statistics[1].lower_bound <= statistics[0].tree_error);
+        assert_eq!(
+            statistics[1].gap,
+            statistics[1].tree_error - statistics[1].lower_bound
+        );
+
+        assert_eq!(statistics[2].status, SearchStatus::ErrorLimit);
+        assert_eq!(statistics[2].lower_bound, 1.);
+        assert_eq!(statistics[2].gap, statistics[2].tree_error - 1.);
+    }
+
+    #[test]
+    fn run_dl85_counters() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        // Without the dynamic branching, the similarity bound is only applied in the recursion
+        let mut counters = vec![];
+        for specialization in [Specialization::None, Specialization::Murtree] {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                3,
+                <f64>::MAX,
+                100,
+                specialization,
+                LowerBoundHeuristic::Similarity,
+                BranchingType::None,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.fit(&mut structure).unwrap();
+            counters.push(algo.statistics.counters.clone());
+        }
+
+        for counter in &counters {
+            // Every call of the recursion returns once, the expanded nodes are a part of them
+            let returns = counter.return_conditions.values().sum::<usize>();
+            assert!(counter.nodes_expanded > 0);
+            assert!(returns >= counter.nodes_expanded);
+            assert!(counter.cache_hits > 0);
+            assert!(counter.cache_misses > 0);
+        }
+        assert_eq!(counters[0].specialized_calls, 0);
+        assert!(counters[0].similarity_prunings > 0);
+        assert!(counters[1].specialized_calls > 0);
+        assert!(counters[1].nodes_expanded < counters[0].nodes_expanded);
+    }
+
+    #[test]
+    fn run_dl85_with_memory_limit() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+        let node_size = std::mem::size_of::<TrieNode<Data>>();
+
+        // No limit, then a quarter of the cache, with and without a leaves budget, then too little
+        let mut statistics = vec![];
+        let mut trees = vec![];
+        let mut limit = None;
+        for (max_leaves, nodes) in [(None, 0), (None, 4), (Some(5), 0), (Some(5), 4), (None, 50)] {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                3,
+                <f64>::MAX,
+                100,
+                Specialization::None,
+                LowerBoundHeuristic::None,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            if let Some(leaves) = max_leaves {
+                algo.set_max_leaves(leaves).unwrap();
+            }
+            match nodes {
+                0 => {}
+                50 => algo.set_memory_limit(50 * node_size).unwrap(),
+                _ => algo.set_memory_limit(limit.unwrap() / nodes).unwrap(),
+            }
+            algo.fit(&mut structure).unwrap();
+            if nodes == 0 {
+                limit = Some(algo.statistics.cache_size * node_size);
+            }
+            statistics.push(algo.statistics.clone());
+            trees.push(algo.tree.clone());
+        }
+
+        for (bounded, unbounded) in [(1, 0), (3, 2)] {
+            assert_eq!(statistics[bounded].status, SearchStatus::Optimal);
+            assert!(statistics[bounded].counters.evictions > 0);
+            assert_eq!(
+                statistics[bounded].tree_error,
+                statistics[unbounded].tree_error
+            );
+            // The evicted entries did not break the generated tree
+            let leaves_error = (0..trees[bounded].len())
+                .filter_map(|index| trees[bounded].get_node(index))
+                .filter(|node| node.left == node.right)
+                .map(|leaf| leaf.value.error)
+                .sum::<f64>();
+            assert_eq!(leaves_error, statistics[bounded].tree_error);
+        }
+        assert_eq!(statistics[4].status, SearchStatus::MemoryLimit);
+        assert!(statistics[4].tree_error >= statistics[0].tree_error);
+    }
+
+    #[test]
+    fn run_dl85_with_hash_cache() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        // (specialization, max leaves, all depths, memory limit in entries of the trie)
+        let configurations = [
+            (Specialization::None, None, false, None),
+            (Specialization::Murtree, None, false, None),
+            (Specialization::None, Some(5), false, None),
+            (Specialization::None, None, true, None),
+            (Specialization::None, None, false, Some(2000)),
+        ];
+        for (specialization, max_leaves, all_depths, memory_limit) in configurations {
+            let mut results = vec![];
+            for cache_type in [CacheType::Trie, CacheType::Hash] {
+                let mut structure = RSparseBitsetStructure::new(&bitset_data);
+                let mut algo: DL85<'_, _, Data> = DL85::new(
+                    1.,
+                    3,
+                    <f64>::MAX,
+                    100,
+                    specialization,
+                    LowerBoundHeuristic::Similarity,
+                    BranchingType::Dynamic,
+                    CacheInit::Normal,
+                    0,
+                    false,
+                    heuristic.as_mut(),
+                    Misclassification,
+                );
+                algo.set_cache_type(cache_type);
+                if let Some(leaves) = max_leaves {
+                    algo.set_max_leaves(leaves).unwrap();
+                }
+                algo.set_all_depths(all_depths);
+                if let Some(entries) = memory_limit {
+                    algo.set_memory_limit(entries * std::mem::size_of::<TrieNode<Data>>())
+                        .unwrap();
+                }
+                algo.fit(&mut structure).unwrap();
+                results.push((
+                    algo.statistics.tree_error,
+                    algo.statistics.depth_errors.clone(),
+                    algo.statistics.counters.cache_hits,
+                ));
+            }
+            assert_eq!(results[0].0, results[1].0);
+            assert_eq!(results[0].1, results[1].1);
+            if memory_limit.is_none() {
+                assert_eq!(results[0].2, results[1].2);
+            }
+        }
+    }
+
+    // Misclassifications of the leaves of the tree, counted again on the structure
+    fn tree_error_on(
+        structure: &mut RSparseBitsetStructure,
+        tree: &Tree<NodeData>,
+        index: usize,
+    ) -> f64 {
+        let node = tree.get_node(index).unwrap();
+        match node.value.test {
+            Some(test) if node.left != node.right => {
+                let mut error = 0.;
+                for (i, child) in [node.left, node.right].iter().enumerate() {
+                    structure.push((test, i));
+                    error += tree_error_on(structure, tree, *child);
+                    structure.backtrack();
+                }
+                error
+            }
+            _ => {
+                let support = structure.support();
+                support
+                    - structure
+                        .labels_support()
+                        .iter()
+                        .copied()
+                        .fold(0., f64::max)
+            }
+        }
+    }
+
+    #[test]
+    fn run_dl85_with_cover_cache() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        // (specialization, max leaves, all depths, memory limit in entries of the trie)
+        let configurations = [
+            (Specialization::None, None, false, None),
+            (Specialization::Murtree, None, false, None),
+            (Specialization::None, Some(5), false, None),
+            (Specialization::None, None, true, None),
+            (Specialization::None, None, false, Some(2000)),
+        ];
+        for (specialization, max_leaves, all_depths, memory_limit) in configurations {
+            let mut results = vec![];
+            for cover_cache in [false, true] {
+                let mut structure = RSparseBitsetStructure::new(&bitset_data);
+                let mut algo: DL85<'_, _, Data> = DL85::new(
+                    1.,
+                    3,
+                    <f64>::MAX,
+                    100,
+                    specialization,
+                    LowerBoundHeuristic::Similarity,
+                    BranchingType::Dynamic,
+                    CacheInit::Normal,
+                    0,
+                    false,
+                    heuristic.as_mut(),
+                    Misclassification,
+                );
+                algo.set_cover_cache(cover_cache);
+                if let Some(leaves) = max_leaves {
+                    algo.set_max_leaves(leaves).unwrap();
+                }
+                algo.set_all_depths(all_depths);
+                if let Some(entries) = memory_limit {
+                    algo.set_memory_limit(entries * std::mem::size_of::<TrieNode<Data>>())
+                        .unwrap();
+                }
+                algo.fit(&mut structure).unwrap();
+
+                // The trees built from copied solutions hold on the data
+                let mut trees = algo.depth_trees.clone();
+                trees.push(algo.tree.clone());
+                for tree in trees.iter() {
+                    structure.reset();
+                    let root = tree.get_root_index();
+                    assert_eq!(
+                        tree_error_on(&mut structure, tree, root),
+                        tree.get_node(root).unwrap().value.error
+                    );
+                }
+                results.push((
+                    algo.statistics.tree_error,
+                    algo.statistics.depth_errors.clone(),
+                    algo.statistics.counters.cover_reuses,
+                ));
+            }
+            assert_eq!(results[0].0, results[1].0);
+            assert_eq!(results[0].1, results[1].1);
+            assert_eq!(results[0].2, 0);
+            if max_leaves.is_none() {
+                assert!(results[1].2 > 0);
+            }
+        }
+    }
+
+    fn anneal_dl85(heuristic: &mut NoHeuristic, depth: usize) -> DL85<'_, NoHeuristic, Data> {
+        DL85::new(
+            1.,
+            depth,
+            <f64>::MAX,
+            100,
+            Specialization::None,
+            LowerBoundHeuristic::Similarity,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            heuristic,
+            Misclassification,
+        )
+    }
+
+    #[test]
+    fn run_dl85_warm_start() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let (mut heuristic, mut other_heuristic) = (NoHeuristic, NoHeuristic);
+
+        let mut cold = anneal_dl85(&mut heuristic, 3);
+        cold.fit(&mut structure).unwrap();
+        let (error, nodes) = (
+            cold.statistics.tree_error,
+            cold.statistics.counters.nodes_expanded,
+        );
+        let snapshot = cold.cache_snapshot(&mut structure).unwrap();
+        assert!(!snapshot.is_empty() && snapshot.len() <= cold.statistics.cache_size);
+
+        // Through a file, the same depth starts from the solutions
+        let filename = std::env::temp_dir().join("dl85_warm_start_cache.json");
+        let filename = filename.to_str().unwrap();
+        cold.save_cache(&mut structure, filename).unwrap();
+        let mut warm = anneal_dl85(&mut heuristic, 3);
+        warm.load_cache(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        warm.fit(&mut structure).unwrap();
+        assert_eq!(warm.statistics.tree_error, error);
+        assert!(warm.statistics.counters.nodes_expanded < nodes);
+
+        // A deeper search over all depths starts from the shallower one
+        let mut shallow = anneal_dl85(&mut heuristic, 2);
+        shallow.fit(&mut structure).unwrap();
+        let mut deeper = anneal_dl85(&mut other_heuristic, 3);
+        deeper.set_all_depths(true);
+        deeper
+            .set_warm_start(shallow.cache_snapshot(&mut structure).unwrap())
+            .unwrap();
+        deeper.fit(&mut structure).unwrap();
+        assert_eq!(
+            deeper.statistics.depth_errors,
+            vec![
+                deeper.statistics.depth_errors[0],
+                shallow.statistics.tree_error,
+                error
+            ]
+        );
+
+        // Without all depths, the shallower solutions are kept by remaining depth and bound the search
+        // of the same itemsets
+        let mut bounded = anneal_dl85(&mut other_heuristic, 3);
+        bounded
+            .set_warm_start(shallow.cache_snapshot(&mut structure).unwrap())
+            .unwrap();
+        bounded.fit(&mut structure).unwrap();
+        assert_eq!(bounded.statistics.tree_error, error);
+        assert!(bounded.statistics.counters.nodes_expanded <= nodes);
+
+        // The entries of a deeper search do not fit a shallower one
+        let mut shallower = anneal_dl85(&mut other_heuristic, 2);
+        shallower.set_warm_start(snapshot).unwrap();
+        assert_eq!(
+            shallower.fit(&mut structure),
+            Err(PyTreesError::InvalidParameter(
+                "The warm start has no entry for the depths of the search".to_string()
+            ))
+        );
+
+        // The partial results of interrupted searches are not kept
+        for incumbents in [1, 2] {
+            let mut interrupted = anneal_dl85(&mut heuristic, 3);
+            let mut calls = 0;
+            interrupted.set_incumbent_callback(move |_| {
+                calls += 1;
+                calls < incumbents
+            });
+            interrupted.fit(&mut structure).unwrap();
+            assert_ne!(interrupted.statistics.status, SearchStatus::Optimal);
+            let mut resumed = anneal_dl85(&mut other_heuristic, 3);
+            resumed
+                .set_warm_start(interrupted.cache_snapshot(&mut structure).unwrap())
+                .unwrap();
+            resumed.fit(&mut structure).unwrap();
+            assert_eq!(resumed.statistics.tree_error, error);
+        }
+    }
+
+    #[test]
+    fn run_dl85_warm_start_with_max_leaves() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let (mut heuristic, mut other_heuristic) = (NoHeuristic, NoHeuristic);
+
+        let mut cold = anneal_dl85(&mut heuristic, 3);
+        cold.set_max_leaves(4).unwrap();
+        cold.fit(&mut structure).unwrap();
+        let mut warm = anneal_dl85(&mut other_heuristic, 3);
+        warm.set_max_leaves(4).unwrap();
+        warm.set_warm_start(cold.cache_snapshot(&mut structure).unwrap())
+            .unwrap();
+        warm.fit(&mut structure).unwrap();
+
+        // The tree is rebuilt from the loaded entries and still keeps to the budget
+        let leaves = (0..warm.tree.len())
+            .filter_map(|index| warm.tree.get_node(index))
+            .filter(|node| node.left == node.right)
+            .count();
+        let root = warm.tree.get_root_index();
+        assert!(leaves <= 4);
+        assert_eq!(warm.statistics.tree_error, cold.statistics.tree_error);
+        assert_eq!(
+            tree_error_on(&mut structure, &warm.tree, root),
+            warm.statistics.tree_error
+        );
+    }
+
+    #[test]
+    fn run_dl85_warm_start_on_another_dataset() {
+        let (mut heuristic, mut other_heuristic) = (NoHeuristic, NoHeuristic);
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut algo = anneal_dl85(&mut heuristic, 2);
+        algo.fit(&mut structure).unwrap();
+        let snapshot = algo.cache_snapshot(&mut structure).unwrap();
+
+        let dataset = BinaryDataset::load("test_data/mushroom.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut algo = anneal_dl85(&mut other_heuristic, 2);
+        algo.set_warm_start(snapshot).unwrap();
+        assert_eq!(
+            algo.fit(&mut structure),
+            Err(PyTreesError::InvalidParameter(
+                "The warm start comes from another dataset".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn run_dl85_with_initial_tree() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+
+        let initial_tree = LGDT::fit(&mut structure, 1., 3, MurTree::fit);
+        let initial_error = tree_error_on(&mut structure, &initial_tree, 0);
+
+        // The nodes the similarity bound saves depend on the order of the search, it is left out
+        let mut cold = anneal_dl85(&mut heuristic, 3);
+        cold.constraints.lower_bound = LowerBoundHeuristic::None;
+        cold.fit(&mut structure).unwrap();
+        let (error, nodes) = (
+            cold.statistics.tree_error,
+            cold.statistics.counters.nodes_expanded,
+        );
+        assert!(error < initial_error);
+
+        // The optimal tree is still found, with the initial error as first bound
+        let mut warm = anneal_dl85(&mut heuristic, 3);
+        warm.constraints.lower_bound = LowerBoundHeuristic::None;
+        warm.set_initial_tree(initial_tree.clone());
+        warm.fit(&mut structure).unwrap();
+        assert_eq!(warm.statistics.status, SearchStatus::Optimal);
+        assert_eq!(warm.statistics.tree_error, error);
+        assert!(warm.statistics.counters.nodes_expanded <= nodes);
+
+        // Without time to search, the initial tree is returned
+        let mut stopped = anneal_dl85(&mut heuristic, 3);
+        stopped.constraints.max_time = 0;
+        stopped.set_initial_tree(initial_tree.clone());
+        stopped.fit(&mut structure).unwrap();
+        assert_eq!(stopped.statistics.status, SearchStatus::TimeLimit);
+        assert_eq!(stopped.statistics.tree_error, initial_error);
+        assert_eq!(
+            tree_error_on(&mut structure, &stopped.tree, 0),
+            initial_error
+        );
+    }
+
+    #[test]
+    fn run_dl85_with_too_deep_initial_tree() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+        let initial_tree = LGDT::fit(&mut structure, 1., 3, MurTree::fit);
+        let mut algo = anneal_dl85(&mut heuristic, 2);
+        algo.set_initial_tree(initial_tree);
+        assert_eq!(
+            algo.fit(&mut structure),
+            Err(PyTreesError::InvalidParameter(
+                "The initial tree does not satisfy the constraints of the search".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn run_dl85_top_trees_of_depth_one() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+
+        let mut expected = vec![Misclassification.leaf_error(&mut structure).0];
+        for feature in 0..structure.num_attributes() {
+            if structure.temp_push((feature, 0)) == 0. || structure.temp_push((feature, 1)) == 0. {
+                continue;
+            }
+            let mut error = 0.;
+            for value in 0..2 {
+                structure.push((feature, value));
+                error += Misclassification.leaf_error(&mut structure).0;
+                structure.backtrack();
+            }
+            expected.push(error);
+        }
+        expected.sort_by(|a, b| a.total_cmp(b));
+
+        let mut algo = anneal_dl85(&mut heuristic, 1);
+        algo.set_top_trees(1000).unwrap();
+        algo.fit(&mut structure).unwrap();
+        assert_eq!(algo.statistics.top_errors, expected);
+        assert_eq!(algo.top_trees.len(), expected.len());
+        assert_eq!(algo.statistics.top_errors[0], algo.statistics.tree_error);
+    }
+
+    #[test]
+    fn run_dl85_top_trees() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let (mut heuristic, mut other_heuristic) = (NoHeuristic, NoHeuristic);
+
+        let mut top = anneal_dl85(&mut heuristic, 2);
+        top.set_top_trees(20).unwrap();
+        top.fit(&mut structure).unwrap();
+        let errors = top.statistics.top_errors.clone();
+        assert_eq!(errors.len(), 20);
+        assert_eq!(errors[0], top.statistics.tree_error);
+        assert!(errors.windows(2).all(|pair| pair[0] <= pair[1]));
+        let mut trees = HashSet::new();
+        for (tree, error) in top.top_trees.iter().zip(errors.iter()) {
+            let root = tree.get_root_index();
+            assert_eq!(tree_error_on(&mut structure, tree, root), *error);
+            assert_eq!(tree.get_node(root).unwrap().value.error, *error);
+            assert!(trees.insert(serde_json::to_string(tree).unwrap()));
+        }
+
+        // The Rashomon set is the prefix of the best trees within the bound
+        let epsilon = errors[10] - errors[0];
+        let mut rashomon = anneal_dl85(&mut other_heuristic, 2);
+        rashomon.set_rashomon_bound(epsilon).unwrap();
+        rashomon.fit(&mut structure).unwrap();
+        let within = errors.iter().filter(|error| **error <= errors[0] + epsilon);
+        assert!(within.clone().count() < errors.len());
+        assert_eq!(
+            rashomon.statistics.top_errors,
+            within.copied().collect::<Vec<f64>>()
+        );
+    }
+
+    #[test]
+    fn run_dl85_top_trees_cancelled() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let (mut heuristic, mut other_heuristic) = (NoHeuristic, NoHeuristic);
+
+        // The polls of the token by the search alone
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counted = polls.clone();
+        let mut algo = anneal_dl85(&mut heuristic, 2);
+        algo.set_cancellation_token(CancellationToken::with_watcher(
+            move || counted.fetch_add(1, Ordering::Relaxed) == usize::MAX,
+            Duration::ZERO,
+        ));
+        algo.fit(&mut structure).unwrap();
+        let (search_polls, tree_error) =
+            (polls.load(Ordering::Relaxed), algo.statistics.tree_error);
+
+        // Cancelled at the first poll of the enumeration, the optimal tree stays proven
+        let polls = Arc::new(AtomicUsize::new(0));
+        let mut top = anneal_dl85(&mut other_heuristic, 2);
+        top.set_top_trees(20).unwrap();
+        top.set_cancellation_token(CancellationToken::with_watcher(
+            move || polls.fetch_add(1, Ordering::Relaxed) >= search_polls,
+            Duration::ZERO,
+        ));
+        top.fit(&mut structure).unwrap();
+        assert_eq!(top.statistics.status, SearchStatus::Cancelled);
+        assert_eq!(top.statistics.tree_error, tree_error);
+        assert_eq!(top.statistics.gap, 0.);
+        assert!(top.top_trees.is_empty());
+    }
+
+    #[test]
+    fn run_dl85_parallel() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = InformationGain;
+
+        // (specialization, leaf penalty, cover cache)
+        let configurations = [
+            (Specialization::None, 0., false),
+            (Specialization::Murtree, 0., false),
+            (Specialization::None, 2., false),
+            (Specialization::None, 0., true),
+        ];
+        for (specialization, leaf_penalty, cover_cache) in configurations {
+            let mut results = vec![];
+            for num_threads in [1, 4] {
+                let mut algo: DL85<'_, _, Data> = DL85::new(
+                    1.,
+                    3,
+                    <f64>::MAX,
+                    100,
+                    specialization,
+                    LowerBoundHeuristic::Similarity,
+                    BranchingType::Dynamic,
+                    CacheInit::Normal,
+                    0,
+                    false,
+                    &mut heuristic,
+                    Misclassification,
+                );
+                algo.set_leaf_penalty(leaf_penalty).unwrap();
+                algo.set_cover_cache(cover_cache);
+                algo.set_num_threads(num_threads);
+                algo.fit_parallel(&mut structure).unwrap();
+                assert_eq!(algo.statistics.status, SearchStatus::Optimal);
+                assert_eq!(algo.statistics.gap, 0.);
+                assert!(algo.statistics.counters.nodes_expanded > 0);
+                // The workers copy the subtrees the others settled instead of searching them again,
+                // the specialization settles the subtrees below the root children on its own
+                let shared = algo
+                    .statistics
+                    .counters
+                    .return_conditions
+                    .contains_key(&ReturnCondition::FromSharedCache);
+                assert_eq!(
+                    shared,
+                    num_threads > 1 && matches!(specialization, Specialization::None)
+                );
+
+                structure.reset();
+                let root = algo.tree.get_root_index();
+                let leaves = (0..algo.tree.len())
+                    .filter_map(|index| algo.tree.get_node(index))
+                    .filter(|node| node.left == node.right)
+                    .count() as f64;
+                assert_eq!(
+                    tree_error_on(&mut structure, &algo.tree, root) + leaf_penalty * leaves,
+                    algo.statistics.tree_error
+                );
+                results.push(algo.statistics.tree_error);
+            }
+            assert_eq!(results[0], results[1]);
+        }
+    }
+
+    #[test]
+    fn run_dl85_parallel_cancelled() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+
+        // Only the calling thread polls the watcher, the workers stop with it
+        let caller = std::thread::current().id();
+        let pollers = Arc::new(std::sync::Mutex::new(HashSet::new()));
+        let recorded_pollers = pollers.clone();
+        let mut algo = anneal_dl85(&mut heuristic, 3);
+        algo.set_num_threads(2);
+        algo.set_cancellation_token(CancellationToken::with_watcher(
+            move || {
+                recorded_pollers
+                    .lock()
+                    .unwrap()
+                    .insert(std::thread::current().id())
+            },
+            Duration::ZERO,
+        ));
+        algo.fit_parallel(&mut structure).unwrap();
+        assert_eq!(algo.statistics.status, SearchStatus::Cancelled);
+        assert_eq!(*pollers.lock().unwrap(), HashSet::from([caller]));
+        let root = algo.tree.get_root_index();
+        assert_eq!(
+            tree_error_on(&mut structure, &algo.tree, root),
+            algo.statistics.tree_error
+        );
+    }
+
+    #[test]
+    fn run_dl85_parallel_all_depths() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+        let mut algo = anneal_dl85(&mut heuristic, 3);
+        algo.set_all_depths(true);
+        algo.set_num_threads(2);
+        assert_eq!(
+            algo.fit_parallel(&mut structure),
+            Err(PyTreesError::Unsupported(
+                "The parallel search does not support searching all depths".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn run_dl85_parallel_progress_callback() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+        let mut algo = anneal_dl85(&mut heuristic, 3);
+        algo.set_progress_callback(Duration::ZERO, |_| {});
+        algo.set_num_threads(2);
+        assert_eq!(
+            algo.fit_parallel(&mut structure),
+            Err(PyTreesError::Unsupported(
+                "The parallel search does not support progress callbacks".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn run_dl85_parallel_memory_limit() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+        let mut algo = anneal_dl85(&mut heuristic, 3);
+        algo.set_memory_limit(1 << 20).unwrap();
+        algo.set_num_threads(2);
+        assert_eq!(
+            algo.fit_parallel(&mut structure),
+            Err(PyTreesError::Unsupported(
+                "The parallel search does not support the memory limit".to_string()
+            ))
+        );
+    }
+
+    fn sum_of_squared_errors(values: &[f64]) -> f64 {
+        if values.is_empty() {
+            return 0.;
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        values.iter().map(|value| (value - mean).powi(2)).sum()
+    }
+
+    #[test]
+    fn run_dl85_regression() {
+        let mut dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let targets = (0..dataset.train_size())
+            .map(|tid| (tid % 7) as f64 + 0.5)
+            .collect::<Vec<f64>>();
+        dataset.set_regression_targets(targets.clone());
+
+        // Brute force over the single splits of the dataset
+        let rows = &dataset.get_train().1;
+        let mut expected_error = sum_of_squared_errors(&targets);
+        for feature in 0..dataset.num_attributes() {
+            let mut split = [vec![], vec![]];
+            for (row, value) in rows.iter().zip(&targets) {
+                split[row[feature]].push(*value);
+            }
+            expected_error = expected_error
+                .min(sum_of_squared_errors(&split[0]) + sum_of_squared_errors(&split[1]));
+        }
+
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+        let mut algo: DL85<'_, _, Data> = DL85::new(
+            1.,
+            1,
+            <f64>::MAX,
             10,
             Specialization::None,
             LowerBoundHeuristic::None,
@@ -892,9 +3727,84 @@ mod dl85_test {
             0,
             false,
             heuristic.as_mut(),
-            None,
-            None,
+            Misclassification,
         );
-        algo.fit(&mut structure);
+        algo.fit(&mut structure).unwrap();
+
+        assert!((algo.statistics.tree_error - expected_error).abs() < 1e-6);
+        let tree = &algo.tree;
+        let root = tree.get_node(tree.get_root_index()).unwrap();
+        let left = tree.get_left_child(root).unwrap();
+        assert!(left.value.prediction.is_some());
+    }
+
+    #[test]
+    fn run_dl85_regression_turns_off_murtree() {
+        let mut dataset = BinaryDataset::load("test_data/small.txt", false, 0.0);
+        dataset.set_regression_targets(vec![1., 2., 3., 4.]);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut errors = vec![];
+        for (specialization, lower_bound) in [
+            (Specialization::None, LowerBoundHeuristic::None),
+            (Specialization::Murtree, LowerBoundHeuristic::Similarity),
+        ] {
+            let mut structure = RSparseBitsetStructure::new(&bitset_data);
+            let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                2,
+                <f64>::MAX,
+                10,
+                specialization,
+                lower_bound,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.fit(&mut structure).unwrap();
+            assert!(matches!(
+                algo.statistics.constraints.specialization,
+                Specialization::None
+            ));
+            assert!(matches!(
+                algo.statistics.constraints.lower_bound,
+                LowerBoundHeuristic::None
+            ));
+            errors.push(algo.statistics.tree_error);
+        }
+        assert!((errors[0] - errors[1]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn run_dl85_multiclass() {
+        let dataset = BinaryDataset::load("test_data/multiclass.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+
+        let mut heuristic: Box<dyn Heuristic> = Box::<NoHeuristic>::default();
+
+        for (depth, expected_error) in [(1, 3.), (2, 0.)] {
+            let mut algo: DL85<'_, _, Data> = DL85::new(
+                1.,
+                depth,
+                <f64>::MAX,
+                10,
+                Specialization::None,
+                LowerBoundHeuristic::None,
+                BranchingType::Dynamic,
+                CacheInit::Normal,
+                0,
+                false,
+                heuristic.as_mut(),
+                Misclassification,
+            );
+            algo.fit(&mut structure).unwrap();
+            assert_eq!(algo.statistics.tree_error, expected_error);
+            assert_eq!(algo.statistics.train_distribution, vec![3., 3., 6.]);
+            structure.reset();
+        }
     }
 }
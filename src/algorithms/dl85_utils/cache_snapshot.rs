@@ -0,0 +1,223 @@
+use crate::algorithms::dl85::{BUDGET_ATTRIBUTE, DEPTH_ATTRIBUTE, DL85};
+use crate::algorithms::dl85_utils::leaf_objective::LeafObjective;
+use crate::algorithms::dl85_utils::structs_enums::LowerBoundHeuristic;
+use crate::errors::{PyTreesError, PyTreesResult};
+use crate::heuristics::Heuristic;
+use crate::structures::caching::cache_trait::Cache;
+use crate::structures::caching::trie::DataTrait;
+use crate::structures::structure_trait::Structure;
+use crate::structures::structures_types::{mix, CostMatrix, Item, Support};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+/// Cache entries of a finished DL85 search, to warm start another search on the same dataset.
+/// Each key is the itemset, then the remaining depth, then the leaves budget if any, so the
+/// entries also fit searches of other maximum depths.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSnapshot<T> {
+    pub(crate) fingerprint: (u64, u64),
+    pub(crate) num_samples: Support,
+    pub(crate) num_attributes: usize,
+    pub(crate) objective: String,
+    pub(crate) min_sup: Support,
+    pub(crate) leaf_penalty: f64,
+    pub(crate) cost_matrix: Option<CostMatrix>,
+    pub(crate) entries: Vec<(Vec<Item>, T)>,
+}
+
+impl<T> CacheSnapshot<T> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> CacheSnapshot<T> {
+    pub fn save(&self, filename: &str) -> std::io::Result<()> {
+        let writer = BufWriter::new(File::create(filename)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(filename: &str) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(filename)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+/// Hash of the covers and class supports of every item, the leaf errors of a cache only hold
+/// on a dataset with the same fingerprint. Structure is left where it was.
+pub(crate) fn dataset_fingerprint<S: Structure>(structure: &mut S) -> (u64, u64) {
+    let mut fingerprint = structure.cover_hash();
+    for attribute in 0..structure.num_attributes() {
+        let _ = structure.push((attribute, 1));
+        let (first, second) = structure.cover_hash();
+        let mut supports = mix(attribute as u64);
+        for support in structure.labels_support().iter() {
+            supports = mix(supports ^ support.to_bits());
+        }
+        if let Some((count, sum, squares)) = structure.regression_statistics() {
+            supports =
+                mix(supports ^ count.to_bits() ^ sum.to_bits() ^ squares.to_bits().rotate_left(1));
+        }
+        structure.backtrack();
+        fingerprint.0 = mix(fingerprint.0 ^ first ^ supports);
+        fingerprint.1 = mix(fingerprint.1 ^ second);
+    }
+    fingerprint
+}
+
+impl<'heur, H, T, O> DL85<'heur, H, T, O>
+where
+    H: Heuristic + ?Sized,
+    T: DataTrait + Default + Debug,
+    O: LeafObjective,
+{
+    // Adds the entries of a previous search with the remaining depths of the current one or less,
+    // the entries are keyed by their remaining depth when some are shallower than the search
+    pub(crate) fn load_snapshot<S: Structure>(
+        &mut self,
+        snapshot: CacheSnapshot<T>,
+        structure: &mut S,
+    ) -> PyTreesResult<()> {
+        if snapshot.fingerprint != dataset_fingerprint(structure)
+            || snapshot.num_samples != self.statistics.num_samples
+            || snapshot.num_attributes != self.statistics.num_attributes
+        {
+            return Err(PyTreesError::InvalidParameter(
+                "The warm start comes from another dataset".to_string(),
+            ));
+        }
+        if snapshot.objective != std::any::type_name::<O>()
+            || snapshot.min_sup != self.constraints.min_sup
+            || snapshot.leaf_penalty != self.constraints.leaf_penalty
+            || snapshot.cost_matrix != self.constraints.cost_matrix
+        {
+            return Err(PyTreesError::InvalidParameter(
+                "The warm start comes from a search with other leaf errors".to_string(),
+            ));
+        }
+
+        let max_depth = self.constraints.max_depth;
+        let snapshot_entries = snapshot.entries.len();
+        let mut entries = vec![];
+        for (key, value) in snapshot.entries {
+            let length = key.partition_point(|item| item.0 < DEPTH_ATTRIBUTE);
+            match key.get(length) {
+                Some(item) if length <= max_depth && item.1 <= max_depth - length => {
+                    self.depth_keyed |= item.1 < max_depth - length;
+                    entries.push((length, key, value));
+                }
+                _ => {}
+            }
+        }
+        if entries.is_empty() && snapshot_entries > 0 {
+            return Err(PyTreesError::InvalidParameter(
+                "The warm start has no entry for the depths of the search".to_string(),
+            ));
+        }
+
+        for (length, key, value) in entries {
+            let (itemset, tail) = key.split_at(length);
+            let depth_item = tail.first().filter(|_| self.depth_keyed);
+            let (_, index) = self
+                .cache
+                .find_or_create(itemset.iter().chain(depth_item).chain(tail.get(1)));
+            if let Some(node) = self.cache.get_node_mut(index) {
+                node.value = value;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'heur, H, T, O> DL85<'heur, H, T, O>
+where
+    H: Heuristic + ?Sized,
+    T: DataTrait + Default + Debug + Clone + Serialize + DeserializeOwned,
+    O: LeafObjective,
+{
+    /// Entries of the last `fit`, keyed by their remaining depth so that a search of another
+    /// maximum depth can start from them. The entries an interruption left unfinished are dropped,
+    /// as are the entries without a solution and the entries of a leaves budget when their lower
+    /// bound may come from the similarity bound, which only holds within the search that computed
+    /// it. `structure` is the one the last `fit` ran on, it tells the datasets apart.
+    pub fn cache_snapshot<S: Structure>(
+        &self,
+        structure: &mut S,
+    ) -> PyTreesResult<CacheSnapshot<T>> {
+        if self.constraints.cover_cache {
+            return Err(PyTreesError::Unsupported(
+                "The cover cache does not support warm starts".to_string(),
+            ));
+        }
+        if self.constraints.num_threads > 1 {
+            return Err(PyTreesError::Unsupported(
+                "The parallel search does not support warm starts".to_string(),
+            ));
+        }
+        let unsettled = self.unsettled.iter().collect::<HashSet<_>>();
+        let similarity = matches!(
+            self.constraints.lower_bound,
+            LowerBoundHeuristic::Similarity
+        );
+        let mut entries = vec![];
+        for (index, node) in (0..)
+            .map_while(|index| self.cache.get_node(index))
+            .enumerate()
+        {
+            if !node.is_entry
+                || unsettled.contains(&index)
+                || (similarity && node.value.get_node_error() == <f64>::MAX)
+            {
+                continue;
+            }
+            let (itemset, leaves) = self.entry_key(index);
+            if similarity && leaves < <usize>::MAX {
+                continue;
+            }
+            let remaining_depth = match self
+                .cache
+                .path(index)
+                .iter()
+                .find(|item| item.0 == DEPTH_ATTRIBUTE)
+            {
+                Some(item) => item.1,
+                None => self.constraints.max_depth - itemset.len(),
+            };
+            let mut key = itemset.into_iter().collect::<Vec<Item>>();
+            key.push((DEPTH_ATTRIBUTE, remaining_depth));
+            if leaves < <usize>::MAX {
+                key.push((BUDGET_ATTRIBUTE, leaves));
+            }
+            entries.push((key, node.value.clone()));
+        }
+        Ok(CacheSnapshot {
+            fingerprint: dataset_fingerprint(structure),
+            num_samples: self.statistics.num_samples,
+            num_attributes: self.statistics.num_attributes,
+            objective: std::any::type_name::<O>().to_string(),
+            min_sup: self.constraints.min_sup,
+            leaf_penalty: self.constraints.leaf_penalty,
+            cost_matrix: self.constraints.cost_matrix.clone(),
+            entries,
+        })
+    }
+
+    /// Saves the entries of the last `fit` to `filename`, see `cache_snapshot`.
+    pub fn save_cache<S: Structure>(&self, structure: &mut S, filename: &str) -> PyTreesResult<()> {
+        Ok(self.cache_snapshot(structure)?.save(filename)?)
+    }
+
+    /// Warm starts the next `fit` from the entries saved in `filename`, see `set_warm_start`.
+    pub fn load_cache(&mut self, filename: &str) -> PyTreesResult<()> {
+        self.set_warm_start(CacheSnapshot::load(filename)?)
+    }
+}
@@ -0,0 +1,271 @@
+use crate::algorithms::cancellation::CancellationToken;
+use crate::algorithms::dl85_utils::structs_enums::PythonFunctionData;
+use crate::errors::{PyTreesError, PyTreesResult};
+use crate::structures::structure_trait::Structure;
+use crate::structures::structures_types::{CostMatrix, Support};
+use pyo3::{IntoPy, PyErr, PyObject, Python};
+use std::sync::Mutex;
+
+/// Error of the current cover of a structure seen as a leaf, and the class the leaf predicts,
+/// the first one when classes tie.
+/// The optimal engines minimise the sum of this error over the leaves of the tree.
+pub trait LeafObjective {
+    fn leaf_error<S: Structure>(&self, structure: &mut S) -> (f64, usize);
+
+    /// The Murtree specialization and the similarity lower bound assume the misclassification
+    /// error, other objectives cannot use them.
+    fn is_misclassification(&self) -> bool {
+        false
+    }
+
+    /// Fails when the objective does not apply to `num_labels` classes, `fit` checks it before
+    /// searching.
+    fn check_labels(&self, _num_labels: usize) -> PyTreesResult<()> {
+        Ok(())
+    }
+}
+
+// The workers of the parallel search share the objective of the main search
+impl<O: LeafObjective + ?Sized> LeafObjective for &O {
+    fn leaf_error<S: Structure>(&self, structure: &mut S) -> (f64, usize) {
+        (**self).leaf_error(structure)
+    }
+
+    fn is_misclassification(&self) -> bool {
+        (**self).is_misclassification()
+    }
+
+    fn check_labels(&self, num_labels: usize) -> PyTreesResult<()> {
+        (**self).check_labels(num_labels)
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Misclassification;
+
+impl LeafObjective for Misclassification {
+    fn leaf_error<S: Structure>(&self, structure: &mut S) -> (f64, usize) {
+        let mut max_idx = 0;
+        let mut max_value = 0.;
+        let mut total = 0.;
+        for (idx, value) in structure.labels_support().iter().enumerate() {
+            total += value;
+            if *value > max_value {
+                max_value = *value;
+                max_idx = idx;
+            }
+        }
+        (total - max_value, max_idx)
+    }
+
+    fn is_misclassification(&self) -> bool {
+        true
+    }
+}
+
+/// Misclassifying a transaction of class `k` costs `costs[k]`.
+#[derive(Clone)]
+pub struct WeightedCost {
+    costs: Vec<f64>,
+}
+
+impl WeightedCost {
+    pub fn new(costs: Vec<f64>) -> PyTreesResult<Self> {
+        if costs.iter().any(|cost| *cost < 0.) {
+            return Err(PyTreesError::InvalidParameter(
+                "Misclassification costs must be non-negative".to_string(),
+            ));
+        }
+        Ok(Self { costs })
+    }
+}
+
+impl LeafObjective for WeightedCost {
+    fn leaf_error<S: Structure>(&self, structure: &mut S) -> (f64, usize) {
+        let supports = structure.labels_support();
+        let total = supports
+            .iter()
+            .zip(&self.costs)
+            .map(|(support, cost)| support * cost)
+            .sum::<f64>();
+
+        let mut best = (<f64>::MAX, 0);
+        for (class, (support, cost)) in supports.iter().zip(&self.costs).enumerate() {
+            let error = total - support * cost;
+            if error < best.0 {
+                best = (error, class);
+            }
+        }
+        best
+    }
+
+    fn check_labels(&self, num_labels: usize) -> PyTreesResult<()> {
+        match self.costs.len() == num_labels {
+            true => Ok(()),
+            false => Err(PyTreesError::InvalidParameter(
+                "One misclassification cost per class is required".to_string(),
+            )),
+        }
+    }
+}
+
+/// Expected cost of the class minimising it, predicting `c` costs `costs[k][c]` for each
+/// transaction of class `k`.
+pub fn expected_cost(classes_support: &[Support], costs: &CostMatrix) -> (f64, usize) {
+    let mut best = (<f64>::MAX, 0);
+    for class in 0..costs.len() {
+        let error = classes_support
+            .iter()
+            .zip(costs)
+            .map(|(support, row)| support * row[class])
+            .sum::<f64>();
+        if error < best.0 {
+            best = (error, class);
+        }
+    }
+    best
+}
+
+/// Support times the entropy of the class distribution, the leaf predicts the majority class.
+#[derive(Default, Clone, Copy)]
+pub struct Entropy;
+
+impl LeafObjective for Entropy {
+    fn leaf_error<S: Structure>(&self, structure: &mut S) -> (f64, usize) {
+        let supports = structure.labels_support();
+        let total = supports.iter().sum::<Support>();
+        let mut error = 0.;
+        let mut max_idx = 0;
+        let mut max_value = 0.;
+        for (idx, value) in supports.iter().enumerate() {
+            if *value > 0. {
+                error -= value * (value / total).log2();
+            }
+            if *value > max_value {
+                max_value = *value;
+                max_idx = idx;
+            }
+        }
+        (error.max(0.), max_idx)
+    }
+}
+
+/// Adapter for a Python function returning `(error, class)` from the class supports or the tids.
+/// The first exception raised by the function cancels `cancellation`, the token of the search, and
+/// stops the calls. The leaves then get the maximum error and the exception is kept for the caller.
+pub struct PythonObjective {
+    function: PyObject,
+    data: PythonFunctionData,
+    cancellation: CancellationToken,
+    error: Mutex<Option<PyErr>>,
+}
+
+impl PythonObjective {
+    pub fn new(
+        function: PyObject,
+        data: PythonFunctionData,
+        cancellation: CancellationToken,
+    ) -> Self {
+        Self {
+            function,
+            data,
+            cancellation,
+            error: Mutex::new(None),
+        }
+    }
+
+    pub fn take_error(&self) -> Option<PyErr> {
+        self.error.lock().ok().and_then(|mut error| error.take())
+    }
+}
+
+impl LeafObjective for PythonObjective {
+    fn leaf_error<S: Structure>(&self, structure: &mut S) -> (f64, usize) {
+        let mut error = match self.error.lock() {
+            Ok(error) => error,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if error.is_some() {
+            return (<f64>::MAX, 0);
+        }
+
+        Python::with_gil(|py| {
+            let input = match self.data {
+                PythonFunctionData::ClassSupports => {
+                    structure.labels_support().to_vec().into_py(py)
+                }
+                PythonFunctionData::Tids => structure.get_tids().into_py(py),
+            };
+            match self
+                .function
+                .call1(py, (input,))
+                .and_then(|result| result.extract::<(f64, usize)>(py))
+            {
+                Ok(value) => value,
+                Err(err) => {
+                    *error = Some(err);
+                    self.cancellation.cancel();
+                    (<f64>::MAX, 0)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod leaf_objective_test {
+    use crate::algorithms::dl85_utils::leaf_objective::{
+        expected_cost, Entropy, LeafObjective, Misclassification, WeightedCost,
+    };
+    use crate::dataset::binary_dataset::BinaryDataset;
+    use crate::dataset::data_trait::Dataset;
+    use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
+
+    #[test]
+    fn builtin_objectives() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        // Root distribution is [187, 625]
+
+        assert_eq!(Misclassification.leaf_error(&mut structure), (187., 1));
+
+        let costs = WeightedCost::new(vec![4., 1.]).unwrap();
+        assert_eq!(costs.leaf_error(&mut structure), (625., 0));
+        assert!(costs.check_labels(2).is_ok());
+        assert!(costs.check_labels(3).is_err());
+        assert!(WeightedCost::new(vec![-1., 1.]).is_err());
+
+        let (error, class) = Entropy.leaf_error(&mut structure);
+        let expected = -187. * (187f64 / 812.).log2() - 625. * (625f64 / 812.).log2();
+        assert!((error - expected).abs() < 1e-9);
+        assert_eq!(class, 1);
+    }
+
+    #[test]
+    fn ties_go_to_the_first_class() {
+        let dataset = BinaryDataset::load("test_data/small.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        // Root distribution is [2, 2]
+
+        assert_eq!(Misclassification.leaf_error(&mut structure), (2., 0));
+        assert_eq!(Entropy.leaf_error(&mut structure).1, 0);
+        let costs = WeightedCost::new(vec![1., 1.]).unwrap();
+        assert_eq!(costs.leaf_error(&mut structure), (2., 0));
+        assert_eq!(
+            expected_cost(&[2., 2.], &vec![vec![0., 1.], vec![1., 0.]]),
+            (2., 0)
+        );
+    }
+
+    #[test]
+    fn expected_cost_of_a_leaf() {
+        let costs = vec![vec![0., 10.], vec![1., 0.]];
+        assert_eq!(expected_cost(&[187., 625.], &costs), (625., 0));
+        assert_eq!(expected_cost(&[10., 625.], &costs), (100., 1));
+
+        let unit_costs = vec![vec![0., 1.], vec![1., 0.]];
+        assert_eq!(expected_cost(&[187., 625.], &unit_costs), (187., 1));
+    }
+}
@@ -1,3 +1,8 @@
+pub mod cache_snapshot;
+pub mod leaf_objective;
+pub mod parallel_search;
+pub mod shared_cache;
 pub mod slb;
 pub mod stop_conditions;
 pub mod structs_enums;
+pub mod top_trees;
@@ -0,0 +1,244 @@
+use crate::algorithms::cancellation::run_on_pool;
+use crate::algorithms::dl85::DL85;
+use crate::algorithms::dl85_utils::leaf_objective::LeafObjective;
+use crate::algorithms::dl85_utils::shared_cache::{SharedCache, SharedSolution};
+use crate::algorithms::dl85_utils::structs_enums::{
+    HasIntersected, ReturnCondition, SearchCounters, SearchStatus, Specialization,
+};
+use crate::errors::{PyTreesError, PyTreesResult};
+use crate::heuristics::Heuristic;
+use crate::structures::caching::cache_trait::Cache;
+use crate::structures::caching::trie::DataTrait;
+use crate::structures::structure_trait::Structure;
+use crate::structures::structures_types::{Attribute, Item};
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+impl<'heur, H, T, O> DL85<'heur, H, T, O>
+where
+    H: Heuristic + ?Sized,
+    T: DataTrait + Default + Debug,
+    O: LeafObjective,
+{
+    // Best root error found by the workers of the parallel search
+    pub(crate) fn shared_upper_bound(&self) -> f64 {
+        match &self.shared_bound {
+            Some(bound) => f64::from_bits(bound.load(Ordering::Relaxed)),
+            None => <f64>::MAX,
+        }
+    }
+
+    // The bits of non negative floats sort as the floats do
+    pub(crate) fn share_upper_bound(&self, error: f64) {
+        if let Some(bound) = &self.shared_bound {
+            bound.fetch_min(error.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    // Solution another worker of the parallel search settled for a new entry, copied with its
+    // subtree. Nothing is copied unless the whole subtree was shared.
+    pub(crate) fn shared_solution(
+        &mut self,
+        itemset: &BTreeSet<Item>,
+        is_new: bool,
+    ) -> Option<(f64, ReturnCondition, HasIntersected)> {
+        let shared = match (&self.shared_cache, is_new) {
+            (Some(shared), true) => shared.clone(),
+            _ => return None,
+        };
+        let mut solutions = vec![];
+        let mut pending = vec![itemset.clone()];
+        while let Some(itemset) = pending.pop() {
+            let solution = shared.get(&itemset.iter().copied().collect::<Vec<Item>>())?;
+            if !solution.is_leaf {
+                for branch in 0..2 {
+                    let mut child = itemset.clone();
+                    child.insert((solution.test, branch));
+                    pending.push(child);
+                }
+            }
+            solutions.push((itemset, solution));
+        }
+        let error = solutions[0].1.error;
+        for (itemset, solution) in solutions {
+            let (_, index) = self.find_or_create_entry(&itemset, None);
+            if let Some(node) = self.cache.get_node_mut(index) {
+                solution.write_to(&mut node.value);
+            }
+        }
+        Some((error, ReturnCondition::FromSharedCache, HasIntersected::Yes))
+    }
+
+    // Shares the solution the entry of the itemset settled on with the other workers
+    pub(crate) fn share_solution(&self, itemset: &mut BTreeSet<Item>, error: f64) {
+        if let Some(shared) = &self.shared_cache {
+            if self.interruption.is_none() && error < <f64>::MAX {
+                self.share_subtree(shared, itemset);
+            }
+        }
+    }
+
+    // The children are shared first, so that a shared entry always comes with its subtree. False
+    // when part of the subtree is not in the cache, the copies of the cover cache for instance.
+    fn share_subtree(&self, shared: &SharedCache, itemset: &mut BTreeSet<Item>) -> bool {
+        let key = itemset.iter().copied().collect::<Vec<Item>>();
+        if shared.contains(&key) {
+            return true;
+        }
+        let solution = match self
+            .find_entry(itemset, None)
+            .and_then(|index| self.cache.get_node(index))
+        {
+            Some(node) if node.value.get_node_error() < <f64>::MAX => {
+                SharedSolution::from_data(&node.value)
+            }
+            _ => return false,
+        };
+        if !solution.is_leaf {
+            if solution.test == Attribute::MAX {
+                return false;
+            }
+            for branch in 0..2 {
+                itemset.insert((solution.test, branch));
+                let complete = self.share_subtree(shared, itemset);
+                itemset.remove(&(solution.test, branch));
+                if !complete {
+                    return false;
+                }
+            }
+        }
+        shared.insert(key, solution);
+        true
+    }
+}
+
+impl<'heur, H, T, O> DL85<'heur, H, T, O>
+where
+    H: Heuristic + Sync + ?Sized,
+    T: DataTrait + Default + Debug,
+    O: LeafObjective + Sync,
+{
+    /// `fit` with the root candidates dealt between `num_threads` workers on a rayon pool, in the
+    /// order of the heuristic. Each worker searches its candidates with its own cache, all of them
+    /// share the best root error and a sharded cache of the subtrees they settled, the best of
+    /// their trees is kept. Searching all depths, the leaves budget, the anytime mode, the progress
+    /// callback, the warm starts and the top trees are not supported. The calling thread checks the
+    /// cancellation token while the workers run and stops them with it.
+    pub fn fit_parallel<S>(&mut self, structure: &mut S) -> PyTreesResult<()>
+    where
+        S: Structure + Clone + Send,
+    {
+        let num_threads = self.constraints.num_threads;
+        let specialized_root = matches!(self.constraints.specialization, Specialization::Murtree)
+            && self.constraints.max_depth <= 2;
+        if num_threads <= 1 || self.constraints.max_depth == 0 || specialized_root {
+            return self.fit(structure);
+        }
+        let unsupported = if self.constraints.all_depths {
+            Some("searching all depths")
+        } else if self.constraints.max_leaves < <usize>::MAX {
+            Some("the maximum number of leaves")
+        } else if self.constraints.anytime {
+            Some("the anytime mode")
+        } else if self.progress_callback.is_some() {
+            Some("progress callbacks")
+        } else if self.constraints.memory_limit < <usize>::MAX {
+            Some("the memory limit")
+        } else if self.warm_start.is_some() {
+            Some("warm starts")
+        } else if self.constraints.top_trees > 0 {
+            Some("the top trees")
+        } else {
+            None
+        };
+        if let Some(option) = unsupported {
+            return Err(PyTreesError::Unsupported(format!(
+                "The parallel search does not support {option}"
+            )));
+        }
+
+        // BEGIN STEP: Deal the root candidates between the workers
+        let run_time = Instant::now();
+        let candidates = self.root_candidates(structure);
+        let jobs = (0..num_threads.min(candidates.len()).max(1))
+            .map(|worker| {
+                let share = candidates
+                    .iter()
+                    .skip(worker)
+                    .step_by(num_threads)
+                    .copied()
+                    .collect::<Vec<Attribute>>();
+                (share, structure.clone())
+            })
+            .collect::<Vec<_>>();
+        // END STEP: Deal the root candidates between the workers
+
+        // BEGIN STEP: Run the workers
+        let bound = Arc::new(AtomicU64::new(self.constraints.max_error.to_bits()));
+        let shared_cache = Arc::new(SharedCache::new());
+        let (constraints, heuristic, objective) =
+            (&self.constraints, self.heuristic, &self.objective);
+        let initial_tree = &self.initial_tree;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|error| PyTreesError::Search(format!("Cannot start the workers: {error}")))?;
+        let workers = run_on_pool(
+            &pool,
+            jobs,
+            self.cancellation.as_ref(),
+            |(share, mut structure), token| {
+                let mut worker: DL85<'_, H, T, &O> =
+                    DL85::with_constraints(constraints.clone(), heuristic, objective);
+                worker.initial_tree = initial_tree.clone();
+                worker.cancellation = Some(token.clone());
+                worker.root_share = share;
+                worker.shared_bound = Some(bound.clone());
+                worker.shared_cache = Some(shared_cache.clone());
+                worker.fit(&mut structure)?;
+                Ok((worker.tree, worker.statistics))
+            },
+        )
+        .into_iter()
+        .collect::<PyTreesResult<Vec<_>>>()?;
+        // END STEP: Run the workers
+
+        // BEGIN STEP: Keep the best tree and add up the work of the workers
+        let mut best = 0;
+        let mut counters = SearchCounters::default();
+        let (mut cache_size, mut lower_bound, mut interruption) = (0, <f64>::MAX, None);
+        for (index, (_, statistics)) in workers.iter().enumerate() {
+            if statistics.tree_error < workers[best].1.tree_error {
+                best = index;
+            }
+            counters.merge(&statistics.counters);
+            cache_size += statistics.cache_size;
+            lower_bound = lower_bound.min(statistics.lower_bound);
+            if !matches!(
+                statistics.status,
+                SearchStatus::Optimal | SearchStatus::ErrorLimit
+            ) {
+                interruption.get_or_insert(statistics.status);
+            }
+        }
+        let (tree, mut statistics) = workers.into_iter().nth(best).unwrap();
+        let error = statistics.tree_error;
+        statistics.status = interruption.unwrap_or(statistics.status);
+        statistics.lower_bound = lower_bound.min(error);
+        statistics.gap = match error < <f64>::MAX {
+            true => error - statistics.lower_bound,
+            false => <f64>::MAX,
+        };
+        statistics.counters = counters;
+        statistics.cache_size = cache_size;
+        statistics.duration = run_time.elapsed();
+        self.tree = tree;
+        self.depth_trees = vec![];
+        self.statistics = statistics;
+        // END STEP: Keep the best tree and add up the work of the workers
+        Ok(())
+    }
+}
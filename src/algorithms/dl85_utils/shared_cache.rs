@@ -0,0 +1,92 @@
+use crate::structures::caching::trie::DataTrait;
+use crate::structures::structures_types::{Attribute, Item};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+const NUM_SHARDS: usize = 64;
+
+/// Solution of a cache entry, what another worker needs to rebuild it without searching.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SharedSolution {
+    pub(crate) error: f64,
+    pub(crate) leaf_error: f64,
+    pub(crate) test: Attribute,
+    pub(crate) class: usize,
+    pub(crate) prediction: Option<f64>,
+    pub(crate) is_leaf: bool,
+}
+
+impl SharedSolution {
+    pub(crate) fn from_data<T: DataTrait>(data: &T) -> Self {
+        Self {
+            error: data.get_node_error(),
+            leaf_error: data.get_leaf_error(),
+            test: data.get_test(),
+            class: data.get_class(),
+            prediction: data.get_prediction(),
+            is_leaf: data.is_leaf(),
+        }
+    }
+
+    // The solution is optimal, so its error is also the lower bound of the entry
+    pub(crate) fn write_to<T: DataTrait>(&self, data: &mut T) {
+        data.set_leaf_error(self.leaf_error);
+        data.set_class(self.class);
+        if let Some(prediction) = self.prediction {
+            data.set_prediction(prediction);
+        }
+        match self.is_leaf {
+            true => data.set_as_leaf(),
+            false => {
+                data.set_node_error(self.error);
+                data.set_test(self.test);
+            }
+        }
+        data.set_lower_bound(self.error);
+    }
+}
+
+/// Solutions the workers of the parallel search settled, keyed by itemset. The itemsets are
+/// spread over shards with their own lock so that the workers rarely wait for each other.
+#[derive(Debug)]
+pub(crate) struct SharedCache {
+    shards: Vec<Mutex<HashMap<Vec<Item>, SharedSolution>>>,
+}
+
+impl SharedCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            shards: (0..NUM_SHARDS)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, itemset: &[Item]) -> &Mutex<HashMap<Vec<Item>, SharedSolution>> {
+        let mut hasher = DefaultHasher::new();
+        itemset.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % NUM_SHARDS]
+    }
+
+    pub(crate) fn get(&self, itemset: &[Item]) -> Option<SharedSolution> {
+        match self.shard(itemset).lock() {
+            Ok(shard) => shard.get(itemset).copied(),
+            Err(_) => None,
+        }
+    }
+
+    pub(crate) fn contains(&self, itemset: &[Item]) -> bool {
+        match self.shard(itemset).lock() {
+            Ok(shard) => shard.contains_key(itemset),
+            Err(_) => false,
+        }
+    }
+
+    pub(crate) fn insert(&self, itemset: Vec<Item>, solution: SharedSolution) {
+        if let Ok(mut shard) = self.shard(&itemset).lock() {
+            shard.entry(itemset).or_insert(solution);
+        }
+    }
+}
@@ -1,10 +1,6 @@
-use crate::dataset::data_trait::Dataset;
-use crate::structures::bitsets_structure::BitsetStructure;
-use crate::structures::caching::trie::{Data, DataTrait};
-use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
+use crate::structures::caching::trie::DataTrait;
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::{Bitset, BitsetStructData, Support};
-use std::cmp::max;
+use crate::structures::structures_types::{Bitset, Support};
 use std::fmt::Debug;
 
 #[derive(Debug, Default)]
@@ -24,13 +20,16 @@ where
         }
     }
 
-    pub fn update(&mut self, data: &T, structure: &mut RSparseBitsetStructure) -> bool {
-        let error = match data.get_node_error() == <usize>::MAX {
+    pub fn update<S>(&mut self, data: &T, structure: &mut S) -> bool
+    where
+        S: Structure,
+    {
+        let error = match data.get_node_error() == <f64>::MAX {
             true => data.get_lower_bound(),
             false => data.get_node_error(),
         };
 
-        if error == 0 {
+        if error == 0. {
             return false;
         }
 
@@ -53,7 +52,10 @@ where
         self.first.is_empty() && self.second.is_empty()
     }
 
-    fn set_empty(&mut self, data: &T, structure: &mut RSparseBitsetStructure) -> bool {
+    fn set_empty<S>(&mut self, data: &T, structure: &mut S) -> bool
+    where
+        S: Structure,
+    {
         if self.first.is_empty() {
             self.first.update(structure, data);
             return true;
@@ -64,13 +66,17 @@ where
         false
     }
 
-    pub fn compute_similarity(&mut self, structure: &mut RSparseBitsetStructure) -> usize {
-        let mut bound = 0;
+    // A transaction missing from a saved cover lowers its error by at most `max_cost`
+    pub fn compute_similarity<S>(&mut self, structure: &mut S, max_cost: f64) -> f64
+    where
+        S: Structure,
+    {
+        let mut bound = 0f64;
         let saved = [&self.first, &self.second];
         for similarity in saved {
             if !similarity.is_empty() {
-                let diff = structure.difference(similarity, false);
-                bound = max(bound, similarity.error.saturating_sub(diff));
+                let diff = structure.cover_difference(&similarity.state, false);
+                bound = bound.max((similarity.error - diff * max_cost).max(0.));
             }
         }
         bound
@@ -80,10 +86,8 @@ where
 #[derive(Default, Debug)]
 pub struct Similarity<T> {
     pub state: Bitset,
-    pub limit: isize,
-    pub index: Vec<usize>,
-    pub error: usize,
-    pub support: usize,
+    pub error: f64,
+    pub support: Support,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -94,19 +98,22 @@ where
     pub fn new() -> Self {
         Self {
             state: Bitset::new(),
-            index: vec![],
-            limit: 0,
-            error: 0,
-            support: 0,
+            error: 0.,
+            support: 0.,
             _phantom: Default::default(),
         }
     }
 
-    pub fn update(&mut self, structure: &mut RSparseBitsetStructure, data: &T) {
-        self.state = structure.get_last_state_bitset();
-        self.index = structure.get_current_index();
-        self.limit = structure.get_current_limit();
-        self.error = data.get_node_error();
+    pub fn update<S>(&mut self, structure: &mut S, data: &T)
+    where
+        S: Structure,
+    {
+        self.state = structure.get_cover();
+        // An unsolved entry only knows that its error reaches its lower bound
+        self.error = match data.get_node_error() == <f64>::MAX {
+            true => data.get_lower_bound(),
+            false => data.get_node_error(),
+        };
         self.support = structure.get_support();
     }
 
@@ -114,10 +121,13 @@ where
         self.state.is_empty()
     }
 
-    pub fn difference(&self, structure: &mut RSparseBitsetStructure) -> (usize, usize) {
+    pub fn difference<S>(&self, structure: &mut S) -> (Support, Support)
+    where
+        S: Structure,
+    {
         let (in_cout, out_count) = (
-            structure.difference(self, true),
-            structure.difference(self, false),
+            structure.cover_difference(&self.state, true),
+            structure.cover_difference(&self.state, false),
         );
         (in_cout, out_count)
     }
@@ -130,6 +140,7 @@ mod slb_tests {
     use crate::dataset::binary_dataset::BinaryDataset;
     use crate::dataset::data_trait::Dataset;
     use crate::structures::caching::trie::{Data, DataTrait};
+    use crate::structures::horizontal_binary_structure::HorizontalBinaryStructure;
     use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
     use crate::structures::structure_trait::Structure;
 
@@ -143,7 +154,7 @@ mod slb_tests {
         assert_eq!(slb.is_empty(), true);
 
         let mut data = Data::new();
-        data.error = 1;
+        data.error = 1.;
 
         structure.push((0, 0));
         slb.update(&data, &mut structure);
@@ -152,10 +163,33 @@ mod slb_tests {
 
         structure.push((1, 0));
 
-        let elem_in = structure.difference(&slb.first, true);
-        let elem_out = structure.difference(&slb.first, false);
+        let elem_in = structure.cover_difference(&slb.first.state, true);
+        let elem_out = structure.cover_difference(&slb.first.state, false);
+
+        assert_eq!(elem_in, 812.);
+        assert_eq!(elem_out, 0.);
+    }
+
+    #[test]
+    fn test_slb_default_cover() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let horizontal_data = HorizontalBinaryStructure::format_input_data(&dataset);
+        let mut structure = HorizontalBinaryStructure::new(&horizontal_data);
+
+        let mut slb: SimilarDatasets<Data> = SimilarDatasets::new();
+        let mut data = Data::new();
+        data.error = 1.;
+
+        structure.push((0, 0));
+        slb.update(&data, &mut structure);
+        structure.backtrack();
+
+        structure.push((1, 0));
+
+        let elem_in = structure.cover_difference(&slb.first.state, true);
+        let elem_out = structure.cover_difference(&slb.first.state, false);
 
-        assert_eq!(elem_in, 812);
-        assert_eq!(elem_out, 0);
+        assert_eq!(elem_in, 812.);
+        assert_eq!(elem_out, 0.);
     }
 }
@@ -1,9 +1,18 @@
-use crate::algorithms::dl85_utils::structs_enums::ReturnCondition;
+use crate::algorithms::dl85_utils::structs_enums::{Constraints, ReturnCondition};
 use crate::structures::caching::trie::{DataTrait, TrieNode};
 use crate::structures::structures_types::{Depth, Support};
 use std::fmt::Debug;
 use std::time::Duration;
 
+// The node checked against the stop conditions, and the time left to the search
+pub(crate) struct NodeState {
+    pub support: Support,
+    pub depth: Depth,
+    pub upper_bound: f64,
+    pub elapsed: Duration,
+    pub max_time: usize, // 0 once the search is interrupted
+}
+
 #[derive(Default)]
 pub struct StopConditions<T>
 where
@@ -19,40 +28,39 @@ where
     pub(crate) fn check(
         &self,
         node: &mut TrieNode<T>,
-        support: Support,
-        min_sup: Support,
-        current_depth: Depth,
-        max_depth: Depth,
-        current_time: Duration,
-        max_time: usize,
-        upper_bound: usize,
+        state: &NodeState,
+        constraints: &Constraints,
     ) -> (bool, ReturnCondition) {
-        if self.time_limit_reached(current_time, max_time, upper_bound, node) {
+        if self.time_limit_reached(state.elapsed, state.max_time, state.upper_bound, node) {
             return (true, ReturnCondition::TimeLimitReached);
         }
 
-        if self.max_depth_reached(current_depth, max_depth, upper_bound, node) {
+        if self.max_depth_reached(state.depth, constraints.max_depth, state.upper_bound, node) {
             return (true, ReturnCondition::MaxDepthReached);
         }
 
-        if self.not_enough_support(support, min_sup, upper_bound, node) {
+        if self.not_enough_support(state.support, constraints.min_sup, state.upper_bound, node) {
             return (true, ReturnCondition::NotEnoughSupport);
         }
 
-        if self.lower_bound_constrained(upper_bound, node) {
+        if self.lower_bound_constrained(state.upper_bound, node) {
             return (true, ReturnCondition::LowerBoundConstrained);
         }
 
-        if self.pure_node(upper_bound, node) {
+        if self.pure_node(state.upper_bound, node) {
             return (true, ReturnCondition::PureNode);
         }
+
+        if self.split_cannot_pay_penalty(constraints.leaf_penalty, node) {
+            return (true, ReturnCondition::PenaltyConstrained);
+        }
         (false, ReturnCondition::None)
     }
 
     pub(crate) fn stop_from_lower_bound(
         &self,
         node: &mut TrieNode<T>,
-        actual_upper_bound: usize,
+        actual_upper_bound: f64,
     ) -> (bool, ReturnCondition) {
         if node.value.get_lower_bound() >= actual_upper_bound {
             return (true, ReturnCondition::LowerBoundConstrained);
@@ -68,7 +76,7 @@ where
         &self,
         current_time: Duration,
         max_time: usize,
-        actual_upper_bound: usize,
+        actual_upper_bound: f64,
         node: &mut TrieNode<T>,
     ) -> bool {
         match current_time.as_secs() as usize >= max_time {
@@ -80,8 +88,8 @@ where
         }
     }
 
-    fn lower_bound_constrained(&self, actual_upper_bound: usize, node: &mut TrieNode<T>) -> bool {
-        match (node.value.get_lower_bound() >= actual_upper_bound) || (actual_upper_bound == 0) {
+    fn lower_bound_constrained(&self, actual_upper_bound: f64, node: &mut TrieNode<T>) -> bool {
+        match (node.value.get_lower_bound() >= actual_upper_bound) || (actual_upper_bound == 0.) {
             true => true,
             false => false,
         }
@@ -91,7 +99,7 @@ where
         &self,
         depth: Depth,
         max_depth: Depth,
-        actual_upper_bound: usize,
+        actual_upper_bound: f64,
         node: &mut TrieNode<T>,
     ) -> bool {
         match depth == max_depth {
@@ -107,10 +115,10 @@ where
         &self,
         support: Support,
         min_sup: Support,
-        actual_upper_bound: usize,
+        actual_upper_bound: f64,
         node: &mut TrieNode<T>,
     ) -> bool {
-        match support < min_sup * 2 {
+        match support < min_sup * 2. {
             true => {
                 node.value.set_as_leaf();
                 true
@@ -119,7 +127,7 @@ where
         }
     }
 
-    fn pure_node(&self, actual_upper_bound: usize, node: &mut TrieNode<T>) -> bool {
+    fn pure_node(&self, actual_upper_bound: f64, node: &mut TrieNode<T>) -> bool {
         match node.value.get_leaf_error() == node.value.get_lower_bound() {
             // TODO : check if this is correct
             true => {
@@ -130,40 +138,45 @@ where
         }
     }
 
-    pub fn check_using_discrepancy(
+    // A split creates at least two leaves, so it costs at least twice the penalty
+    fn split_cannot_pay_penalty(&self, leaf_penalty: f64, node: &mut TrieNode<T>) -> bool {
+        match leaf_penalty > 0. && node.value.get_leaf_error() <= 2. * leaf_penalty {
+            true => {
+                node.value.set_as_leaf();
+                true
+            }
+            false => false,
+        }
+    }
+
+    pub(crate) fn check_using_discrepancy(
         &self,
         node: &mut TrieNode<T>,
-        support: Support,
-        min_sup: Support,
-        current_depth: Depth,
-        max_depth: Depth,
-        current_time: Duration,
-        max_time: usize,
-        upper_bound: usize,
+        state: &NodeState,
         discrepancy: usize,
-        max_discrepancy: usize,
+        constraints: &Constraints,
     ) -> (bool, ReturnCondition) {
-        if self.time_limit_reached(current_time, max_time, upper_bound, node) {
+        if self.time_limit_reached(state.elapsed, state.max_time, state.upper_bound, node) {
             node.value.set_discrepancy(discrepancy);
             return (true, ReturnCondition::TimeLimitReached);
         }
 
-        if self.max_depth_reached(current_depth, max_depth, upper_bound, node) {
+        if self.max_depth_reached(state.depth, constraints.max_depth, state.upper_bound, node) {
             node.value.set_discrepancy(discrepancy);
             return (true, ReturnCondition::MaxDepthReached);
         }
 
-        if self.not_enough_support(support, min_sup, upper_bound, node) {
+        if self.not_enough_support(state.support, constraints.min_sup, state.upper_bound, node) {
             node.value.set_discrepancy(discrepancy);
             return (true, ReturnCondition::NotEnoughSupport);
         }
 
-        if self.pure_node(upper_bound, node) {
+        if self.pure_node(state.upper_bound, node) {
             node.value.set_discrepancy(discrepancy);
             return (true, ReturnCondition::PureNode);
         }
-        if node.value.get_discrepancy() >= max_discrepancy
-            && upper_bound <= node.value.get_lower_bound()
+        if node.value.get_discrepancy() >= constraints.discrepancy_budget
+            && state.upper_bound <= node.value.get_lower_bound()
         {
             node.value.set_as_leaf();
             return (true, ReturnCondition::PureNode); // TODO: Change this to a new enum
@@ -1,15 +1,18 @@
-use crate::structures::structures_types::{Depth, Support};
-use pyo3::{IntoPy, PyObject, Python};
+use crate::structures::binary_tree::{NodeData, Tree};
+use crate::structures::structures_types::{CostMatrix, Depth, Support};
+use pyo3::types::PyType;
+use pyo3::{pyclass, pymethods, PyAny, PyCell, PyResult};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 // Start: Structures used in the algorithm
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Constraints {
     pub max_depth: Depth,
     pub min_sup: Support,
-    pub max_error: usize,
+    pub max_error: f64,
     pub max_time: usize,
     pub one_time_sort: bool,
     pub specialization: Specialization,
@@ -17,61 +20,146 @@ pub struct Constraints {
     pub branching: BranchingType,
     pub cache_init: CacheInit,
     pub cache_init_size: usize,
+    pub cache_type: CacheType,
     pub discrepancy_budget: usize,
     pub discrepancy_strategy: DiscrepancyStrategy,
     pub python_function_data: Option<PythonFunctionData>,
+    pub cost_matrix: Option<CostMatrix>,
+    pub leaf_penalty: f64,   // Added to the error for each leaf of the tree
+    pub max_leaves: usize,   // <usize>::MAX when only the depth limits the size
+    pub all_depths: bool,    // Search the optimal tree of every depth up to max_depth
+    pub anytime: bool,       // Record the improvements of the root solution
+    pub memory_limit: usize, // Bytes of cache, <usize>::MAX when unbounded
+    pub cover_cache: bool,   // Share the solutions of the itemsets with the same cover
+    pub num_threads: usize,  // Workers exploring the root candidates, 1 when sequential
+    pub top_trees: usize,    // Best trees enumerated after the search, 0 when only one is kept
+    pub rashomon_bound: f64, // Largest excess objective of the enumerated trees, MAX when unbounded
 }
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub(crate) struct Branching {
     pub branch: usize,
-    pub lower_bound: usize,
+    pub lower_bound: f64,
+}
+
+/// Solution of the root found during the search of the trees of depth `depth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Incumbent {
+    pub depth: Depth,
+    #[serde(with = "seconds")]
+    pub time: Duration,
+    pub error: f64,
+    pub tree: Tree<NodeData>,
 }
 
+/// State of a running search, reported at most once per interval of the progress callback.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Progress {
+    #[serde(with = "seconds")]
+    pub time: Duration,
+    pub upper_bound: f64, // Best root objective found so far, <f64>::MAX when none
+    pub cache_size: usize,
+}
+
+/// Work done by the search, to compare the configurations of the algorithm.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchCounters {
+    pub nodes_expanded: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+    pub return_conditions: BTreeMap<ReturnCondition, usize>,
+    pub specialized_calls: usize,
+    pub similarity_prunings: usize,
+    pub evictions: usize,
+    pub cover_reuses: usize,
+}
+
+impl SearchCounters {
+    /// Adds the work of another search, the workers of the parallel search are added up.
+    pub fn merge(&mut self, other: &SearchCounters) {
+        self.nodes_expanded += other.nodes_expanded;
+        self.cache_hits += other.cache_hits;
+        self.cache_misses += other.cache_misses;
+        for (condition, count) in other.return_conditions.iter() {
+            *self.return_conditions.entry(*condition).or_insert(0) += count;
+        }
+        self.specialized_calls += other.specialized_calls;
+        self.similarity_prunings += other.similarity_prunings;
+        self.evictions += other.evictions;
+        self.cover_reuses += other.cover_reuses;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Statistics {
     pub(crate) cache_size: usize,
-    pub tree_error: usize,
+    pub tree_error: f64,
+    pub depth_errors: Vec<f64>, // Error of the tree of each depth when all depths are searched
+    pub top_errors: Vec<f64>,   // Objective of each enumerated tree, in increasing order
+    pub incumbents: Vec<Incumbent>, // Improvements of the root solution in anytime mode
+    pub status: SearchStatus,
+    pub lower_bound: f64, // Proven lower bound of the root objective
+    pub gap: f64,         // tree_error - lower_bound, <f64>::MAX when no tree was found
+    pub counters: SearchCounters,
+    #[serde(with = "seconds")]
     pub(crate) duration: Duration,
     pub(crate) num_attributes: usize,
-    pub(crate) num_samples: usize,
-    pub(crate) train_distribution: [usize; 2],
+    pub(crate) num_samples: Support,
+    pub(crate) train_distribution: Vec<Support>,
     pub(crate) constraints: Constraints,
 }
 
-impl IntoPy<PyObject> for Statistics {
-    fn into_py(self, py: Python<'_>) -> PyObject {
-        let json = serde_json::to_string_pretty(&self).unwrap();
-        json.into_py(py)
+// Durations are serialized as seconds, as floats
+pub(crate) mod seconds {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(D::Error::custom)
     }
 }
 
 // End: Structures used in the algorithm
 
 // Start: Enums used in the algorithm
+// The enums of the Python API are these ones, under the names of pytrees.enum_params
+
+#[pyclass(name = "Heuristic", module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SortHeuristic {
-    InformationGain,
-    InformationGainRatio,
-    GiniIndex,
-    None,
+    #[pyo3(name = "None_")]
+    None = 0,
+    InformationGain = 1,
+    InformationGainRatio = 2,
+    GiniIndex = 3,
 }
 
+#[pyclass(name = "LowerBound", module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum LowerBoundHeuristic {
-    Similarity,
-    None,
+    #[pyo3(name = "None_")]
+    None = 0,
+    Similarity = 1,
 }
+
+#[pyclass(module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Specialization {
-    Murtree,
-    None,
+    #[pyo3(name = "None_")]
+    None = 0,
+    #[pyo3(name = "MurTree")]
+    Murtree = 1,
 }
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum HasIntersected {
     Yes,
     No,
 }
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ReturnCondition {
     Done,
     TimeLimitReached,
@@ -79,34 +167,139 @@ pub enum ReturnCondition {
     MaxDepthReached,
     NotEnoughSupport,
     PureNode,
+    PenaltyConstrained,
     FromSpecializedAlgorithm,
+    FromSameCover,
+    FromSharedCache, // Settled by another worker of the parallel search
     None,
 }
 
+/// Why the search ended, only an `Optimal` search proves the tree error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchStatus {
+    Optimal,
+    TimeLimit,
+    ErrorLimit,       // No tree has an error below max_error
+    MemoryLimit,      // The cache reached its memory budget
+    DiscrepancyLimit, // LDSDL85 ran out of discrepancy budget before a complete search
+    Interrupted,      // The incumbent callback asked to stop
+    Cancelled,        // The cancellation token was signalled
+}
+
+#[pyclass(name = "Branching", module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum BranchingType {
-    Dynamic,
-    None,
+    #[pyo3(name = "None_")]
+    None = 0,
+    Dynamic = 1,
 }
 
+#[pyclass(module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum CacheInit {
-    Normal,
-    WithMemoryDynamic,
-    WithMemoryFromUser,
+    #[pyo3(name = "None_")]
+    Normal = 0,
+    #[pyo3(name = "Dynamic")]
+    WithMemoryDynamic = 1,
+    #[pyo3(name = "FromUser")]
+    WithMemoryFromUser = 2,
 }
 
+#[pyclass(module = "pytrees_internal.optimal")]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum CacheType {
+    Trie = 0,
+    Hash = 1,
+}
+
+#[pyclass(module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum DiscrepancyStrategy {
-    None,
-    Incremental,
-    Double,
+    #[pyo3(name = "None_")]
+    None = 0,
+    Incremental = 1,
+    Double = 2,
 }
 
+#[pyclass(name = "CustomFunctionDataType", module = "pytrees_internal.optimal")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum PythonFunctionData {
-    ClassSupports,
-    Tids,
+    ClassSupports = 0,
+    Tids = 1,
 }
 
+/// The enums the Python API also takes as the integer codes of their variants.
+pub(crate) trait FromCode: Sized {
+    const LABEL: &'static str; // Name of the option in the errors of the invalid codes
+
+    fn from_code(code: usize) -> Option<Self>;
+}
+
+// A variant is pickled as the attribute of its class, so that sklearn can clone the estimators
+macro_rules! python_enum {
+    ($($name:ident($label:literal) { $($variant:ident => $attribute:literal),* }),*) => {
+        $(
+            #[pymethods]
+            impl $name {
+                fn __reduce__(slf: &PyCell<Self>) -> PyResult<(&PyAny, (&PyType, &'static str))> {
+                    let getattr = slf.py().import("builtins")?.getattr("getattr")?;
+                    let attribute = match *slf.borrow() {
+                        $($name::$variant => $attribute),*
+                    };
+                    Ok((getattr, (slf.get_type(), attribute)))
+                }
+            }
+
+            impl FromCode for $name {
+                const LABEL: &'static str = $label;
+
+                fn from_code(code: usize) -> Option<Self> {
+                    [$($name::$variant),*]
+                        .into_iter()
+                        .find(|variant| *variant as usize == code)
+                }
+            }
+        )*
+    };
+}
+
+python_enum!(
+    SortHeuristic("heuristic") {
+        None => "None_",
+        InformationGain => "InformationGain",
+        InformationGainRatio => "InformationGainRatio",
+        GiniIndex => "GiniIndex"
+    },
+    LowerBoundHeuristic("lower bound") {
+        None => "None_",
+        Similarity => "Similarity"
+    },
+    Specialization("specialization") {
+        None => "None_",
+        Murtree => "MurTree"
+    },
+    BranchingType("branching type") {
+        None => "None_",
+        Dynamic => "Dynamic"
+    },
+    CacheInit("cache init type") {
+        Normal => "None_",
+        WithMemoryDynamic => "Dynamic",
+        WithMemoryFromUser => "FromUser"
+    },
+    CacheType("cache type") {
+        Trie => "Trie",
+        Hash => "Hash"
+    },
+    DiscrepancyStrategy("discrepancy strategy") {
+        None => "None_",
+        Incremental => "Incremental",
+        Double => "Double"
+    },
+    PythonFunctionData("custom function type") {
+        ClassSupports => "ClassSupports",
+        Tids => "Tids"
+    }
+);
+
 // End: Enums used in the algorithm
@@ -0,0 +1,227 @@
+use crate::algorithms::dl85::DL85;
+use crate::algorithms::dl85_utils::leaf_objective::LeafObjective;
+use crate::algorithms::dl85_utils::structs_enums::SearchStatus;
+use crate::heuristics::Heuristic;
+use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
+use crate::structures::caching::cache_trait::Cache;
+use crate::structures::caching::trie::DataTrait;
+use crate::structures::structure_trait::Structure;
+use crate::structures::structures_types::{Attribute, Index, Item};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+
+// Best subtrees of each itemset with the bound they were searched with
+type Subtrees = HashMap<BTreeSet<Item>, (f64, Vec<(f64, Tree<NodeData>)>)>;
+
+impl<'heur, H, T, O> DL85<'heur, H, T, O>
+where
+    H: Heuristic + ?Sized,
+    T: DataTrait + Default + Debug,
+    O: LeafObjective,
+{
+    // Fills `top_trees` with the best trees of objective within the Rashomon bound of the search.
+    // The time limit and the cancellation stop the enumeration with the trees found so far, the
+    // status then tells the list may be incomplete.
+    pub(crate) fn enumerate_top_trees<S>(&mut self, structure: &mut S, candidates: &[Attribute])
+    where
+        S: Structure,
+    {
+        let bound = self.statistics.tree_error + self.constraints.rashomon_bound;
+        let mut subtrees = HashMap::new();
+        let trees = self.best_subtrees(
+            structure,
+            &mut BTreeSet::new(),
+            candidates,
+            bound,
+            &mut subtrees,
+        );
+        for (error, mut tree) in trees {
+            if error >= self.constraints.max_error {
+                break;
+            }
+            if self.constraints.leaf_penalty > 0. {
+                let root = tree.get_root_index();
+                Self::remove_leaf_penalty(&mut tree, root, self.constraints.leaf_penalty);
+            }
+            self.statistics.top_errors.push(error);
+            self.top_trees.push(tree);
+        }
+        if let Some(status) = self.interruption {
+            self.statistics.status = status;
+        }
+    }
+
+    // The `top_trees` best subtrees of the itemset with an objective of at most `bound`, in
+    // increasing objective. The lists are kept by itemset, a list holding as many trees as asked
+    // is complete whatever the bound it was searched with. The bounds of the cache prune the
+    // splits whose children cannot fit under `bound` together.
+    fn best_subtrees<S>(
+        &mut self,
+        structure: &mut S,
+        itemset: &mut BTreeSet<Item>,
+        candidates: &[Attribute],
+        bound: f64,
+        subtrees: &mut Subtrees,
+    ) -> Vec<(f64, Tree<NodeData>)>
+    where
+        S: Structure,
+    {
+        let count = self.constraints.top_trees;
+        if let Some((searched, trees)) = subtrees.get(itemset) {
+            if bound <= *searched || trees.len() >= count {
+                return trees
+                    .iter()
+                    .filter(|(error, _)| *error <= bound)
+                    .cloned()
+                    .collect();
+            }
+        }
+
+        let mut trees = vec![];
+        if self.enumeration_stopped() {
+            return trees;
+        }
+
+        // BEGIN STEP: The node as a leaf
+        let (leaf_error, class) = self.leaf_error(structure);
+        if leaf_error <= bound {
+            let prediction = structure.regression_statistics().map(Self::mean_value);
+            let node_data =
+                self.create_node_data(Attribute::MAX, leaf_error, class, prediction, true);
+            let mut leaf = Tree::new();
+            leaf.add_root(TreeNode::new(node_data));
+            trees.push((leaf_error, leaf));
+        }
+        // END STEP: The node as a leaf
+
+        // BEGIN STEP: Combine the best subtrees of the children of each split
+        let mut limit = bound;
+        if itemset.len() < self.constraints.max_depth {
+            for attribute in self.get_node_candidates(structure, Attribute::MAX, candidates) {
+                if self.enumeration_stopped() {
+                    break;
+                }
+                let mut lower_bounds = [0.; 2];
+                for (branch, lower_bound) in lower_bounds.iter_mut().enumerate() {
+                    itemset.insert((attribute, branch));
+                    *lower_bound = self.cached_lower_bound(itemset);
+                    itemset.remove(&(attribute, branch));
+                }
+                if lower_bounds[0] + lower_bounds[1] > limit {
+                    continue;
+                }
+
+                let mut children = vec![];
+                for branch in 0..2 {
+                    let sibling_error = children
+                        .first()
+                        .and_then(|trees: &Vec<(f64, Tree<NodeData>)>| trees.first())
+                        .map_or(lower_bounds[1], |(error, _)| *error);
+                    itemset.insert((attribute, branch));
+                    let _ = structure.push((attribute, branch));
+                    let child = self.best_subtrees(
+                        structure,
+                        itemset,
+                        candidates,
+                        limit - sibling_error,
+                        subtrees,
+                    );
+                    structure.backtrack();
+                    itemset.remove(&(attribute, branch));
+                    if child.is_empty() {
+                        break;
+                    }
+                    children.push(child);
+                }
+                if children.len() < 2 {
+                    continue;
+                }
+
+                let mut pairs = vec![];
+                for (left, (left_error, _)) in children[0].iter().enumerate() {
+                    for (right, (right_error, _)) in children[1].iter().enumerate() {
+                        if left_error + right_error <= limit {
+                            pairs.push((left_error + right_error, left, right));
+                        }
+                    }
+                }
+                pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+                pairs.truncate(count);
+                for (error, left, right) in pairs {
+                    let node_data = self.create_node_data(attribute, error, 0, None, false);
+                    let mut tree = Tree::new();
+                    let root = tree.add_root(TreeNode::new(node_data));
+                    for (is_left, (_, child)) in
+                        [(true, &children[0][left]), (false, &children[1][right])]
+                    {
+                        Self::graft_subtree(
+                            &mut tree,
+                            root,
+                            is_left,
+                            child,
+                            child.get_root_index(),
+                        );
+                    }
+                    trees.push((error, tree));
+                }
+
+                // The sort is stable, the trees found first come first among equal objectives
+                trees.sort_by(|a, b| a.0.total_cmp(&b.0));
+                trees.truncate(count);
+                if trees.len() == count {
+                    limit = limit.min(trees[count - 1].0);
+                }
+            }
+        }
+        // END STEP: Combine the best subtrees of the children of each split
+
+        // The lists of a stopped enumeration may miss trees, they are not reused
+        if self.interruption.is_none() {
+            subtrees.insert(itemset.clone(), (bound, trees.clone()));
+        }
+        trees
+    }
+
+    fn enumeration_stopped(&mut self) -> bool {
+        self.check_cancellation();
+        if self.interruption.is_none()
+            && self.run_time.elapsed().as_secs() as usize >= self.constraints.max_time
+        {
+            self.interrupt(SearchStatus::TimeLimit);
+        }
+        self.interruption.is_some()
+    }
+
+    // Lower bound of the objective of the subtrees of the itemset, the error of its entry when the
+    // search solved it
+    fn cached_lower_bound(&self, itemset: &BTreeSet<Item>) -> f64 {
+        let node = match self
+            .find_entry(itemset, None)
+            .and_then(|index| self.cache.get_node(index))
+        {
+            Some(node) => node,
+            None => return 0.,
+        };
+        match self.interruption.is_none() && node.value.get_node_error() < <f64>::MAX {
+            true => node.value.get_node_error(),
+            false => node.value.get_lower_bound(),
+        }
+    }
+
+    // Copies the subtree of `source` at `index` as a child of `parent`
+    fn graft_subtree(
+        tree: &mut Tree<NodeData>,
+        parent: Index,
+        is_left: bool,
+        source: &Tree<NodeData>,
+        index: Index,
+    ) {
+        if let Some(node) = source.get_node(index) {
+            let node_index = tree.add_node(parent, is_left, TreeNode::new(node.value));
+            if node.left != node.right {
+                Self::graft_subtree(tree, node_index, true, source, node.left);
+                Self::graft_subtree(tree, node_index, false, source, node.right);
+            }
+        }
+    }
+}
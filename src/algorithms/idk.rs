@@ -5,7 +5,7 @@ use crate::structures::structures_types::{Attribute, Depth, Index, Support};
 
 pub struct IDK {
     tree: Option<Tree<NodeData>>,
-    error: Option<usize>,
+    error: Option<f64>,
 }
 
 impl Basic for IDK {}
@@ -65,21 +65,21 @@ impl IDK {
         next: Option<Attribute>,
         minsup: Support,
         fit_method: &F,
-    ) -> usize
+    ) -> f64
     where
         S: Structure,
         F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
     {
-        let mut parent_error = 0;
+        let mut parent_error = 0.;
         for (i, val) in [false, true].iter().enumerate() {
             let _ = structure.push((next.unwrap(), i));
             let child_tree = fit_method(structure, minsup, 2);
             let mut child_error = Self::get_tree_error(&child_tree);
-            if child_error == <usize>::MAX {
+            if child_error == <f64>::MAX {
                 child_error = Self::create_leaf(tree, structure, index, !*val);
             } else {
                 let child_index = Self::create_child(tree, index, !*val);
-                if child_error == 0 {
+                if child_error == 0. {
                     Self::move_tree(tree, child_index, &child_tree, child_tree.get_root_index());
                 } else if let Some(child) = tree.get_node_mut(child_index) {
                     let mut child_next = None;
@@ -122,7 +122,7 @@ mod idk_test {
         let dataset = BinaryDataset::load("test_data/ionosphere.txt", false, 0.0);
         let bitset_data = BitsetStructure::format_input_data(&dataset);
         let mut structure = RSparseBitsetStructure::new(&bitset_data);
-        let a = IDK::fit(&mut structure, 1, InfoGain::fit);
+        let a = IDK::fit(&mut structure, 1., InfoGain::fit);
         let error = IDK::get_tree_error(&a);
     }
 }
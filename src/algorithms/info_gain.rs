@@ -1,17 +1,20 @@
 use crate::algorithms::algorithm_trait::{Algorithm, Basic};
 use crate::algorithms::murtree::MurTree;
+use crate::heuristics::Handler;
 use crate::structures::binary_tree::{NodeData, Tree};
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::{Attribute, Item, Support};
+use crate::structures::structures_types::{Item, Support};
 use float_cmp::{ApproxEq, F64Margin};
 
 pub struct InfoGain {
     tree: Option<Tree<NodeData>>,
-    error: usize,
+    error: f64,
 }
 
 impl Basic for InfoGain {}
 
+impl Handler for InfoGain {}
+
 impl Algorithm for InfoGain {
     fn build_depth_one_tree<S>(structure: &mut S, min_sup: Support) -> Tree<NodeData>
     where
@@ -26,7 +29,7 @@ impl Algorithm for InfoGain {
         let mut left_index = 0;
         let mut right_index = 0;
 
-        let info_gain = InfoGain::sort_using_information_gain(structure, &mut candidates, false);
+        let info_gain = Self::internally_compute(structure, &mut candidates, false);
         let mut tree = Self::empty_tree(1);
 
         if let Some(root) = tree.get_node_mut(tree.get_root_index()) {
@@ -35,7 +38,7 @@ impl Algorithm for InfoGain {
             right_index = root.right;
         }
 
-        let mut node_error = 0;
+        let mut node_error = 0.;
 
         for branch in [0usize, 1].iter() {
             structure.push((candidates[0], *branch));
@@ -77,7 +80,7 @@ impl Algorithm for InfoGain {
         let matrix = MurTree::build_depth_two_matrix(structure, &candidates);
 
         let classes_support = structure.labels_support();
-        let support = classes_support.iter().sum::<usize>();
+        let support = classes_support.iter().sum::<Support>();
 
         let parent_entropy = Self::compute_entropy(classes_support);
 
@@ -111,7 +114,7 @@ impl Algorithm for InfoGain {
 
                 let mut left_leaves = vec![];
                 let mut right_leaves = vec![];
-                let mut root_error = 0;
+                let mut root_error = 0.;
                 let mut root_gain = 0f64;
 
                 for val in [0usize, 1].iter() {
@@ -126,9 +129,9 @@ impl Algorithm for InfoGain {
                         classes_support,
                     );
 
-                    let weight = match support {
-                        0 => 0f64,
-                        _ => left_leaves.iter().sum::<usize>() as f64 / support as f64,
+                    let weight = match support == 0. {
+                        true => 0f64,
+                        false => left_leaves.iter().sum::<Support>() / support,
                     };
 
                     node_gain -= Self::compute_entropy(&left_leaves) * weight;
@@ -141,9 +144,9 @@ impl Algorithm for InfoGain {
                         classes_support,
                     );
 
-                    let weight = match support {
-                        0 => 0f64,
-                        _ => right_leaves.iter().sum::<usize>() as f64 / support as f64,
+                    let weight = match support == 0. {
+                        true => 0f64,
+                        false => right_leaves.iter().sum::<Support>() / support,
                     };
 
                     node_gain -= Self::compute_entropy(&right_leaves) * weight;
@@ -191,7 +194,7 @@ impl Algorithm for InfoGain {
                 if let Some(root_node) = root_tree.get_node_mut(root_tree.get_root_index()) {
                     root_node.value.error = root_error;
                     root_node.value.metric = Some(root_gain);
-                    if root_node.value.error == 0 {
+                    if root_node.value.error == 0. {
                         break;
                     }
                 }
@@ -200,7 +203,7 @@ impl Algorithm for InfoGain {
             if Self::get_info_gain(&root_tree) > Self::get_info_gain(&tree) {
                 tree = root_tree;
             }
-            if Self::get_tree_error(&tree) == 0 {
+            if Self::get_tree_error(&tree) == 0. {
                 break;
             }
         }
@@ -209,118 +212,6 @@ impl Algorithm for InfoGain {
 }
 
 impl InfoGain {
-    fn sort_using_information_gain<S>(
-        structure: &mut S,
-        candidates: &mut Vec<Attribute>,
-        ratio: bool,
-    ) -> f64
-    where
-        S: Structure,
-    {
-        let mut root_classes_support = structure.labels_support().to_vec();
-
-        let parent_entropy = Self::compute_entropy(&root_classes_support);
-        let mut candidates_sorted = vec![];
-        for attribute in candidates.iter() {
-            let info_gain = Self::information_gain(
-                *attribute,
-                structure,
-                &root_classes_support,
-                parent_entropy,
-                ratio,
-            );
-            candidates_sorted.push((*attribute, info_gain));
-        }
-        candidates_sorted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        *candidates = candidates_sorted
-            .iter()
-            .map(|(a, _)| *a)
-            .collect::<Vec<Attribute>>();
-        candidates_sorted[0].1
-    }
-
-    fn information_gain<S>(
-        attribute: Attribute,
-        structure: &mut S,
-        root_classes_support: &[usize],
-        parent_entropy: f64,
-        ratio: bool,
-    ) -> f64
-    where
-        S: Structure,
-    {
-        let _ = structure.push((attribute, 0));
-        let left_classes_supports = structure.labels_support().to_vec();
-        structure.backtrack();
-
-        let right_classes_support = root_classes_support
-            .iter()
-            .enumerate()
-            .map(|(idx, val)| *val - left_classes_supports[idx])
-            .collect::<Vec<usize>>();
-
-        let actual_size = root_classes_support.iter().sum::<usize>();
-        let left_split_size = left_classes_supports.iter().sum::<usize>();
-        let right_split_size = right_classes_support.iter().sum::<usize>();
-
-        let left_weight = match actual_size {
-            0 => 0f64,
-            _ => left_split_size as f64 / actual_size as f64,
-        };
-
-        let right_weight = match actual_size {
-            0 => 0f64,
-            _ => right_split_size as f64 / actual_size as f64,
-        };
-
-        let mut split_info = 0f64;
-        if ratio {
-            if left_weight > 0. {
-                split_info = -left_weight * left_weight.log2();
-            }
-            if right_weight > 0. {
-                split_info += -right_weight * right_weight.log2();
-            }
-        }
-        if split_info.approx_eq(
-            0.,
-            F64Margin {
-                ulps: 2,
-                epsilon: 0.0,
-            },
-        ) {
-            split_info = 1f64;
-        }
-
-        let left_split_entropy = Self::compute_entropy(&left_classes_supports);
-        let right_split_entropy = Self::compute_entropy(&right_classes_support);
-
-        let info_gain = parent_entropy
-            - (left_weight * left_split_entropy + right_weight * right_split_entropy);
-        if ratio {
-            return info_gain / split_info;
-        }
-        info_gain
-    }
-
-    fn compute_entropy(covers: &[usize]) -> f64 {
-        let support = covers.iter().sum::<usize>();
-        let mut entropy = 0f64;
-        for class_support in covers {
-            let p = match support {
-                0 => 0f64,
-                _ => *class_support as f64 / support as f64,
-            };
-
-            let mut log_val = 0f64;
-            if p > 0. {
-                log_val = p.log2();
-            }
-            entropy += -p * log_val;
-        }
-        entropy
-    }
-
     fn get_info_gain(tree: &Tree<NodeData>) -> f64 {
         if let Some(node) = tree.get_node(tree.get_root_index()) {
             if let Some(metric) = node.value.metric {
@@ -331,11 +222,11 @@ impl InfoGain {
     }
 
     fn get_leaves_classes_support(
-        matrix: &[Vec<Vec<usize>>],
+        matrix: &[Vec<Vec<Support>>],
         first: Item,
         second: Item,
-        root_classes_support: &[usize],
-    ) -> Vec<usize> {
+        root_classes_support: &[Support],
+    ) -> Vec<Support> {
         let i = first.0;
         let j = second.0;
         let is_left_i = first.1 == 0;
@@ -392,7 +283,7 @@ mod info_gain_test {
         let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
         let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
         let mut structure = RSparseBitsetStructure::new(&bitset_data);
-        let tree = InfoGain::build_depth_one_tree(&mut structure, 1);
-        assert_eq!(InfoGain::get_tree_error(&tree), 152);
+        let tree = InfoGain::build_depth_one_tree(&mut structure, 1.);
+        assert_eq!(InfoGain::get_tree_error(&tree), 152.);
     }
 }
@@ -1,50 +1,59 @@
 use crate::algorithms::algorithm_trait::{Algorithm, Basic};
+use crate::algorithms::cancellation::CancellationToken;
+use crate::algorithms::dl85_utils::leaf_objective::{LeafObjective, Misclassification};
 use crate::algorithms::dl85_utils::slb::{SimilarDatasets, Similarity};
-use crate::algorithms::dl85_utils::stop_conditions::StopConditions;
+use crate::algorithms::dl85_utils::stop_conditions::{NodeState, StopConditions};
 use crate::algorithms::dl85_utils::structs_enums::{
-    Branching, BranchingType, CacheInit, Constraints, DiscrepancyStrategy, HasIntersected,
-    LowerBoundHeuristic, ReturnCondition, Specialization, Statistics,
+    Branching, BranchingType, CacheInit, CacheType, Constraints, DiscrepancyStrategy,
+    HasIntersected, LowerBoundHeuristic, ReturnCondition, SearchCounters, SearchStatus,
+    Specialization, Statistics,
 };
 use crate::algorithms::lgdt::LGDT;
 use crate::algorithms::murtree::MurTree;
+use crate::errors::PyTreesResult;
 use crate::heuristics::Heuristic;
 use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
+use crate::structures::caching::cache_trait::{Cache, CacheBackend};
+use crate::structures::caching::hash_cache::HashCache;
 use crate::structures::caching::trie::{DataTrait, Trie, TrieNode};
-use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
 use crate::structures::structure_trait::Structure;
 use crate::structures::structures_types::{Attribute, Depth, Index, Item, Support};
-use std::cmp::{max, min};
+use std::cmp::min;
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::time::{Duration, Instant};
 
 // TODO: Check if it can be consistent with similarity lower bound And Murtree specialization
 // TODO: Check if it is possible to use blank implementation to reduce duplicates in code
-pub struct LDSDL85<'heur, H, T>
+pub struct LDSDL85<'heur, H, T, O = Misclassification>
 where
     H: Heuristic + ?Sized,
     T: DataTrait + Default + Debug,
+    O: LeafObjective,
 {
     constraints: Constraints,
     heuristic: &'heur mut H,
-    cache: Trie<T>,
+    pub objective: O,
+    cache: CacheBackend<T>,
     stop_conditions: StopConditions<T>,
     pub statistics: Statistics,
     pub tree: Tree<NodeData>,
+    cancellation: Option<CancellationToken>,
     run_time: Instant,
 }
 
-impl<'heur, H, T> LDSDL85<'heur, H, T>
+impl<'heur, H, T, O> LDSDL85<'heur, H, T, O>
 where
     H: Heuristic + ?Sized,
     T: DataTrait + Default + Debug,
+    O: LeafObjective,
 {
     pub fn new(
         min_sup: Support,
         max_depth: Depth,
         discrepancy_budget: usize,
         discrepancy_strategy: DiscrepancyStrategy,
-        max_error: usize,
+        max_error: f64,
         max_time: usize,
         specialization: Specialization,
         lower_bound: LowerBoundHeuristic,
@@ -53,6 +62,7 @@ where
         cache_init_size: usize,
         one_time_sort: bool,
         heuristic: &'heur mut H,
+        objective: O,
     ) -> Self {
         let constraints = Constraints {
             max_depth,
@@ -65,53 +75,124 @@ where
             branching,
             cache_init,
             cache_init_size,
+            cache_type: CacheType::Trie,
             discrepancy_budget,
             discrepancy_strategy,
             python_function_data: None,
+            cost_matrix: None,
+            leaf_penalty: 0.,
+            max_leaves: <usize>::MAX,
+            all_depths: false,
+            anytime: false,
+            memory_limit: <usize>::MAX,
+            cover_cache: false,
+            num_threads: 1,
+            top_trees: 0,
+            rashomon_bound: <f64>::MAX,
         };
         Self {
-            constraints,
+            constraints: constraints.clone(),
             heuristic,
-            cache: Trie::default(),
+            objective,
+            cache: CacheBackend::default(),
             stop_conditions: StopConditions::default(),
             statistics: Statistics {
                 num_attributes: 0,
-                num_samples: 0,
-                train_distribution: [0, 0],
+                num_samples: 0.,
+                train_distribution: vec![],
                 constraints,
                 cache_size: 0,
-                tree_error: 0,
+                tree_error: 0.,
+                depth_errors: vec![],
+                top_errors: vec![],
+                incumbents: vec![],
+                status: SearchStatus::Optimal,
+                lower_bound: 0.,
+                gap: 0.,
+                counters: SearchCounters::default(),
                 duration: Duration::default(),
             },
             tree: Tree::default(),
+            cancellation: None,
             run_time: Instant::now(),
         }
     }
 
-    pub fn fit(&mut self, structure: &mut RSparseBitsetStructure) {
+    pub fn set_cache_type(&mut self, cache_type: CacheType) {
+        self.constraints.cache_type = cache_type;
+        self.statistics.constraints = self.constraints.clone();
+    }
+
+    /// Stops the search once `token` is cancelled, like a time limit, with the best tree found so
+    /// far and `SearchStatus::Cancelled`.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = Some(token);
+    }
+
+    /// Fails before searching when the objective does not apply to the data, or when the cache
+    /// cannot be allocated. The specialization and the similarity bound are turned off for the
+    /// objectives other than the misclassification error for this run only.
+    pub fn fit<S>(&mut self, structure: &mut S) -> PyTreesResult<()>
+    where
+        S: Structure,
+    {
+        let requested = (
+            self.constraints.specialization,
+            self.constraints.lower_bound,
+        );
+        let result = self.fit_with_effective_options(structure);
+        (
+            self.constraints.specialization,
+            self.constraints.lower_bound,
+        ) = requested;
+        result
+    }
+
+    fn fit_with_effective_options<S>(&mut self, structure: &mut S) -> PyTreesResult<()>
+    where
+        S: Structure,
+    {
         // BEGIN STEP: Setup everything in the statist Update Statistics structures
         self.statistics.num_attributes = structure.num_attributes();
         let distribution = structure.labels_support();
-        self.statistics.train_distribution = [distribution[0], distribution[1]];
+        self.statistics.train_distribution = distribution.to_vec();
         self.statistics.num_samples = structure.support();
         // END STEP : Setup everything in the statistics structures
 
+        // BEGIN STEP: Check the options available for the leaf error
+        self.objective.check_labels(structure.num_labels())?;
+        // The specialization and the similarity bound only hold for the misclassification error
+        if !self.objective.is_misclassification() {
+            self.constraints.specialization = Specialization::None;
+            self.constraints.lower_bound = LowerBoundHeuristic::None;
+            self.statistics.constraints = self.constraints.clone();
+        }
+        // END STEP: Check the options available for the leaf error
+
         // BEGIN STEP: Setup the cache
 
-        self.cache = match self.constraints.cache_init {
-            CacheInit::Normal => Trie::default(),
-            CacheInit::WithMemoryDynamic => {
-                Trie::with_capacity(structure.num_attributes(), self.constraints.max_depth)
-            }
-            CacheInit::WithMemoryFromUser => {
-                Trie::from_user_memory(self.constraints.cache_init_size)
-            }
+        let (features, depth) = (structure.num_attributes(), self.constraints.max_depth);
+        self.cache = match self.constraints.cache_type {
+            CacheType::Trie => CacheBackend::Trie(match self.constraints.cache_init {
+                CacheInit::Normal => Trie::default(),
+                CacheInit::WithMemoryDynamic => Trie::with_capacity(features, depth),
+                CacheInit::WithMemoryFromUser => {
+                    Trie::from_user_memory(self.constraints.cache_init_size)?
+                }
+            }),
+            CacheType::Hash => CacheBackend::Hash(match self.constraints.cache_init {
+                CacheInit::Normal => HashCache::default(),
+                CacheInit::WithMemoryDynamic => HashCache::with_capacity(features, depth),
+                CacheInit::WithMemoryFromUser => {
+                    HashCache::from_user_memory(self.constraints.cache_init_size)?
+                }
+            }),
         };
         // END STEP: Setup the cache
 
         // BEGIN STEP: Load candidates
         let mut candidates = Vec::new();
-        if self.constraints.min_sup == 1 {
+        if self.constraints.min_sup == 1. {
             candidates = (0..structure.num_attributes()).collect();
         } else {
             for i in 0..structure.num_attributes() {
@@ -130,7 +211,7 @@ where
 
         // BEGIN STEP: Setup the root
         let mut root_data = T::new();
-        let root_leaf_error = Self::leaf_error(structure.labels_support());
+        let root_leaf_error = self.objective.leaf_error(structure);
         root_data.set_node_error(root_leaf_error.0);
         root_data.set_leaf_error(root_leaf_error.0);
         let root = TrieNode::new(root_data);
@@ -157,7 +238,9 @@ where
         self.run_time = Instant::now();
         let mut max_error = self.constraints.max_error;
         let mut last_iteration = false;
+        let mut searched_budget = 0;
         while current_budget <= budget {
+            searched_budget = current_budget;
             self.recursion(
                 structure,
                 0,
@@ -171,8 +254,9 @@ where
                 &mut similarity_data,
             );
             max_error = self.get_tree_error();
-            if max_error == 0
+            if max_error == 0.
                 || self.run_time.elapsed().as_secs() as usize >= self.constraints.max_time
+                || self.cancelled()
             {
                 break;
             }
@@ -196,10 +280,16 @@ where
 
         // BEGIN STEP: Update the statistics
         self.update_statistics();
-        if self.get_tree_error() < <usize>::MAX {
+        let complete_budget = Self::compute_discrepancy_limit(
+            candidates.len().saturating_sub(1),
+            self.constraints.max_depth,
+        );
+        self.update_status(searched_budget >= complete_budget);
+        if self.get_tree_error() < <f64>::MAX {
             self.generate_tree();
         }
         // END STEP: Update the statistics
+        Ok(())
     }
 
     fn compute_discrepancy_limit(nb_candidates: usize, remaining_depth: Depth) -> usize {
@@ -224,37 +314,41 @@ where
         }
     }
 
-    fn recursion(
+    fn recursion<S>(
         &mut self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         depth: Depth,
         current_discrepancy: usize,
-        upper_bound: usize,
+        upper_bound: f64,
         parent_item: Item,
         itemset: &mut BTreeSet<Item>,
         candidates: &[usize],
         parent_index: Index,
         parent_is_new: bool,
         similarity_data: &mut SimilarDatasets<T>,
-    ) -> (usize, ReturnCondition, HasIntersected) {
+    ) -> (f64, ReturnCondition, HasIntersected)
+    where
+        S: Structure,
+    {
         // TODO: Check if there is not enough time left (Maybe this can be done outside of the recursion)
 
         let mut child_upper_bound = upper_bound;
         let current_support = structure.support();
 
         // BEGIN STEP: Check if we should stop
+        let max_time = self.max_time();
         if let Some(node) = self.cache.get_node_mut(parent_index) {
             let return_condition = self.stop_conditions.check_using_discrepancy(
                 node,
-                current_support,
-                self.constraints.min_sup,
-                depth,
-                self.constraints.max_depth,
-                self.run_time.elapsed(),
-                self.constraints.max_time,
-                child_upper_bound,
+                &NodeState {
+                    support: current_support,
+                    depth,
+                    upper_bound: child_upper_bound,
+                    elapsed: self.run_time.elapsed(),
+                    max_time,
+                },
                 current_discrepancy,
-                self.constraints.discrepancy_budget,
+                &self.constraints,
             );
 
             if return_condition.0 {
@@ -275,10 +369,10 @@ where
         // BEGIN STEP: Check if we should use the similarity lower bound to stop
         if let LowerBoundHeuristic::Similarity = self.constraints.lower_bound {
             if let Some(node) = self.cache.get_node_mut(parent_index) {
-                let lower_bound = max(
-                    node.value.get_lower_bound(),
-                    similarity_data.compute_similarity(structure),
-                );
+                let lower_bound = node
+                    .value
+                    .get_lower_bound()
+                    .max(similarity_data.compute_similarity(structure, 1.));
                 node.value.set_lower_bound(lower_bound);
 
                 let return_condition = self
@@ -346,7 +440,7 @@ where
 
         // BEGIN STEP: Setup the node similarity data
         let mut child_similarity_data = SimilarDatasets::new();
-        let mut min_lower_bound = <usize>::MAX;
+        let mut min_lower_bound = <f64>::MAX;
         // END STEP: Setup the node similarity data
 
         let mut discrepancy_pruned = false;
@@ -414,21 +508,19 @@ where
                 &item,
                 &return_infos,
                 child_index,
-                similarity_data,
+                &mut child_similarity_data,
             );
 
             // END STEP: will backtrack if the node is new and update the similarity data if needed
 
             // BEGIN STEP: If the error is too high, we don't need to explore the right part of the node
-            if left_error as f64 >= child_upper_bound as f64 - second.lower_bound as f64 {
-                // TODO: Ugly
+            if left_error >= child_upper_bound - second.lower_bound {
                 if let Some(node) = self.cache.get_node_mut(child_index) {
-                    min_lower_bound = match left_error == <usize>::MAX {
-                        true => min(
-                            min_lower_bound,
-                            node.value.get_lower_bound() + second.lower_bound,
-                        ),
-                        false => min(min_lower_bound, left_error + second.lower_bound),
+                    min_lower_bound = match left_error == <f64>::MAX {
+                        true => {
+                            min_lower_bound.min(node.value.get_lower_bound() + second.lower_bound)
+                        }
+                        false => min_lower_bound.min(left_error + second.lower_bound),
                     }
                 }
 
@@ -463,7 +555,7 @@ where
                 &node_candidates,
                 child_index,
                 is_new,
-                similarity_data,
+                &mut child_similarity_data,
             );
             let right_error = return_infos.0;
             // END STEP: Explore the second child node
@@ -476,11 +568,11 @@ where
                 &item,
                 &return_infos,
                 child_index,
-                similarity_data,
+                &mut child_similarity_data,
             );
             // END STEP: Will backtrack if the node is new and update the similarity data if needed
 
-            if right_error == <usize>::MAX || left_error == <usize>::MAX {
+            if right_error == <f64>::MAX || left_error == <f64>::MAX {
                 continue;
             }
 
@@ -499,25 +591,26 @@ where
                     }
                 }
             } else {
-                min_lower_bound = min(feature_error, min_lower_bound);
+                min_lower_bound = min_lower_bound.min(feature_error);
             }
             // END STEP: Update the node error if possible based on the upper bound and the branches error
         }
 
         // BEGIN STEP: If the node error is still MAX, we need to update the lower bound
         if let Some(node) = self.cache.get_node_mut(parent_index) {
-            if node.value.get_node_error() == <usize>::MAX {
-                node.value.set_lower_bound(max(
-                    node.value.get_lower_bound(),
-                    max(min_lower_bound, upper_bound),
-                ));
+            if node.value.get_node_error() == <f64>::MAX {
+                node.value.set_lower_bound(
+                    node.value
+                        .get_lower_bound()
+                        .max(min_lower_bound.max(upper_bound)),
+                );
                 return (
                     node.value.get_node_error(),
                     ReturnCondition::LowerBoundConstrained,
                     HasIntersected::Yes,
                 );
             }
-            if node.value.get_node_error() == 0 || !discrepancy_pruned {
+            if node.value.get_node_error() == 0. || !discrepancy_pruned {
                 node.value
                     .set_discrepancy(self.constraints.discrepancy_budget);
             }
@@ -534,12 +627,15 @@ where
         // END STEP: If the node error is still MAX, we need to update the lower bound
     }
 
-    fn get_node_candidates(
+    fn get_node_candidates<S>(
         &self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         last_candidate: Attribute,
         candidates: &[Attribute],
-    ) -> Vec<Attribute> {
+    ) -> Vec<Attribute>
+    where
+        S: Structure,
+    {
         let mut node_candidates = Vec::new();
         let support = structure.support();
         for potential_candidate in candidates {
@@ -557,30 +653,35 @@ where
         node_candidates
     }
 
-    fn init_data(&mut self, structure: &mut RSparseBitsetStructure, index: Index) {
+    fn init_data<S>(&mut self, structure: &mut S, index: Index)
+    where
+        S: Structure,
+    {
+        let (leaf_error, class) = self.objective.leaf_error(structure);
         if let Some(node) = self.cache.get_node_mut(index) {
-            let classes_support = structure.labels_support();
-            let (leaf_error, class) = Self::leaf_error(classes_support);
             node.value.set_leaf_error(leaf_error);
             node.value.set_class(class)
         }
     }
 
-    fn compute_lower_bounds(
+    fn compute_lower_bounds<S>(
         &self,
         attribute: Attribute,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         itemset: &mut BTreeSet<Item>,
         similarities: &mut SimilarDatasets<T>,
         option: LowerBoundHeuristic,
-    ) -> (usize, usize) {
-        let mut lower_bounds: [usize; 2] = [0, 0];
+    ) -> (f64, f64)
+    where
+        S: Structure,
+    {
+        let mut lower_bounds: [f64; 2] = [0., 0.];
 
         for (i, lower_bound) in lower_bounds.iter_mut().enumerate() {
             itemset.insert((attribute, i));
             if let Some(index) = self.cache.find(itemset.iter()) {
                 if let Some(node) = self.cache.get_node(index) {
-                    *lower_bound = match node.value.get_node_error() == <usize>::MAX {
+                    *lower_bound = match node.value.get_node_error() == <f64>::MAX {
                         true => node.value.get_lower_bound(),
                         false => node.value.get_node_error(),
                     }
@@ -590,8 +691,8 @@ where
 
             if let LowerBoundHeuristic::Similarity = option {
                 structure.push((attribute, i));
-                let sim_lb = similarities.compute_similarity(structure);
-                *lower_bound = max(*lower_bound, sim_lb);
+                let sim_lb = similarities.compute_similarity(structure, 1.);
+                *lower_bound = lower_bound.max(sim_lb);
                 structure.backtrack();
             }
         }
@@ -599,14 +700,17 @@ where
         (lower_bounds[0], lower_bounds[1])
     }
 
-    fn find_where_to_branch_first(
+    fn find_where_to_branch_first<S>(
         &self,
         child: Attribute,
         itemset: &mut BTreeSet<Item>,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         similarity_dataset: &mut SimilarDatasets<T>,
-    ) -> [Branching; 2] {
-        let mut lower_bounds = [0, 0];
+    ) -> [Branching; 2]
+    where
+        S: Structure,
+    {
+        let mut lower_bounds = [0., 0.];
         // If Dynamic branching is enabled, we check where to move first
         if let BranchingType::Dynamic = self.constraints.branching {
             for (i, lower_bound) in lower_bounds.iter_mut().enumerate() {
@@ -614,7 +718,7 @@ where
                 if let Some(index) = self.cache.find(itemset.iter()) {
                     if let Some(node) = self.cache.get_node(index) {
                         let error = node.value.get_node_error();
-                        *lower_bound = match error < <usize>::MAX {
+                        *lower_bound = match error < <f64>::MAX {
                             true => error,
                             false => node.value.get_lower_bound(),
                         }
@@ -632,8 +736,8 @@ where
                     self.constraints.lower_bound,
                 );
 
-                lower_bounds[0] = max(lower_bounds[0], similarity_lower_bounds.0);
-                lower_bounds[1] = max(lower_bounds[1], similarity_lower_bounds.1);
+                lower_bounds[0] = lower_bounds[0].max(similarity_lower_bounds.0);
+                lower_bounds[1] = lower_bounds[1].max(similarity_lower_bounds.1);
             }
         }
 
@@ -665,16 +769,18 @@ where
         [first_data, second_data]
     }
 
-    fn reset_after_branching(
+    fn reset_after_branching<S>(
         &self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         itemset: &mut BTreeSet<Item>,
         is_new: bool,
         item: &Item,
-        return_infos: &(usize, ReturnCondition, HasIntersected),
+        return_infos: &(f64, ReturnCondition, HasIntersected),
         child_index: usize,
         child_similarity_data: &mut SimilarDatasets<T>,
-    ) {
+    ) where
+        S: Structure,
+    {
         let has_intersected = match return_infos.2 {
             HasIntersected::Yes => true,
             HasIntersected::No => false,
@@ -702,13 +808,16 @@ where
         }
     }
 
-    fn update_similarity_data(
+    fn update_similarity_data<S>(
         &self,
         similarity_dataset: &mut SimilarDatasets<T>,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         child_index: Index,
         condition: ReturnCondition,
-    ) -> bool {
+    ) -> bool
+    where
+        S: Structure,
+    {
         match condition {
             ReturnCondition::LowerBoundConstrained => false,
             _ => {
@@ -721,29 +830,17 @@ where
         }
     }
 
-    fn leaf_error(classes_support: &[usize]) -> (usize, usize) {
-        let mut max_idx = 0;
-        let mut max_value = 0;
-        let mut total = 0;
-        for (idx, value) in classes_support.iter().enumerate() {
-            total += value;
-            if *value >= max_value {
-                max_value = *value;
-                max_idx = idx;
-            }
-        }
-        let error = total - max_value;
-        (error, max_idx)
-    }
-
-    fn run_specialized_algorithm(
+    fn run_specialized_algorithm<S>(
         &mut self,
-        structure: &mut RSparseBitsetStructure,
+        structure: &mut S,
         index: Index,
-        upper_bound: usize,
+        upper_bound: f64,
         itemset: &mut BTreeSet<Item>,
         depth: Depth,
-    ) -> (usize, ReturnCondition, HasIntersected) {
+    ) -> (f64, ReturnCondition, HasIntersected)
+    where
+        S: Structure,
+    {
         if let Some(node) = self.cache.get_node(index) {
             if upper_bound < node.value.get_lower_bound() {
                 return (
@@ -807,6 +904,20 @@ where
         }
     }
 
+    fn cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    // A cancelled search stops like one out of time
+    fn max_time(&self) -> usize {
+        match self.cancelled() {
+            true => 0,
+            false => self.constraints.max_time,
+        }
+    }
+
     fn update_statistics(&mut self) {
         self.statistics.cache_size = self.cache.len();
         self.statistics.duration = self.run_time.elapsed();
@@ -815,11 +926,43 @@ where
         }
     }
 
-    fn get_tree_error(&self) -> usize {
+    // Same status as DL85, a search within a partial discrepancy budget proves nothing
+    fn update_status(&mut self, is_complete: bool) {
+        let error = self.get_tree_error();
+        let status = if error == 0. {
+            SearchStatus::Optimal
+        } else if self.cancelled() {
+            SearchStatus::Cancelled
+        } else if self.run_time.elapsed().as_secs() as usize >= self.constraints.max_time {
+            SearchStatus::TimeLimit
+        } else if !is_complete {
+            SearchStatus::DiscrepancyLimit
+        } else if error >= self.constraints.max_error {
+            SearchStatus::ErrorLimit
+        } else {
+            SearchStatus::Optimal
+        };
+        let lower_bound = match status {
+            SearchStatus::Optimal => error,
+            SearchStatus::ErrorLimit => self.constraints.max_error,
+            _ => match self.cache.get_node(self.cache.get_root_index()) {
+                Some(root) => root.value.get_lower_bound().min(error),
+                None => 0.,
+            },
+        };
+        self.statistics.status = status;
+        self.statistics.lower_bound = lower_bound;
+        self.statistics.gap = match error < <f64>::MAX {
+            true => error - lower_bound,
+            false => <f64>::MAX,
+        };
+    }
+
+    fn get_tree_error(&self) -> f64 {
         if let Some(root) = self.cache.get_node(self.cache.get_root_index()) {
             root.value.get_node_error()
         } else {
-            <usize>::MAX
+            <f64>::MAX
         }
     }
 
@@ -883,18 +1026,13 @@ where
         }
     }
 
-    fn create_node_data(
-        &self,
-        test: Attribute,
-        error: usize,
-        out: usize,
-        is_leaf: bool,
-    ) -> NodeData {
+    fn create_node_data(&self, test: Attribute, error: f64, out: usize, is_leaf: bool) -> NodeData {
         if is_leaf {
             return NodeData {
                 test: None,
                 error,
                 out: Some(out),
+                prediction: None,
                 metric: None,
             };
         }
@@ -902,6 +1040,7 @@ where
             test: Some(test),
             error,
             out: None,
+            prediction: None,
             metric: None,
         }
     }
@@ -909,10 +1048,14 @@ where
 
 #[cfg(test)]
 mod dl85_test {
+    use crate::algorithms::cancellation::CancellationToken;
     use crate::algorithms::dl85::DL85;
+    use crate::algorithms::dl85_utils::leaf_objective::{LeafObjective, Misclassification};
     use crate::algorithms::dl85_utils::structs_enums::{
-        BranchingType, CacheInit, LowerBoundHeuristic, Specialization,
+        BranchingType, CacheInit, DiscrepancyStrategy, LowerBoundHeuristic, SearchStatus,
+        Specialization,
     };
+    use crate::algorithms::lds_dl85::LDSDL85;
     use crate::dataset::binary_dataset::BinaryDataset;
     use crate::dataset::data_trait::Dataset;
     use crate::heuristics::{Heuristic, InformationGain, NoHeuristic};
@@ -926,12 +1069,12 @@ mod dl85_test {
         let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
         let mut structure = RSparseBitsetStructure::new(&bitset_data);
 
-        let mut heuristic: Box<dyn Heuristic> = Box::new(NoHeuristic::default());
+        let mut heuristic: Box<dyn Heuristic> = Box::new(NoHeuristic);
 
         let mut algo: DL85<'_, _, Data> = DL85::new(
-            1,
+            1.,
             6,
-            <usize>::MAX,
+            <f64>::MAX,
             10,
             Specialization::None,
             LowerBoundHeuristic::None,
@@ -940,9 +1083,44 @@ mod dl85_test {
             0,
             false,
             heuristic.as_mut(),
-            None,
-            None,
+            Misclassification,
+        );
+        algo.fit(&mut structure).unwrap();
+    }
+
+    #[test]
+    fn run_lds_dl85_cancelled() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        let mut heuristic = NoHeuristic;
+
+        let mut algo: LDSDL85<'_, _, Data> = LDSDL85::new(
+            1.,
+            3,
+            <usize>::MAX,
+            DiscrepancyStrategy::Incremental,
+            <f64>::MAX,
+            100,
+            Specialization::None,
+            LowerBoundHeuristic::None,
+            BranchingType::Dynamic,
+            CacheInit::Normal,
+            0,
+            false,
+            &mut heuristic,
+            Misclassification,
+        );
+        let token = CancellationToken::new();
+        token.cancel();
+        algo.set_cancellation_token(token);
+        algo.fit(&mut structure).unwrap();
+
+        // Stopped at the root, which is kept as a leaf
+        assert_eq!(algo.statistics.status, SearchStatus::Cancelled);
+        assert_eq!(
+            algo.statistics.tree_error,
+            Misclassification.leaf_error(&mut structure).0
         );
-        algo.fit(&mut structure);
     }
 }
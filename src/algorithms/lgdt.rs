@@ -1,11 +1,20 @@
 use crate::algorithms::algorithm_trait::Basic;
+use crate::algorithms::cancellation::CancellationToken;
 use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::{Attribute, Depth, Index, Support};
+use crate::structures::structures_types::{Attribute, CostMatrix, Depth, Index, Support};
 
 pub struct LGDT {
     tree: Option<Tree<NodeData>>,
-    error: Option<usize>,
+    error: Option<f64>,
+}
+
+// Options of a fit, the same for every subtree it grows
+struct FitSettings<'a> {
+    min_sup: Support,
+    max_depth: Depth,
+    costs: Option<&'a CostMatrix>,
+    cancellation: Option<&'a CancellationToken>,
 }
 
 impl Basic for LGDT {}
@@ -35,6 +44,63 @@ impl LGDT {
         S: Structure,
         F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
     {
+        let settings = FitSettings {
+            min_sup,
+            max_depth,
+            costs: None,
+            cancellation: None,
+        };
+        Self::fit_tree(structure, fit_method, &settings)
+    }
+
+    /// Same as `fit`, the subtrees not grown yet once `token` is cancelled are left as leaves.
+    pub fn fit_with_cancellation<S, F>(
+        structure: &mut S,
+        min_sup: Support,
+        max_depth: Depth,
+        fit_method: F,
+        token: &CancellationToken,
+    ) -> Tree<NodeData>
+    where
+        S: Structure,
+        F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
+    {
+        let settings = FitSettings {
+            min_sup,
+            max_depth,
+            costs: None,
+            cancellation: Some(token),
+        };
+        Self::fit_tree(structure, fit_method, &settings)
+    }
+
+    /// Same as `fit` with cost-sensitive leaves, `fit_method` should use the same costs.
+    pub fn fit_with_costs<S, F>(
+        structure: &mut S,
+        min_sup: Support,
+        max_depth: Depth,
+        fit_method: F,
+        costs: &CostMatrix,
+    ) -> Tree<NodeData>
+    where
+        S: Structure,
+        F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
+    {
+        let settings = FitSettings {
+            min_sup,
+            max_depth,
+            costs: Some(costs),
+            cancellation: None,
+        };
+        Self::fit_tree(structure, fit_method, &settings)
+    }
+
+    fn fit_tree<S, F>(structure: &mut S, fit_method: F, settings: &FitSettings) -> Tree<NodeData>
+    where
+        S: Structure,
+        F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
+    {
+        let (min_sup, max_depth) = (settings.min_sup, settings.max_depth);
         if max_depth <= 2 {
             fit_method(structure, min_sup, max_depth)
         } else {
@@ -61,9 +127,9 @@ impl LGDT {
                     &mut solution_tree,
                     root_index,
                     root_attribute,
-                    min_sup,
                     max_depth - 1,
                     &fit_method,
+                    settings,
                 );
             }
 
@@ -76,23 +142,24 @@ impl LGDT {
         tree: &mut Tree<NodeData>,
         index: Index,
         next: Option<Attribute>,
-        minsup: Support,
         depth: Depth,
         fit_method: &F,
-    ) -> usize
+        settings: &FitSettings,
+    ) -> f64
     where
         S: Structure,
         F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
     {
         return if depth <= 1 {
-            let mut parent_error = 0;
+            let mut parent_error = 0.;
             for (i, val) in [false, true].iter().enumerate() {
                 let _ = structure.push((next.unwrap(), i));
-                let child_tree = fit_method(structure, minsup, 1);
+                let child_tree = Self::fit_child(structure, 1, fit_method, settings);
                 let child_error = LGDT::get_tree_error(&child_tree);
 
-                if child_error == <usize>::MAX {
-                    let child_error = LGDT::create_leaf(tree, structure, index, !*val);
+                if child_error == <f64>::MAX {
+                    let child_error =
+                        LGDT::create_leaf_with_costs(tree, structure, index, !*val, settings.costs);
 
                     parent_error += child_error;
                 } else {
@@ -108,7 +175,7 @@ impl LGDT {
             }
             parent_error
         } else {
-            let mut parent_error = 0;
+            let mut parent_error = 0.;
             for (i, val) in [false, true].iter().enumerate() {
                 // tree.print();
                 // if next.is_none(){
@@ -116,14 +183,15 @@ impl LGDT {
                 // println!("Next: {:?}", (next, i));
                 // println!("Depth : {}", depth - 1);
                 let x = structure.push((next.unwrap(), i));
-                let child_tree = fit_method(structure, minsup, 2);
+                let child_tree = Self::fit_child(structure, 2, fit_method, settings);
                 // child_tree.print();
                 let mut child_error = LGDT::get_tree_error(&child_tree);
-                if child_error == <usize>::MAX {
-                    child_error = LGDT::create_leaf(tree, structure, index, !*val);
+                if child_error == <f64>::MAX {
+                    child_error =
+                        LGDT::create_leaf_with_costs(tree, structure, index, !*val, settings.costs);
                 } else {
                     let child_index = LGDT::create_child(tree, index, !*val);
-                    if child_error == 0 {
+                    if child_error == 0. {
                         LGDT::move_tree(
                             tree,
                             child_index,
@@ -141,9 +209,9 @@ impl LGDT {
                             tree,
                             child_index,
                             child_next,
-                            minsup,
                             depth - 1,
                             fit_method,
+                            settings,
                         );
                     }
                 }
@@ -156,11 +224,32 @@ impl LGDT {
             parent_error
         };
     }
+
+    // An empty tree once cancelled, the child is then left as a leaf
+    fn fit_child<S, F>(
+        structure: &mut S,
+        depth: Depth,
+        fit_method: &F,
+        settings: &FitSettings,
+    ) -> Tree<NodeData>
+    where
+        S: Structure,
+        F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
+    {
+        match settings
+            .cancellation
+            .is_some_and(|token| token.is_cancelled())
+        {
+            true => Tree::new(),
+            false => fit_method(structure, settings.min_sup, depth),
+        }
+    }
 }
 
 #[cfg(test)]
 mod lgdt_test {
     use crate::algorithms::algorithm_trait::{Algorithm, Basic};
+    use crate::algorithms::cancellation::CancellationToken;
     use crate::algorithms::info_gain::InfoGain;
     use crate::algorithms::lgdt::LGDT;
     use crate::algorithms::murtree::MurTree;
@@ -175,17 +264,57 @@ mod lgdt_test {
         let mut structure = BitsetStructure::new(&bitset_data);
 
         let steps = 3;
-        let expected_errors = [151usize, 137, 119, 108, 99, 90, 71, 55, 48, 41];
+        let expected_errors = [151., 137., 119., 108., 99., 90., 71., 55., 48., 41.];
 
         for _ in 0..steps {
             let mut rng = rand::thread_rng();
             let depth = rng.gen_range(1..11) as usize;
-            let a = LGDT::fit(&mut structure, 1, depth, MurTree::fit);
+            let a = LGDT::fit(&mut structure, 1., depth, MurTree::fit);
             let error = LGDT::get_tree_error(&a);
             assert_eq!(expected_errors.contains(&error), true);
         }
     }
 
+    #[test]
+    fn test_lgdt_murtree_unit_costs() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = BitsetStructure::format_input_data(&dataset);
+        let mut structure = BitsetStructure::new(&bitset_data);
+        let costs = vec![vec![0., 1.], vec![1., 0.]];
+
+        for depth in 1..5 {
+            let tree = LGDT::fit_with_costs(
+                &mut structure,
+                1.,
+                depth,
+                |structure, min_sup, depth| {
+                    MurTree::fit_with_costs(structure, min_sup, depth, &costs)
+                },
+                &costs,
+            );
+            let expected = LGDT::fit(&mut structure, 1., depth, MurTree::fit);
+            assert_eq!(LGDT::get_tree_error(&tree), LGDT::get_tree_error(&expected));
+        }
+    }
+
+    #[test]
+    fn test_lgdt_cancelled() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = BitsetStructure::format_input_data(&dataset);
+        let mut structure = BitsetStructure::new(&bitset_data);
+
+        let token = CancellationToken::new();
+        let tree = LGDT::fit_with_cancellation(&mut structure, 1., 4, MurTree::fit, &token);
+        let expected = LGDT::fit(&mut structure, 1., 4, MurTree::fit);
+        assert_eq!(LGDT::get_tree_error(&tree), LGDT::get_tree_error(&expected));
+
+        // Only the root split is grown, its children are leaves
+        token.cancel();
+        let tree = LGDT::fit_with_cancellation(&mut structure, 1., 4, MurTree::fit, &token);
+        assert_eq!(tree.actual_len(), 3);
+        assert!(LGDT::get_tree_error(&tree) > LGDT::get_tree_error(&expected));
+    }
+
     #[test]
     fn test_lgdt_info_gain_anneal() {
         let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
@@ -193,12 +322,12 @@ mod lgdt_test {
         let mut structure = BitsetStructure::new(&bitset_data);
 
         let steps = 3;
-        let expected_errors = [152usize, 151, 149, 126, 89, 79, 69, 57, 50];
+        let expected_errors = [152., 151., 149., 126., 89., 79., 69., 57., 50.];
         for _ in 0..steps {
             let mut rng = rand::thread_rng();
             let depth = rng.gen_range(1..11) as usize;
 
-            let a = LGDT::fit(&mut structure, 1, depth, InfoGain::fit);
+            let a = LGDT::fit(&mut structure, 1., depth, InfoGain::fit);
             let error = LGDT::get_tree_error(&a);
             assert_eq!(expected_errors.contains(&error), true);
         }
@@ -1,4 +1,5 @@
 pub mod algorithm_trait;
+pub mod cancellation;
 pub mod dl85;
 pub mod dl85_utils;
 pub mod idk;
@@ -1,7 +1,7 @@
 use crate::algorithms::algorithm_trait::{Algorithm, Basic};
-use crate::structures::binary_tree::{NodeData, Tree};
+use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::Support;
+use crate::structures::structures_types::{Attribute, CostMatrix, Depth, Support};
 
 pub struct MurTree {}
 
@@ -9,6 +9,144 @@ impl Basic for MurTree {}
 
 impl Algorithm for MurTree {
     fn build_depth_one_tree<S>(structure: &mut S, min_sup: Support) -> Tree<NodeData>
+    where
+        S: Structure,
+    {
+        Self::depth_one_tree(structure, min_sup, None)
+    }
+
+    fn build_depth_two_tree<S>(structure: &mut S, min_sup: Support) -> Tree<NodeData>
+    where
+        S: Structure,
+    {
+        Self::depth_two_tree(structure, min_sup, None)
+    }
+}
+
+impl MurTree {
+    /// Same as `fit`, the leaves predict the class of minimum expected cost.
+    pub fn fit_with_costs<S>(
+        structure: &mut S,
+        min_sup: Support,
+        max_depth: Depth,
+        costs: &CostMatrix,
+    ) -> Tree<NodeData>
+    where
+        S: Structure,
+    {
+        match max_depth < 2 {
+            true => Self::depth_one_tree(structure, min_sup, Some(costs)),
+            false => Self::depth_two_tree(structure, min_sup, Some(costs)),
+        }
+    }
+
+    /// Best tree of depth at most two with at most `leaves` leaves, the leaves predict the class of
+    /// minimum expected cost when `costs` is given.
+    pub fn fit_with_leaves<S>(
+        structure: &mut S,
+        min_sup: Support,
+        leaves: usize,
+        costs: Option<&CostMatrix>,
+    ) -> Tree<NodeData>
+    where
+        S: Structure,
+    {
+        let candidates = Self::generate_candidates_list(structure, min_sup);
+        if leaves < 2 || candidates.is_empty() {
+            let mut tree = Tree::new();
+            tree.add_root(Self::leaf_node(structure.labels_support(), costs));
+            return tree;
+        }
+        match leaves {
+            2 => Self::depth_one_tree(structure, min_sup, costs),
+            3 => Self::three_leaves_tree(structure, min_sup, &candidates, costs),
+            _ => Self::depth_two_tree(structure, min_sup, costs),
+        }
+    }
+
+    // A root split with one child split again, the best depth one tree when no child can be split
+    fn three_leaves_tree<S>(
+        structure: &mut S,
+        min_sup: Support,
+        candidates: &Vec<Attribute>,
+        costs: Option<&CostMatrix>,
+    ) -> Tree<NodeData>
+    where
+        S: Structure,
+    {
+        let matrix = Self::build_depth_two_matrix(structure, candidates);
+        let classes_support = structure.labels_support().to_vec();
+
+        // The error, the two splits, the side of the split child and the supports of the leaves
+        type Split = (f64, usize, usize, bool, [Vec<Support>; 3]);
+        let mut best: Option<Split> = None;
+        for i in 0..candidates.len() {
+            let right = &matrix[i][i];
+            let left = Self::get_diff_errors(&classes_support, right);
+            for (j, second) in matrix.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let left_right = Self::get_diff_errors(&second[j], &matrix[i][j]);
+                let left_left = Self::get_diff_errors(&left, &left_right);
+                let right_left = Self::get_diff_errors(right, &matrix[i][j]);
+                let splits = [
+                    (true, [left_left, left_right, right.clone()]),
+                    (false, [right_left, matrix[i][j].clone(), left.clone()]),
+                ];
+                for (is_left, leaves) in splits {
+                    if leaves[..2]
+                        .iter()
+                        .any(|leaf| leaf.iter().sum::<Support>() < min_sup)
+                    {
+                        continue;
+                    }
+                    let error = leaves
+                        .iter()
+                        .map(|leaf| Self::get_leaf_cost(leaf, costs).0)
+                        .sum::<f64>();
+                    if best.as_ref().is_none_or(|best| error < best.0) {
+                        best = Some((error, i, j, is_left, leaves));
+                    }
+                }
+            }
+        }
+
+        let (error, first, second, is_left, leaves) = match best {
+            Some(best) => best,
+            None => return Self::depth_one_tree(structure, min_sup, costs),
+        };
+        let mut tree = Tree::new();
+        let mut root = NodeData::new();
+        root.test = Some(candidates[first]);
+        root.error = error;
+        let root = tree.add_root(TreeNode::new(root));
+        let mut split = NodeData::new();
+        split.test = Some(candidates[second]);
+        split.error = leaves[..2]
+            .iter()
+            .map(|leaf| Self::get_leaf_cost(leaf, costs).0)
+            .sum();
+        let split = tree.add_node(root, is_left, TreeNode::new(split));
+        tree.add_node(split, true, Self::leaf_node(&leaves[0], costs));
+        tree.add_node(split, false, Self::leaf_node(&leaves[1], costs));
+        tree.add_node(root, !is_left, Self::leaf_node(&leaves[2], costs));
+        tree
+    }
+
+    fn leaf_node(classes_support: &[Support], costs: Option<&CostMatrix>) -> TreeNode<NodeData> {
+        let (error, class) = Self::get_leaf_cost(classes_support, costs);
+        let mut leaf = NodeData::new();
+        leaf.error = error;
+        leaf.out = Some(class);
+        TreeNode::new(leaf)
+    }
+
+    fn depth_one_tree<S>(
+        structure: &mut S,
+        min_sup: Support,
+        costs: Option<&CostMatrix>,
+    ) -> Tree<NodeData>
     where
         S: Structure,
     {
@@ -28,16 +166,16 @@ impl Algorithm for MurTree {
         for candidate in candidates.iter() {
             structure.push((*candidate, 0));
             let classes_support = structure.labels_support();
-            let left_error = Self::get_leaf_error(classes_support);
+            let left_error = Self::get_leaf_cost(classes_support, costs);
             structure.backtrack();
 
             structure.push((*candidate, 1));
             let classes_support = structure.labels_support();
-            let right_error = Self::get_leaf_error(classes_support);
+            let right_error = Self::get_leaf_cost(classes_support, costs);
             structure.backtrack();
 
             let error = left_error.0 + right_error.0;
-            let mut past_error = <usize>::MAX;
+            let mut past_error = <f64>::MAX;
 
             if let Some(root) = tree.get_node_mut(tree.get_root_index()) {
                 past_error = root.value.error;
@@ -60,7 +198,11 @@ impl Algorithm for MurTree {
         tree
     }
 
-    fn build_depth_two_tree<S>(structure: &mut S, min_sup: Support) -> Tree<NodeData>
+    fn depth_two_tree<S>(
+        structure: &mut S,
+        min_sup: Support,
+        costs: Option<&CostMatrix>,
+    ) -> Tree<NodeData>
     where
         S: Structure,
     {
@@ -71,7 +213,7 @@ impl Algorithm for MurTree {
             return Self::empty_tree(2);
         }
         if candidates.len() < 2 {
-            return Self::build_depth_one_tree(structure, min_sup);
+            return Self::depth_one_tree(structure, min_sup, costs);
         }
 
         let matrix = Self::build_depth_two_matrix(structure, &candidates);
@@ -80,7 +222,7 @@ impl Algorithm for MurTree {
         let classes_support = structure.labels_support().to_vec();
         let support = structure.get_support();
 
-        let before_creation_error = Self::get_leaf_error(&classes_support);
+        let before_creation_error = Self::get_leaf_cost(&classes_support, costs);
 
         for (i, first) in candidates.iter().enumerate() {
             // LEFT PART
@@ -91,7 +233,7 @@ impl Algorithm for MurTree {
             let i_left_classes_support =
                 Self::get_diff_errors(&classes_support, i_right_classes_support);
 
-            let root_right_support = matrix[i][i].iter().sum::<usize>();
+            let root_right_support = matrix[i][i].iter().sum::<Support>();
             let root_left_support = support - root_right_support;
             // println!("Left sup : {}, right_sup {}", root_right_support, root_left_support);
             if root_left_support < min_sup || root_right_support < min_sup {
@@ -113,8 +255,8 @@ impl Algorithm for MurTree {
 
             // TODO : Check if support in enough 2 * min_sup
 
-            if root_left_support < 2 * min_sup {
-                let error = Self::get_leaf_error(&i_left_classes_support);
+            if root_left_support < 2. * min_sup {
+                let error = Self::get_leaf_cost(&i_left_classes_support, costs);
                 if let Some(left_node) = root_tree.get_node_mut(left_index) {
                     left_node.value.error = error.0;
                     left_node.value.out = Some(error.1);
@@ -127,11 +269,11 @@ impl Algorithm for MurTree {
                     }
                 }
             } else {
-                let mut feat_error = <usize>::MAX;
+                let mut feat_error = <f64>::MAX;
                 if let Some(root) = tree.get_node_mut(tree.get_root_index()) {
                     feat_error = root.value.error;
                 }
-                let error = Self::get_leaf_error(&i_left_classes_support);
+                let error = Self::get_leaf_cost(&i_left_classes_support, costs);
 
                 if error.0 < feat_error {
                     if let Some(left_node) = root_tree.get_node_mut(left_index) {
@@ -150,8 +292,8 @@ impl Algorithm for MurTree {
                     // Here is if the left part of the tree so the classes support of i__left -> j__right
                     let i_left_j_right_classes_support =
                         Self::get_diff_errors(&matrix[j][j], &matrix[i][j]);
-                    let j_right_support = matrix[j][j].iter().sum::<usize>();
-                    let i_right_j_right_support = matrix[i][j].iter().sum::<usize>();
+                    let j_right_support = matrix[j][j].iter().sum::<Support>();
+                    let i_right_j_right_support = matrix[i][j].iter().sum::<Support>();
                     let i_left_j_right_support = j_right_support - i_right_j_right_support; // Important
                     let i_left_j_left_support = root_left_support - i_left_j_right_support; // Important
 
@@ -160,7 +302,8 @@ impl Algorithm for MurTree {
                         continue;
                     }
 
-                    let right_leaf_error = Self::get_leaf_error(&i_left_j_right_classes_support);
+                    let right_leaf_error =
+                        Self::get_leaf_cost(&i_left_j_right_classes_support, costs);
 
                     // TODO Upper bound control here
                     if right_leaf_error.0 >= feat_error {
@@ -171,7 +314,8 @@ impl Algorithm for MurTree {
                         &i_left_classes_support,
                         &i_left_j_right_classes_support,
                     );
-                    let left_leaf_error = Self::get_leaf_error(&i_left_j_left_classes_support);
+                    let left_leaf_error =
+                        Self::get_leaf_cost(&i_left_j_left_classes_support, costs);
 
                     // TODO Upper bound control here
                     // println!("Feature error : {:?}", feat_error);
@@ -206,7 +350,7 @@ impl Algorithm for MurTree {
 
                     feat_error = left_leaf_error.0 + right_leaf_error.0;
 
-                    if feat_error == 0 {
+                    if feat_error == 0. {
                         break;
                     }
                 }
@@ -215,8 +359,8 @@ impl Algorithm for MurTree {
             // println!("Root tree Before going to the right");
             // root_tree.print();
 
-            if root_right_support < 2 * min_sup {
-                let error = Self::get_leaf_error(i_right_classes_support);
+            if root_right_support < 2. * min_sup {
+                let error = Self::get_leaf_cost(i_right_classes_support, costs);
                 if let Some(right_node) = root_tree.get_node_mut(right_index) {
                     right_node.value.error = error.0;
                     right_node.value.out = Some(error.1);
@@ -224,7 +368,7 @@ impl Algorithm for MurTree {
                     right_node.right = 0;
                 }
 
-                let mut current_left_error = <usize>::MAX;
+                let mut current_left_error = <f64>::MAX;
                 let best_error = tree.get_node(tree.get_root_index()).unwrap().value.error;
 
                 if let Some(node) = root_tree.get_node(left_index) {
@@ -235,12 +379,12 @@ impl Algorithm for MurTree {
                     continue;
                 }
             } else {
-                let mut feat_error = <usize>::MAX;
+                let mut feat_error = <f64>::MAX;
                 if let Some(root) = tree.get_node(tree.get_root_index()) {
                     feat_error = root.value.error;
                 }
                 // println!("Before feat_error: {}", feat_error);
-                let mut current_left_error = <usize>::MAX;
+                let mut current_left_error = <f64>::MAX;
                 //root_tree.print();
                 if let Some(left_node) = root_tree.get_node(left_index) {
                     current_left_error = left_node.value.error;
@@ -249,7 +393,7 @@ impl Algorithm for MurTree {
 
                 // println!("After feat_error: {}", feat_error);
 
-                let error = Self::get_leaf_error(i_right_classes_support);
+                let error = Self::get_leaf_cost(i_right_classes_support, costs);
                 // println!("Error: {:?}", error);
                 // println!("I right classes support: {:?}", i_right_classes_support);
                 if current_left_error > feat_error || error.0 < feat_error - current_left_error {
@@ -272,7 +416,7 @@ impl Algorithm for MurTree {
                     let i_right_j_right_classes_support = &matrix[i][j];
                     let i_right_j_left_classes_support =
                         Self::get_diff_errors(&matrix[i][i], &matrix[i][j]);
-                    let i_right_j_right_support = matrix[i][j].iter().sum::<usize>();
+                    let i_right_j_right_support = matrix[i][j].iter().sum::<Support>();
                     let i_right_j_left_support = root_right_support - i_right_j_right_support; // Important
 
                     if i_right_j_left_support < min_sup || i_right_j_right_support < min_sup {
@@ -280,14 +424,16 @@ impl Algorithm for MurTree {
                         continue;
                     }
 
-                    let left_leaf_error = Self::get_leaf_error(&i_right_j_left_classes_support);
+                    let left_leaf_error =
+                        Self::get_leaf_cost(&i_right_j_left_classes_support, costs);
 
                     // TODO Upper bound control here
                     if left_leaf_error.0 >= feat_error {
                         continue;
                     }
 
-                    let right_leaf_error = Self::get_leaf_error(i_right_j_right_classes_support);
+                    let right_leaf_error =
+                        Self::get_leaf_cost(i_right_j_right_classes_support, costs);
 
                     // TODO Upper bound control here
                     if (left_leaf_error.0 + right_leaf_error.0) >= feat_error {
@@ -315,24 +461,24 @@ impl Algorithm for MurTree {
                     }
 
                     feat_error = left_leaf_error.0 + right_leaf_error.0;
-                    if feat_error == 0 {
+                    if feat_error == 0. {
                         break;
                     }
                 }
 
-                let mut feat_error = <usize>::MAX;
+                let mut feat_error = <f64>::MAX;
                 if let Some(node) = root_tree.get_node(left_index) {
                     feat_error = node.value.error;
                 }
                 if let Some(node) = root_tree.get_node(right_index) {
-                    feat_error = feat_error.saturating_add(node.value.error);
+                    feat_error += node.value.error;
                 }
 
                 if let Some(feat_tree) = root_tree.get_node_mut(tree.get_root_index()) {
                     feat_tree.value.error = feat_error;
                 }
 
-                let mut best_error = <usize>::MAX;
+                let mut best_error = <f64>::MAX;
                 if let Some(node) = tree.get_node(tree.get_root_index()) {
                     best_error = node.value.error;
                 }
@@ -343,7 +489,7 @@ impl Algorithm for MurTree {
                     tree = root_tree;
                 }
                 // tree.print();
-                if feat_error == 0 {
+                if feat_error == 0. {
                     break;
                 }
             }
@@ -356,7 +502,7 @@ impl Algorithm for MurTree {
 
 #[cfg(test)]
 mod murtree_test {
-    use crate::algorithms::algorithm_trait::Algorithm;
+    use crate::algorithms::algorithm_trait::{Algorithm, Basic};
     use crate::algorithms::murtree::MurTree;
     use crate::dataset::binary_dataset::BinaryDataset;
     use crate::dataset::data_trait::Dataset;
@@ -8311,4 +8457,43 @@ mod murtree_test {
     //     let matrix = MurTree::build_depth_two_matrix(&mut structure, &candidates);
     //     assert_eq!(matrix.iter().eq(expected_matrix.iter()), true);
     // }
+
+    #[test]
+    fn test_multiclass_depth_two() {
+        let dataset = BinaryDataset::load("test_data/multiclass.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        assert_eq!(structure.labels_support(), &[3., 3., 6.]);
+
+        let tree = MurTree::build_depth_one_tree(&mut structure, 1.);
+        assert_eq!(MurTree::get_tree_error(&tree), 3.);
+
+        let tree = MurTree::build_depth_two_tree(&mut structure, 1.);
+        assert_eq!(MurTree::get_tree_error(&tree), 0.);
+    }
+
+    #[test]
+    fn test_cost_matrix() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+
+        let unit_costs = vec![vec![0., 1.], vec![1., 0.]];
+        for depth in 1..3 {
+            let tree = MurTree::fit_with_costs(&mut structure, 1., depth, &unit_costs);
+            let expected = MurTree::fit(&mut structure, 1., depth);
+            assert_eq!(
+                MurTree::get_tree_error(&tree),
+                MurTree::get_tree_error(&expected)
+            );
+        }
+
+        let costs = vec![vec![0., 3.], vec![3., 0.]];
+        let tree = MurTree::fit_with_costs(&mut structure, 1., 2, &costs);
+        let expected = MurTree::fit(&mut structure, 1., 2);
+        assert_eq!(
+            MurTree::get_tree_error(&tree),
+            3. * MurTree::get_tree_error(&expected)
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use crate::algorithms::algorithm_trait::{Algorithm, Basic};
+use crate::algorithms::cancellation::{run_on_pool, CancellationToken};
 use crate::algorithms::lgdt::LGDT;
 use crate::algorithms::murtree::MurTree;
 use crate::structures;
@@ -10,11 +11,11 @@ use crate::structures::structures_types::{
 use pyo3::ffi::lenfunc;
 use rayon::prelude::IntoParallelIterator;
 use rayon::prelude::ParallelIterator;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 pub struct ParallelLGDT {
     tree: Option<Tree<NodeData>>,
-    error: Option<usize>,
+    error: Option<f64>,
 }
 
 impl Basic for ParallelLGDT {}
@@ -79,17 +80,63 @@ impl ParallelLGDT {
         n_threads: usize,
         fit_method: F,
     ) -> Tree<NodeData>
+    where
+        S: Structure + Clone + Send + structures::structure_trait::BitsetTrait,
+        F: Fn(&mut S, Support, Depth) -> Tree<NodeData> + Send + Sync,
+    {
+        Self::fit_tree(structure, min_sup, max_depth, n_threads, fit_method, None)
+    }
+
+    /// Same as `fit`, the subtrees not grown yet once `token` is cancelled are left as leaves.
+    /// The calling thread checks `token` while the workers run and stops them with it.
+    pub fn fit_with_cancellation<S, F>(
+        structure: &mut S,
+        min_sup: Support,
+        max_depth: Depth,
+        n_threads: usize,
+        fit_method: F,
+        token: &CancellationToken,
+    ) -> Tree<NodeData>
+    where
+        S: Structure + Clone + Send + structures::structure_trait::BitsetTrait,
+        F: Fn(&mut S, Support, Depth) -> Tree<NodeData> + Send + Sync,
+    {
+        Self::fit_tree(
+            structure,
+            min_sup,
+            max_depth,
+            n_threads,
+            fit_method,
+            Some(token),
+        )
+    }
+
+    fn fit_tree<S, F>(
+        structure: &mut S,
+        min_sup: Support,
+        max_depth: Depth,
+        n_threads: usize,
+        fit_method: F,
+        cancellation: Option<&CancellationToken>,
+    ) -> Tree<NodeData>
     where
         S: Structure + Clone + Send + structures::structure_trait::BitsetTrait,
         F: Fn(&mut S, Support, Depth) -> Tree<NodeData> + Send + Sync,
     {
         let method = Arc::new(fit_method);
+        let token = cancellation.cloned().unwrap_or_default();
 
         if max_depth <= 3 {
-            return LGDT::fit(structure, min_sup, max_depth, method.as_ref());
+            return LGDT::fit_with_cancellation(
+                structure,
+                min_sup,
+                max_depth,
+                method.as_ref(),
+                &token,
+            );
         }
 
-        let mut tree = LGDT::fit(structure, min_sup, 4, method.as_ref());
+        let mut tree = LGDT::fit_with_cancellation(structure, min_sup, 4, method.as_ref(), &token);
         let root_index = tree.get_root_index();
         Self::remove_below_depth(&mut tree, 3, root_index, &mut vec![]);
         let mut state_collection = vec![];
@@ -103,31 +150,35 @@ impl ParallelLGDT {
             .num_threads(n_threads)
             .build()
             .unwrap();
-        let output = Arc::new(Mutex::new(tree));
-        let main_handler = output.clone();
 
-        let thread_structure = structure.clone();
-
-        pool.scope(move |s| {
-            for collection in state_collection {
-                if collection.error == 0 {
-                    continue;
-                }
-                let mut sub_struct = thread_structure.clone();
+        let jobs = state_collection
+            .into_iter()
+            .filter(|collection| collection.error != 0.)
+            .map(|collection| {
+                let mut sub_struct = structure.clone();
                 sub_struct.set_state(&collection.bitset, &collection.position);
-                let tree_handler = main_handler.clone();
-                let method = method.clone();
-                s.spawn(move |_| {
-                    let tr = LGDT::fit(&mut sub_struct, min_sup, remaining_depth, method.as_ref());
-                    let mut out = tree_handler.lock().unwrap();
-                    LGDT::move_tree(&mut out, collection.index, &tr, tr.get_root_index());
-                    drop(out);
-                });
-            }
-        });
-
-        let x = output.lock().unwrap();
-        x.clone()
+                (collection.index, sub_struct)
+            })
+            .collect::<Vec<_>>();
+        let subtrees = run_on_pool(
+            &pool,
+            jobs,
+            Some(&token),
+            |(index, mut sub_struct), token| {
+                let tr = LGDT::fit_with_cancellation(
+                    &mut sub_struct,
+                    min_sup,
+                    remaining_depth,
+                    method.as_ref(),
+                    token,
+                );
+                (index, tr)
+            },
+        );
+        for (index, tr) in subtrees {
+            LGDT::move_tree(&mut tree, index, &tr, tr.get_root_index());
+        }
+        tree
     }
 
     fn build_tree_recurse<S, F>(
@@ -138,19 +189,19 @@ impl ParallelLGDT {
         minsup: Support,
         depth: Depth,
         fit_method: &F,
-    ) -> usize
+    ) -> f64
     where
         S: Structure,
         F: Fn(&mut S, Support, Depth) -> Tree<NodeData>,
     {
         return if depth <= 1 {
-            let mut parent_error = 0;
+            let mut parent_error = 0.;
             for (i, val) in [false, true].iter().enumerate() {
                 let _ = structure.push((next.unwrap(), i));
                 let child_tree = fit_method(structure, minsup, 1);
                 let child_error = Self::get_tree_error(&child_tree);
 
-                if child_error == <usize>::MAX {
+                if child_error == <f64>::MAX {
                     let child_error = Self::create_leaf(tree, structure, index, !*val);
 
                     parent_error += child_error;
@@ -167,16 +218,16 @@ impl ParallelLGDT {
             }
             parent_error
         } else {
-            let mut parent_error = 0;
+            let mut parent_error = 0.;
             for (i, val) in [false, true].iter().enumerate() {
                 let _ = structure.push((next.unwrap(), i));
                 let child_tree = fit_method(structure, minsup, 2);
                 let mut child_error = Self::get_tree_error(&child_tree);
-                if child_error == <usize>::MAX {
+                if child_error == <f64>::MAX {
                     child_error = Self::create_leaf(tree, structure, index, !*val);
                 } else {
                     let child_index = Self::create_child(tree, index, !*val);
-                    if child_error == 0 {
+                    if child_error == 0. {
                         Self::move_tree(
                             tree,
                             child_index,
@@ -1,9 +1,9 @@
 use self::super::data_trait::Dataset;
 use super::data_types::Data;
+use crate::errors::{PyTreesError, PyTreesResult};
 use ndarray::{Array, IxDyn};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
-use std::collections::HashSet;
 
 pub struct BinaryDataset {
     filename: String,
@@ -11,6 +11,8 @@ pub struct BinaryDataset {
     split: f64,
     train: Data,
     test: Option<Data>,
+    sample_weights: Option<Vec<f64>>,
+    regression_targets: Option<Vec<f64>>,
     size: usize,
     train_size: usize,
     num_labels: usize,
@@ -38,7 +40,7 @@ impl Dataset for BinaryDataset {
         let train = BinaryDataset::create_set(data);
         let train_size = train.0.len();
         let num_attributes = train.1[0].len();
-        let num_labels = train.0.iter().collect::<HashSet<_>>().len();
+        let num_labels = Self::count_labels(&train.0);
 
         Self {
             filename: filename.to_string(),
@@ -46,6 +48,8 @@ impl Dataset for BinaryDataset {
             split,
             train,
             test,
+            sample_weights: None,
+            regression_targets: None,
             size,
             train_size,
             num_labels,
@@ -61,7 +65,7 @@ impl Dataset for BinaryDataset {
         }
         let train_size = inputs.len();
         let num_attributes = inputs[0].len();
-        let num_labels = targets.iter().collect::<HashSet<_>>().len();
+        let num_labels = Self::count_labels(&targets);
         let train: Data = (targets, inputs);
 
         Self {
@@ -70,6 +74,8 @@ impl Dataset for BinaryDataset {
             split: 0.0f64,
             train,
             test: None,
+            sample_weights: None,
+            regression_targets: None,
             size: train_size,
             train_size,
             num_labels,
@@ -96,9 +102,52 @@ impl Dataset for BinaryDataset {
     fn get_train(&self) -> &Data {
         &self.train
     }
+
+    fn get_sample_weights(&self) -> Option<&[f64]> {
+        self.sample_weights.as_deref()
+    }
+
+    fn get_regression_targets(&self) -> Option<&[f64]> {
+        self.regression_targets.as_deref()
+    }
 }
 
 impl BinaryDataset {
+    /// Sets one non-negative weight per training row, a weight acts as a multiplicity of the row.
+    pub fn set_sample_weights(&mut self, weights: Vec<f64>) -> PyTreesResult<()> {
+        if weights.len() != self.train_size {
+            return Err(PyTreesError::InvalidParameter(
+                "Sample weights must have one value per training row".to_string(),
+            ));
+        }
+        if weights
+            .iter()
+            .any(|weight| !weight.is_finite() || *weight < 0.)
+        {
+            return Err(PyTreesError::InvalidParameter(
+                "Sample weights must be finite and non-negative".to_string(),
+            ));
+        }
+        self.sample_weights = Some(weights);
+        Ok(())
+    }
+
+    /// Turns the dataset into a regression one, each training row gets a real valued target.
+    /// The class labels are dropped and a single dummy label is kept for the structures.
+    pub fn set_regression_targets(&mut self, targets: Vec<f64>) {
+        if targets.len() != self.train_size {
+            panic!("Regression targets must have one value per training row");
+        }
+        self.train.0 = vec![0; self.train_size];
+        self.num_labels = 1;
+        self.regression_targets = Some(targets);
+    }
+
+    // Labels are used as indexes in the structures, so the count must cover the largest one
+    fn count_labels(targets: &[usize]) -> usize {
+        targets.iter().max().map_or(0, |label| label + 1)
+    }
+
     fn create_set(data: Vec<String>) -> Data {
         let data = data
             .iter()
@@ -18,6 +18,10 @@ pub trait Dataset {
 
     fn get_train(&self) -> &Data;
 
+    fn get_sample_weights(&self) -> Option<&[f64]>;
+
+    fn get_regression_targets(&self) -> Option<&[f64]>;
+
     fn open_file(filename: &str) -> Result<Vec<String>, Error> {
         let input = File::open(filename)?; //Error Handling for missing filename
         let buffered = BufReader::new(input); // Buffer for the file
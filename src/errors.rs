@@ -0,0 +1,54 @@
+use pyo3::exceptions::{PyMemoryError, PyOSError, PyRuntimeError, PyValueError};
+use pyo3::PyErr;
+use std::fmt::{Display, Formatter};
+
+/// Errors of the searches and of their setup, raised as Python exceptions by the bindings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PyTreesError {
+    /// A parameter out of its range, or an input the search cannot use. Raised as `ValueError`.
+    InvalidParameter(String),
+    /// Options that cannot be combined. Raised as `ValueError`.
+    Unsupported(String),
+    /// The memory requested could not be allocated. Raised as `MemoryError`.
+    Memory(String),
+    /// The search could not run to its end. Raised as `RuntimeError`, `SearchFailedError` on the
+    /// Python side.
+    Search(String),
+    /// A cache file could not be read or written. Raised as `OSError`.
+    Io(String),
+}
+
+pub type PyTreesResult<T> = Result<T, PyTreesError>;
+
+impl Display for PyTreesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PyTreesError::InvalidParameter(message)
+            | PyTreesError::Unsupported(message)
+            | PyTreesError::Memory(message)
+            | PyTreesError::Search(message)
+            | PyTreesError::Io(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for PyTreesError {}
+
+impl From<PyTreesError> for PyErr {
+    fn from(error: PyTreesError) -> Self {
+        match error {
+            PyTreesError::InvalidParameter(message) | PyTreesError::Unsupported(message) => {
+                PyValueError::new_err(message)
+            }
+            PyTreesError::Memory(message) => PyMemoryError::new_err(message),
+            PyTreesError::Search(message) => PyRuntimeError::new_err(message),
+            PyTreesError::Io(message) => PyOSError::new_err(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for PyTreesError {
+    fn from(error: std::io::Error) -> Self {
+        PyTreesError::Io(error.to_string())
+    }
+}
@@ -1,26 +1,23 @@
-use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
 use crate::structures::structure_trait::Structure;
 use crate::structures::structures_types::{Attribute, Support};
 use float_cmp::{ApproxEq, F64Margin};
 
-pub type DataStructure<'a> = RSparseBitsetStructure<'a>;
-
 pub trait Heuristic {
-    fn compute(&self, structure: &mut DataStructure, candidates: &mut Vec<Attribute>);
+    fn compute(&self, structure: &mut dyn Structure, candidates: &mut Vec<Attribute>);
 }
 
 #[derive(Default)]
 pub struct NoHeuristic;
 
 impl Heuristic for NoHeuristic {
-    fn compute(&self, _structure: &mut DataStructure, _candidates: &mut Vec<Attribute>) {}
+    fn compute(&self, _structure: &mut dyn Structure, _candidates: &mut Vec<Attribute>) {}
 }
 
 #[derive(Default)]
 pub struct GiniIndex;
 
 impl Heuristic for GiniIndex {
-    fn compute(&self, structure: &mut DataStructure, candidates: &mut Vec<Attribute>) {
+    fn compute(&self, structure: &mut dyn Structure, candidates: &mut Vec<Attribute>) {
         let mut root_classes_support = structure.labels_support().to_vec();
         let mut candidates_sorted = vec![];
         for attribute in candidates.iter() {
@@ -38,8 +35,8 @@ impl Heuristic for GiniIndex {
 impl GiniIndex {
     fn gini_index(
         attribute: Attribute,
-        structure: &mut DataStructure,
-        root_classes_support: &[usize],
+        structure: &mut dyn Structure,
+        root_classes_support: &[Support],
     ) -> f64 {
         let _ = structure.push((attribute, 0));
         let left_classes_supports = structure.labels_support().to_vec();
@@ -49,32 +46,31 @@ impl GiniIndex {
             .iter()
             .enumerate()
             .map(|(idx, val)| *val - left_classes_supports[idx])
-            .collect::<Vec<usize>>();
+            .collect::<Vec<Support>>();
 
-        let actual_size = root_classes_support.iter().sum::<usize>() as f64;
-        let left_split_size = left_classes_supports.iter().sum::<usize>();
-        let right_split_size = right_classes_support.iter().sum::<usize>();
+        let actual_size = root_classes_support.iter().sum::<Support>();
+        let left_split_size = left_classes_supports.iter().sum::<Support>();
+        let right_split_size = right_classes_support.iter().sum::<Support>();
 
         let mut left_gini_index = 0f64;
         let mut right_gini_index = 0f64;
 
         for class in 0..root_classes_support.len() {
-            let p = match left_split_size {
-                0 => 0f64,
-                _ => (left_classes_supports[class] as f64 / left_split_size as f64).powf(2.),
+            let p = match left_split_size == 0. {
+                true => 0f64,
+                false => (left_classes_supports[class] / left_split_size).powf(2.),
             };
 
             left_gini_index += p;
 
-            let p = match right_split_size {
-                0 => 0f64,
-                _ => (right_classes_support[class] as f64 / right_split_size as f64).powf(2.),
+            let p = match right_split_size == 0. {
+                true => 0f64,
+                false => (right_classes_support[class] / right_split_size).powf(2.),
             };
 
             right_gini_index += p
         }
-        ((left_split_size as f64) * (1. - left_gini_index)
-            + (right_split_size as f64) * (1. - right_gini_index))
+        (left_split_size * (1. - left_gini_index) + right_split_size * (1. - right_gini_index))
             / actual_size
     }
 }
@@ -85,8 +81,8 @@ pub struct InformationGain;
 impl Handler for InformationGain {}
 
 impl Heuristic for InformationGain {
-    fn compute(&self, structure: &mut DataStructure, candidates: &mut Vec<Attribute>) {
-        self.internally_compute(structure, candidates, false);
+    fn compute(&self, structure: &mut dyn Structure, candidates: &mut Vec<Attribute>) {
+        Self::internally_compute(structure, candidates, false);
     }
 }
 
@@ -96,20 +92,19 @@ pub struct InformationGainRatio;
 impl Handler for InformationGainRatio {}
 
 impl Heuristic for InformationGainRatio {
-    fn compute(&self, structure: &mut DataStructure, candidates: &mut Vec<Attribute>) {
-        self.internally_compute(structure, candidates, true);
+    fn compute(&self, structure: &mut dyn Structure, candidates: &mut Vec<Attribute>) {
+        Self::internally_compute(structure, candidates, true);
     }
 }
 
-// Information Gain and Information Gain Ratio handler
+// Information Gain and Information Gain Ratio handler, shared with the greedy algorithms
 
-trait Handler {
+pub(crate) trait Handler {
     fn internally_compute(
-        &self,
-        structure: &mut DataStructure,
+        structure: &mut dyn Structure,
         attributes: &mut Vec<Attribute>,
         ratio: bool,
-    ) {
+    ) -> f64 {
         let root_classes_support = structure.labels_support().to_vec();
         let parent_entropy = Self::compute_entropy(&root_classes_support);
         let mut candidates_sorted = vec![];
@@ -128,12 +123,13 @@ trait Handler {
             .iter()
             .map(|(a, _)| *a)
             .collect::<Vec<Attribute>>();
+        candidates_sorted.first().map_or(0., |(_, gain)| *gain)
     }
 
     fn information_gain(
         attribute: Attribute,
-        structure: &mut DataStructure,
-        root_classes_support: &[usize],
+        structure: &mut dyn Structure,
+        root_classes_support: &[Support],
         parent_entropy: f64,
         ratio: bool,
     ) -> f64 {
@@ -145,20 +141,20 @@ trait Handler {
             .iter()
             .enumerate()
             .map(|(idx, val)| *val - left_classes_supports[idx])
-            .collect::<Vec<usize>>();
+            .collect::<Vec<Support>>();
 
-        let actual_size = root_classes_support.iter().sum::<usize>();
-        let left_split_size = left_classes_supports.iter().sum::<usize>();
-        let right_split_size = right_classes_support.iter().sum::<usize>();
+        let actual_size = root_classes_support.iter().sum::<Support>();
+        let left_split_size = left_classes_supports.iter().sum::<Support>();
+        let right_split_size = right_classes_support.iter().sum::<Support>();
 
-        let left_weight = match actual_size {
-            0 => 0f64,
-            _ => left_split_size as f64 / actual_size as f64,
+        let left_weight = match actual_size == 0. {
+            true => 0f64,
+            false => left_split_size / actual_size,
         };
 
-        let right_weight = match actual_size {
-            0 => 0f64,
-            _ => right_split_size as f64 / actual_size as f64,
+        let right_weight = match actual_size == 0. {
+            true => 0f64,
+            false => right_split_size / actual_size,
         };
 
         let mut split_info = 0f64;
@@ -191,13 +187,13 @@ trait Handler {
         info_gain
     }
 
-    fn compute_entropy(covers: &[usize]) -> f64 {
-        let support = covers.iter().sum::<usize>();
+    fn compute_entropy(covers: &[Support]) -> f64 {
+        let support = covers.iter().sum::<Support>();
         let mut entropy = 0f64;
         for class_support in covers {
-            let p = match support {
-                0 => 0f64,
-                _ => *class_support as f64 / support as f64,
+            let p = match support == 0. {
+                true => 0f64,
+                false => class_support / support,
             };
 
             let mut log_val = 0f64;
@@ -209,3 +205,52 @@ trait Handler {
         entropy
     }
 }
+
+#[cfg(test)]
+mod heuristics_test {
+    use crate::dataset::binary_dataset::BinaryDataset;
+    use crate::dataset::data_trait::Dataset;
+    use crate::heuristics::{GiniIndex, Heuristic, InformationGain, InformationGainRatio};
+    use crate::structures::bitsets_structure::BitsetStructure;
+    use crate::structures::horizontal_binary_structure::HorizontalBinaryStructure;
+    use crate::structures::raw_binary_structure::RawBinaryStructure;
+    use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
+    use crate::structures::structure_trait::Structure;
+    use crate::structures::structures_types::Attribute;
+
+    fn sort_candidates(heuristic: &dyn Heuristic, structure: &mut dyn Structure) -> Vec<Attribute> {
+        let mut candidates = (0..structure.num_attributes()).collect::<Vec<Attribute>>();
+        heuristic.compute(structure, &mut candidates);
+        candidates
+    }
+
+    #[test]
+    fn same_ordering_on_every_structure() {
+        let dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let horizontal_data = HorizontalBinaryStructure::format_input_data(&dataset);
+
+        let heuristics: Vec<Box<dyn Heuristic>> = vec![
+            Box::<GiniIndex>::default(),
+            Box::<InformationGain>::default(),
+            Box::<InformationGainRatio>::default(),
+        ];
+
+        for heuristic in heuristics.iter() {
+            let mut rsparse = RSparseBitsetStructure::new(&bitset_data);
+            let expected = sort_candidates(heuristic.as_ref(), &mut rsparse);
+
+            let mut bitset = BitsetStructure::new(&bitset_data);
+            assert_eq!(sort_candidates(heuristic.as_ref(), &mut bitset), expected);
+
+            let mut horizontal = HorizontalBinaryStructure::new(&horizontal_data);
+            assert_eq!(
+                sort_candidates(heuristic.as_ref(), &mut horizontal),
+                expected
+            );
+
+            let mut raw = RawBinaryStructure::new(&dataset);
+            assert_eq!(sort_candidates(heuristic.as_ref(), &mut raw), expected);
+        }
+    }
+}
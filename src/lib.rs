@@ -1,6 +1,10 @@
 // #![allow(unused)]
 #![warn(clippy::too_many_arguments)]
 use crate::algorithms::algorithm_trait::Algorithm;
+use crate::algorithms::dl85_utils::structs_enums::{
+    BranchingType, CacheInit, CacheType, DiscrepancyStrategy, LowerBoundHeuristic,
+    PythonFunctionData, SortHeuristic, Specialization,
+};
 
 use crate::dataset::data_trait::Dataset;
 
@@ -16,6 +20,7 @@ use pyo3::{pymodule, IntoPy, PyResult, Python};
 extern crate core;
 pub mod algorithms;
 pub mod dataset;
+pub mod errors;
 pub mod heuristics;
 mod post_process;
 mod pycore;
@@ -38,6 +43,14 @@ fn pytrees_internal(py: Python<'_>, _m: &PyModule) -> PyResult<()> {
 #[pymodule]
 fn optimal(_py: Python, module: &PyModule) -> PyResult<()> {
     module.add_class::<Dl85InternalClassifier>()?;
+    module.add_class::<Specialization>()?;
+    module.add_class::<LowerBoundHeuristic>()?;
+    module.add_class::<BranchingType>()?;
+    module.add_class::<SortHeuristic>()?;
+    module.add_class::<CacheInit>()?;
+    module.add_class::<CacheType>()?;
+    module.add_class::<DiscrepancyStrategy>()?;
+    module.add_class::<PythonFunctionData>()?;
     Ok(())
 }
 
@@ -3,6 +3,7 @@
 
 use crate::algorithms::algorithm_trait::Algorithm;
 use crate::algorithms::dl85::DL85;
+use crate::algorithms::dl85_utils::leaf_objective::Misclassification;
 use crate::algorithms::dl85_utils::structs_enums::{
     BranchingType, CacheInit, LowerBoundHeuristic, Specialization,
 };
@@ -26,6 +27,7 @@ use std::path::PathBuf;
 
 mod algorithms;
 mod dataset;
+mod errors;
 mod heuristics;
 mod post_process;
 mod structures;
@@ -80,8 +82,8 @@ struct Args {
     time_limit: usize,
 
     /// Max error
-    #[arg(long, default_value_t = <usize>::MAX)]
-    max_error: usize,
+    #[arg(long, default_value_t = <f64>::MAX)]
+    max_error: f64,
 }
 
 fn main() {
@@ -92,7 +94,7 @@ fn main() {
 
     let file = args.file.to_str().unwrap();
     let depth = args.depth;
-    let min_sup = args.support;
+    let min_sup = args.support as f64;
     let time_limit = args.time_limit;
     let max_error = args.max_error;
 
@@ -151,10 +153,12 @@ fn main() {
         0,
         false,
         heuristic.as_mut(),
-        None,
-        None,
+        Misclassification,
     );
-    algo.fit(&mut structure);
+    if let Err(error) = algo.fit(&mut structure) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
     println!("--------------- Search Tree ---------------");
     algo.tree.print();
     println!("--------------- Search Tree ---------------");
@@ -133,7 +133,7 @@ impl CostComplexityPruning {
         node_tree
     }
 
-    fn get_sub_trees_infos(trees: &[Tree<NodeData>]) -> Vec<(usize, usize)> {
+    fn get_sub_trees_infos(trees: &[Tree<NodeData>]) -> Vec<(f64, usize)> {
         trees
             .iter()
             .map(|tree| {
@@ -142,14 +142,14 @@ impl CostComplexityPruning {
                     Self::get_leaves_nodes_count(tree),
                 )
             })
-            .collect::<Vec<(usize, usize)>>()
+            .collect::<Vec<(f64, usize)>>()
     }
 
-    fn get_alphas(tree_error: usize, infos: &[(usize, usize)]) -> Vec<(usize, f64)> {
+    fn get_alphas(tree_error: f64, infos: &[(f64, usize)]) -> Vec<(usize, f64)> {
         infos
             .iter()
             .enumerate()
-            .map(|(i, (error, size))| (i, (tree_error - error) as f64 / (size - 1) as f64))
+            .map(|(i, (error, size))| (i, (tree_error - error) / (size - 1) as f64))
             .collect::<Vec<(usize, f64)>>()
     }
 
@@ -171,7 +171,7 @@ impl CostComplexityPruning {
     }
 
     fn clean_error(tree: &mut Tree<NodeData>) {
-        fn recursion(parent: Index, tree: &mut Tree<NodeData>) -> usize {
+        fn recursion(parent: Index, tree: &mut Tree<NodeData>) -> f64 {
             if let Some(node) = tree.get_node(parent) {
                 if CostComplexityPruning::is_leaf(node) {
                     return node.value.error;
@@ -180,7 +180,7 @@ impl CostComplexityPruning {
 
             let mut left_index = 0;
             let mut right_index = 0;
-            let mut parent_error = 0;
+            let mut parent_error = 0.;
 
             if let Some(node) = tree.get_node(parent) {
                 left_index = node.left;
@@ -263,7 +263,7 @@ mod cost_complexity_pruning {
         let bitset_data = BitsetStructure::format_input_data(&dataset);
         let mut structure = BitsetStructure::new(&bitset_data);
 
-        let mut tree = LGDT::fit(&mut structure, 1, 10, MurTree::fit);
+        let mut tree = LGDT::fit(&mut structure, 1., 10, MurTree::fit);
 
         structure.reset();
         let (diff_trees, subtrees) =
@@ -1,4 +1,6 @@
 use crate::algorithms::algorithm_trait::{Algorithm, Basic};
+use crate::algorithms::cancellation::CancellationToken;
+use crate::algorithms::dl85_utils::structs_enums::seconds;
 use crate::algorithms::idk::IDK;
 use crate::algorithms::info_gain::InfoGain;
 use crate::algorithms::lgdt::LGDT;
@@ -6,6 +8,8 @@ use crate::algorithms::murtree::MurTree;
 use crate::algorithms::parallel_lgdt::ParallelLGDT;
 use crate::dataset::binary_dataset::BinaryDataset;
 use crate::dataset::data_trait::Dataset;
+use crate::errors::{PyTreesError, PyTreesResult};
+use crate::pycore::{keyboard_interrupt_token, to_python};
 use crate::structures::binary_tree::{NodeData, Tree};
 use crate::structures::bitsets_structure::BitsetStructure;
 use crate::structures::horizontal_binary_structure::HorizontalBinaryStructure;
@@ -44,14 +48,15 @@ struct LGDTConstraints {
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 struct LGDTStatistics {
+    #[serde(with = "seconds")]
     duration: Duration,
-    error: usize,
+    error: f64,
     duration_milliseconds: u128,
+    cancelled: bool, // A Ctrl-C left the subtrees not grown yet as leaves
 }
 impl IntoPy<PyObject> for LGDTStatistics {
     fn into_py(self, py: Python<'_>) -> PyObject {
-        let json = serde_json::to_string_pretty(&self).unwrap();
-        json.into_py(py)
+        to_python(py, &self)
     }
 }
 
@@ -65,19 +70,24 @@ pub(crate) struct LGDTInternalClassifier {
 #[pymethods]
 impl LGDTInternalClassifier {
     #[new]
-    fn new(min_sup: Support, max_depth: Depth, data_structure: usize, fit_method: usize) -> Self {
+    fn new(
+        min_sup: Support,
+        max_depth: Depth,
+        data_structure: usize,
+        fit_method: usize,
+    ) -> PyTreesResult<Self> {
         let data_structure = match data_structure {
             0 => DataStructure::RawBinaryData,
             1 => DataStructure::HorizontalData,
             2 => DataStructure::RegularBitset,
             3 => DataStructure::ReversibleSparseBitset,
-            _ => panic!("Invalid data structure"),
+            code => return Err(invalid_code("data structure", code)),
         };
 
         let method = match fit_method {
             0 => FitMethod::Murtree,
             1 => FitMethod::InfoGain,
-            _ => panic!("Invalid fit method"),
+            code => return Err(invalid_code("fit method", code)),
         };
 
         let constraints = LGDTConstraints {
@@ -89,45 +99,69 @@ impl LGDTInternalClassifier {
             num_threads: 0,
         };
 
-        Self {
+        Ok(Self {
             tree: Tree::new(),
             constraints,
             statistics: LGDTStatistics {
                 duration: Default::default(),
-                error: 0,
+                error: 0.,
                 duration_milliseconds: 0,
+                cancelled: false,
             },
-        }
+        })
     }
 
-    fn train(&mut self, input: PyReadonlyArrayDyn<f64>, target: PyReadonlyArrayDyn<f64>) {
+    fn train(
+        &mut self,
+        py: Python<'_>,
+        input: PyReadonlyArrayDyn<f64>,
+        target: PyReadonlyArrayDyn<f64>,
+        sample_weight: Option<PyReadonlyArrayDyn<f64>>,
+    ) -> PyTreesResult<()> {
         let input = input.as_array().map(|a| *a as usize);
         let target = target.as_array().map(|a| *a as usize);
-        let dataset = BinaryDataset::load_from_numpy(&input, &target);
+        let mut dataset = BinaryDataset::load_from_numpy(&input, &target);
+        if let Some(weights) = sample_weight {
+            dataset.set_sample_weights(weights.as_array().iter().copied().collect())?;
+        }
+
+        if dataset.get_sample_weights().is_some()
+            && matches!(
+                self.constraints.data_structure,
+                DataStructure::HorizontalData | DataStructure::RawBinaryData
+            )
+        {
+            return Err(PyTreesError::Unsupported(
+                "Sample weights are only supported by the bitset data structures".to_string(),
+            ));
+        }
 
-        let output = match self.constraints.data_structure {
+        // Other Python threads run during the fit
+        let constraints = self.constraints;
+        let output = py.allow_threads(|| match constraints.data_structure {
             DataStructure::RegularBitset => {
                 let formatted_data = BitsetStructure::format_input_data(&dataset);
                 let mut structure = BitsetStructure::new(&formatted_data);
-                solve_instance(&mut structure, self.constraints.method, self.constraints)
+                solve_instance(&mut structure, constraints.method, constraints)
             }
             DataStructure::ReversibleSparseBitset => {
                 let formatted_data = RSparseBitsetStructure::format_input_data(&dataset);
                 let mut structure = RSparseBitsetStructure::new(&formatted_data);
-                solve_instance(&mut structure, self.constraints.method, self.constraints)
+                solve_instance(&mut structure, constraints.method, constraints)
             }
             DataStructure::HorizontalData => {
                 let formatted_data = HorizontalBinaryStructure::format_input_data(&dataset);
                 let mut structure = HorizontalBinaryStructure::new(&formatted_data);
-                solve_instance(&mut structure, self.constraints.method, self.constraints)
+                solve_instance(&mut structure, constraints.method, constraints)
             }
             DataStructure::RawBinaryData => {
                 let mut structure = RawBinaryStructure::new(&dataset);
-                solve_instance(&mut structure, self.constraints.method, self.constraints)
+                solve_instance(&mut structure, constraints.method, constraints)
             }
-        };
+        });
         self.tree = output.0;
         self.statistics = output.1;
+        Ok(())
     }
 
     #[getter]
@@ -157,17 +191,17 @@ impl ParallelLGDTInternalClassifier {
         data_structure: usize,
         fit_method: usize,
         num_threads: usize,
-    ) -> Self {
+    ) -> PyTreesResult<Self> {
         let data_structure = match data_structure {
             2 => DataStructure::RegularBitset,
             3 => DataStructure::ReversibleSparseBitset,
-            _ => panic!("Invalid data structure"),
+            code => return Err(invalid_code("data structure for parallel LGDT", code)),
         };
 
         let method = match fit_method {
             0 => FitMethod::Murtree,
             1 => FitMethod::InfoGain,
-            _ => panic!("Invalid fit method"),
+            code => return Err(invalid_code("fit method", code)),
         };
 
         let constraints = LGDTConstraints {
@@ -179,39 +213,59 @@ impl ParallelLGDTInternalClassifier {
             num_threads,
         };
 
-        Self {
+        Ok(Self {
             tree: Tree::new(),
             constraints,
             statistics: LGDTStatistics {
                 duration: Default::default(),
-                error: 0,
+                error: 0.,
                 duration_milliseconds: 0,
+                cancelled: false,
             },
-        }
+        })
     }
 
-    fn train(&mut self, input: PyReadonlyArrayDyn<f64>, target: PyReadonlyArrayDyn<f64>) {
+    fn train(
+        &mut self,
+        py: Python<'_>,
+        input: PyReadonlyArrayDyn<f64>,
+        target: PyReadonlyArrayDyn<f64>,
+        sample_weight: Option<PyReadonlyArrayDyn<f64>>,
+    ) -> PyTreesResult<()> {
         let input = input.as_array().map(|a| *a as usize);
         let target = target.as_array().map(|a| *a as usize);
-        let dataset = BinaryDataset::load_from_numpy(&input, &target);
+        let mut dataset = BinaryDataset::load_from_numpy(&input, &target);
+        if let Some(weights) = sample_weight {
+            dataset.set_sample_weights(weights.as_array().iter().copied().collect())?;
+        }
 
-        let output = match self.constraints.data_structure {
+        let constraints = self.constraints;
+        let output = py.allow_threads(|| match constraints.data_structure {
             DataStructure::RegularBitset => {
                 let formatted_data = BitsetStructure::format_input_data(&dataset);
                 let mut structure = BitsetStructure::new(&formatted_data);
-                solve_parallel_instance(&mut structure, self.constraints.method, self.constraints)
+                Ok(solve_parallel_instance(
+                    &mut structure,
+                    constraints.method,
+                    constraints,
+                ))
             }
             DataStructure::ReversibleSparseBitset => {
                 let formatted_data = RSparseBitsetStructure::format_input_data(&dataset);
                 let mut structure = RSparseBitsetStructure::new(&formatted_data);
-                solve_parallel_instance(&mut structure, self.constraints.method, self.constraints)
+                Ok(solve_parallel_instance(
+                    &mut structure,
+                    constraints.method,
+                    constraints,
+                ))
             }
-            _ => {
-                panic!("Invalid data structure for parallel LGDT");
-            }
-        };
+            _ => Err(PyTreesError::Unsupported(
+                "The parallel LGDT only supports the bitset data structures".to_string(),
+            )),
+        })?;
         self.tree = output.0;
         self.statistics = output.1;
+        Ok(())
     }
 
     #[getter]
@@ -225,21 +279,26 @@ impl ParallelLGDTInternalClassifier {
     }
 }
 
+fn invalid_code(name: &str, code: usize) -> PyTreesError {
+    PyTreesError::InvalidParameter(format!("Invalid {name}: {code}"))
+}
+
 fn solve_instance<S: Structure>(
     structure: &mut S,
     method: FitMethod,
     constraints: LGDTConstraints,
 ) -> (Tree<NodeData>, LGDTStatistics) {
     let time = Instant::now();
+    let token = keyboard_interrupt_token();
     let tree = match method {
         FitMethod::InfoGain => {
             let method = InfoGain::fit;
-            internal_solver(structure, method, &constraints)
+            internal_solver(structure, method, &constraints, &token)
         }
         FitMethod::Murtree => {
             let time = Instant::now();
             let method = MurTree::fit;
-            internal_solver(structure, method, &constraints)
+            internal_solver(structure, method, &constraints, &token)
         }
     };
     let duration = time.elapsed();
@@ -250,6 +309,7 @@ fn solve_instance<S: Structure>(
             duration,
             error,
             duration_milliseconds: duration.as_millis(),
+            cancelled: token.was_cancelled(),
         },
     )
 }
@@ -258,6 +318,7 @@ fn internal_solver<S, F>(
     structure: &mut S,
     fit_method: F,
     constraints: &LGDTConstraints,
+    token: &CancellationToken,
 ) -> Tree<NodeData>
 where
     S: Structure,
@@ -265,11 +326,12 @@ where
 {
     match constraints.max_depth == 0 {
         true => IDK::fit(structure, constraints.min_sup, fit_method),
-        false => LGDT::fit(
+        false => LGDT::fit_with_cancellation(
             structure,
             constraints.min_sup,
             constraints.max_depth,
             fit_method,
+            token,
         ),
     }
 }
@@ -280,15 +342,16 @@ fn solve_parallel_instance<S: Structure + BitsetTrait + Send + Clone>(
     constraints: LGDTConstraints,
 ) -> (Tree<NodeData>, LGDTStatistics) {
     let time = Instant::now();
+    let token = keyboard_interrupt_token();
     let tree = match method {
         FitMethod::InfoGain => {
             let method = InfoGain::fit;
-            internal_parallel_solver(structure, method, &constraints)
+            internal_parallel_solver(structure, method, &constraints, &token)
         }
         FitMethod::Murtree => {
             let time = Instant::now();
             let method = MurTree::fit;
-            internal_parallel_solver(structure, method, &constraints)
+            internal_parallel_solver(structure, method, &constraints, &token)
         }
     };
     let duration = time.elapsed();
@@ -299,14 +362,17 @@ fn solve_parallel_instance<S: Structure + BitsetTrait + Send + Clone>(
             duration,
             error,
             duration_milliseconds: duration.as_millis(),
+            cancelled: token.was_cancelled(),
         },
     )
 }
 
+// The training thread polls the signals and stops the workers of the parallel LGDT
 fn internal_parallel_solver<S, F>(
     structure: &mut S,
     fit_method: F,
     constraints: &LGDTConstraints,
+    token: &CancellationToken,
 ) -> Tree<NodeData>
 where
     S: Structure + BitsetTrait + Clone + Send,
@@ -315,18 +381,20 @@ where
     match constraints.max_depth == 0 {
         true => IDK::fit(structure, constraints.min_sup, fit_method),
         false => match constraints.parallel {
-            true => ParallelLGDT::fit(
+            true => ParallelLGDT::fit_with_cancellation(
                 structure,
                 constraints.min_sup,
                 constraints.max_depth,
                 constraints.num_threads,
                 fit_method,
+                token,
             ),
-            false => LGDT::fit(
+            false => LGDT::fit_with_cancellation(
                 structure,
                 constraints.min_sup,
                 constraints.max_depth,
                 fit_method,
+                token,
             ),
         },
     }
@@ -1,2 +1,64 @@
+use crate::algorithms::cancellation::CancellationToken;
+use crate::algorithms::dl85_utils::structs_enums::Statistics;
+use crate::structures::binary_tree::{NodeData, Tree};
+use pyo3::types::{PyDict, PyList};
+use pyo3::{IntoPy, PyObject, Python};
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+
 pub mod less_greedy;
 pub mod optimal;
+
+// How often a running fit looks for a Ctrl-C
+const SIGNALS_INTERVAL: Duration = Duration::from_millis(100);
+
+// Cancelled by a Ctrl-C, the fit then returns its best tree instead of raising KeyboardInterrupt
+pub(crate) fn keyboard_interrupt_token() -> CancellationToken {
+    CancellationToken::with_watcher(
+        || Python::with_gil(|py| py.check_signals().is_err()),
+        SIGNALS_INTERVAL,
+    )
+}
+
+// The dicts and lists of the JSON form of `value`, the durations in seconds
+pub(crate) fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyObject {
+    json_to_python(py, &serde_json::to_value(value).unwrap_or(Value::Null))
+}
+
+fn json_to_python(py: Python<'_>, value: &Value) -> PyObject {
+    match value {
+        Value::Null => py.None(),
+        Value::Bool(value) => value.into_py(py),
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(value), _) => value.into_py(py),
+            (None, Some(value)) => value.into_py(py),
+            (None, None) => number.as_f64().into_py(py),
+        },
+        Value::String(value) => value.into_py(py),
+        Value::Array(items) => {
+            PyList::new(py, items.iter().map(|item| json_to_python(py, item))).into_py(py)
+        }
+        Value::Object(entries) => {
+            let dict = PyDict::new(py);
+            for (key, item) in entries {
+                // Setting a str key does not raise
+                let _ = dict.set_item(key, json_to_python(py, item));
+            }
+            dict.into_py(py)
+        }
+    }
+}
+
+// {"tree": [...]} with a dict per node, the root first
+impl IntoPy<PyObject> for Tree<NodeData> {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        to_python(py, &self)
+    }
+}
+
+impl IntoPy<PyObject> for Statistics {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        to_python(py, &self)
+    }
+}
@@ -1,27 +1,119 @@
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::PyModule;
-use pyo3::{pyclass, pymethods, pymodule, IntoPy, PyObject, PyResult, Python};
+use pyo3::types::PyDict;
+use pyo3::{
+    pyclass, pymethods, FromPyObject, IntoPy, PyAny, PyClass, PyErr, PyObject, PyResult, Python,
+};
 
+use crate::algorithms::algorithm_trait::Algorithm;
+use crate::algorithms::cancellation::CancellationToken;
 use crate::algorithms::dl85::DL85;
+use crate::algorithms::dl85_utils::leaf_objective::{
+    LeafObjective, Misclassification, PythonObjective,
+};
 use crate::algorithms::dl85_utils::structs_enums::{
-    BranchingType, CacheInit, Constraints, DiscrepancyStrategy, LowerBoundHeuristic,
-    PythonFunctionData, SortHeuristic, Specialization, Statistics,
+    BranchingType, CacheInit, CacheType, Constraints, DiscrepancyStrategy, FromCode, Incumbent,
+    LowerBoundHeuristic, Progress, PythonFunctionData, SearchCounters, SearchStatus, SortHeuristic,
+    Specialization, Statistics,
 };
 use crate::algorithms::lds_dl85::LDSDL85;
+use crate::algorithms::lgdt::LGDT;
+use crate::algorithms::murtree::MurTree;
 use crate::dataset::binary_dataset::BinaryDataset;
 use crate::dataset::data_trait::Dataset;
+use crate::errors::{PyTreesError, PyTreesResult};
 use crate::heuristics::{GiniIndex, Heuristic, InformationGain, InformationGainRatio, NoHeuristic};
+use crate::pycore::keyboard_interrupt_token;
 use crate::structures::binary_tree::{NodeData, Tree};
 use crate::structures::caching::trie::Data;
 use crate::structures::reversible_sparse_bitsets_structure::RSparseBitsetStructure;
 use crate::structures::structures_types::{Depth, Support};
-use numpy::PyReadonlyArrayDyn;
+use ndarray::Axis;
+use numpy::{PyReadonlyArray2, PyReadonlyArrayDyn};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+// A search enum, or the integer code of one of its variants
+struct Coded<T>(T);
+
+impl<'source, T: PyClass + Clone + FromCode> FromPyObject<'source> for Coded<T> {
+    fn extract(object: &'source PyAny) -> PyResult<Self> {
+        if let Ok(value) = object.extract::<T>() {
+            return Ok(Coded(value));
+        }
+        let code = object.extract::<usize>()?;
+        match T::from_code(code) {
+            Some(value) => Ok(Coded(value)),
+            None => {
+                Err(PyTreesError::InvalidParameter(format!("Invalid {}: {code}", T::LABEL)).into())
+            }
+        }
+    }
+}
+
+// The parameters of the optimal classifiers, passed by keyword from Python
+#[derive(FromPyObject)]
+#[pyo3(from_item_all)]
+struct Dl85Options<'py> {
+    min_sup: Support,
+    max_depth: Depth,
+    discrepancy_budget: isize,
+    discrepancy_strategy: Coded<DiscrepancyStrategy>,
+    max_error: f64,
+    max_time: isize,
+    specialization: Coded<Specialization>,
+    lower_bound: Coded<LowerBoundHeuristic>,
+    branching: Coded<BranchingType>,
+    one_time_sort: bool,
+    heuristic: Coded<SortHeuristic>,
+    cache_init: Coded<CacheInit>,
+    cache_init_size: usize,
+    leaf_penalty: f64,
+    all_depths: bool,
+    anytime: bool,
+    cache_type: Coded<CacheType>,
+    cover_cache: bool,
+    custom_function: Option<PyObject>,
+    custom_function_type: Option<Coded<PythonFunctionData>>,
+    cost_matrix: Option<PyReadonlyArray2<'py, f64>>,
+    max_leaves: Option<usize>,
+    memory_limit: Option<usize>,
+    incumbent_callback: Option<PyObject>,
+    warm_start_file: Option<String>,
+    cache_file: Option<String>,
+    num_threads: Option<usize>,
+    initial_tree: Option<String>,
+    top_k: Option<usize>,
+    rashomon_epsilon: Option<f64>,
+    progress_callback: Option<PyObject>,
+    progress_interval: Option<f64>,
+}
+
+// -1 lifts the limit, the other negative values are mistakes
+fn limit_option(value: isize, name: &str) -> PyTreesResult<usize> {
+    match value {
+        -1 => Ok(<usize>::MAX),
+        value if value < -1 => Err(PyTreesError::InvalidParameter(format!(
+            "{} must be -1 or non-negative",
+            name
+        ))),
+        value => Ok(value as usize),
+    }
+}
+
 #[pyclass]
 pub(crate) struct Dl85InternalClassifier {
     heuristic: SortHeuristic,
     custom_function: Option<PyObject>,
+    incumbent_callback: Option<PyObject>,
+    progress_callback: Option<PyObject>,
+    progress_interval: Duration,
+    warm_start_file: Option<String>,
+    cache_file: Option<String>,
+    initial_tree: Option<String>, // "lgdt" or a tree in JSON
     tree: Tree<NodeData>,
+    depth_trees: Vec<Tree<NodeData>>,
+    top_trees: Vec<Tree<NodeData>>,
     constraints: Constraints,
     statistics: Statistics,
 }
@@ -29,122 +121,109 @@ pub(crate) struct Dl85InternalClassifier {
 #[pymethods]
 impl Dl85InternalClassifier {
     #[new]
-    fn new(
-        min_sup: Support,
-        max_depth: Depth,
-        discrepancy_budget: isize,
-        discrepancy_strategy: usize,
-        error: isize,
-        time: isize,
-        specialization: usize,
-        lower_bound: usize,
-        branching_type: usize,
-        one_time_sort: bool,
-        heuristic: usize,
-        cache_init: usize,
-        cache_init_size: usize,
-        custom_function: Option<PyObject>,
-        function_type: Option<usize>,
-    ) -> Self {
-        let max_error = match error == -1 {
-            true => <usize>::MAX,
-            false => error as usize,
-        };
-
-        let max_time = match time == -1 {
-            true => <usize>::MAX,
-            false => time as usize,
-        };
-
-        let discrepancy_budget = match discrepancy_budget == -1 {
-            true => <usize>::MAX,
-            false => discrepancy_budget as usize,
-        };
-
-        let specialization = match specialization {
-            0 => Specialization::None,
-            1 => Specialization::Murtree,
-            _ => panic!("Invalid specialization"),
-        };
-
-        let lower_bound = match lower_bound {
-            0 => LowerBoundHeuristic::None,
-            1 => LowerBoundHeuristic::Similarity,
-            _ => panic!("Invalid lower bound"),
+    #[pyo3(signature = (**options))]
+    fn new(options: Option<&PyDict>) -> PyResult<Self> {
+        let options: Dl85Options = match options {
+            Some(options) => options.extract()?,
+            None => {
+                return Err(PyValueError::new_err(
+                    "The options of the search are required",
+                ))
+            }
         };
-
-        let heuristic = match heuristic {
-            0 => SortHeuristic::None,
-            1 => SortHeuristic::InformationGain,
-            2 => SortHeuristic::InformationGainRatio,
-            3 => SortHeuristic::GiniIndex,
-            _ => panic!("Invalid heuristic"),
-        };
-
-        let branching = match branching_type {
-            0 => BranchingType::None,
-            1 => BranchingType::Dynamic,
-            _ => panic!("Invalid branching type"),
+        let max_error = match options.max_error < 0. {
+            true => <f64>::MAX,
+            false => options.max_error,
         };
 
-        let cache_init = match cache_init {
-            0 => CacheInit::Normal,
-            1 => CacheInit::WithMemoryDynamic,
-            2 => CacheInit::WithMemoryFromUser,
-            _ => panic!("Invalid cache init type"),
-        };
-
-        let discrepancy_strategy = match discrepancy_strategy {
-            0 => DiscrepancyStrategy::None,
-            1 => DiscrepancyStrategy::Incremental,
-            2 => DiscrepancyStrategy::Double,
-            _ => panic!("Invalid discrepancy strategy"),
-        };
+        let max_time = limit_option(options.max_time, "max_time")?;
+        let discrepancy_budget = limit_option(options.discrepancy_budget, "discrepancy_budget")?;
 
-        let custom_function_type = match function_type {
-            Some(0) => Some(PythonFunctionData::ClassSupports),
-            Some(1) => Some(PythonFunctionData::Tids),
-            _ => None,
-        };
+        if options.top_k == Some(0) {
+            return Err(PyTreesError::InvalidParameter(
+                "At least one tree must be enumerated".to_string(),
+            )
+            .into());
+        }
 
         let constraints = Constraints {
-            max_depth,
-            min_sup,
+            max_depth: options.max_depth,
+            min_sup: options.min_sup,
             max_error,
             max_time,
-            one_time_sort,
-            specialization,
-            lower_bound,
-            branching,
-            cache_init,
-            cache_init_size,
+            one_time_sort: options.one_time_sort,
+            specialization: options.specialization.0,
+            lower_bound: options.lower_bound.0,
+            branching: options.branching.0,
+            cache_init: options.cache_init.0,
+            cache_init_size: options.cache_init_size,
+            cache_type: options.cache_type.0,
             discrepancy_budget,
-            discrepancy_strategy,
-            python_function_data: custom_function_type,
+            discrepancy_strategy: options.discrepancy_strategy.0,
+            python_function_data: options.custom_function_type.map(|data| data.0),
+            cost_matrix: options.cost_matrix.map(|matrix| {
+                matrix
+                    .as_array()
+                    .rows()
+                    .into_iter()
+                    .map(|row| row.to_vec())
+                    .collect()
+            }),
+            leaf_penalty: options.leaf_penalty,
+            max_leaves: options.max_leaves.unwrap_or(<usize>::MAX),
+            all_depths: options.all_depths,
+            anytime: options.anytime,
+            memory_limit: options.memory_limit.unwrap_or(<usize>::MAX),
+            cover_cache: options.cover_cache,
+            num_threads: options.num_threads.unwrap_or(1).max(1),
+            top_trees: match options.rashomon_epsilon {
+                Some(_) => options.top_k.unwrap_or(<usize>::MAX),
+                None => options.top_k.unwrap_or(0),
+            },
+            rashomon_bound: options.rashomon_epsilon.unwrap_or(<f64>::MAX),
         };
 
         let statistics = Statistics {
             num_attributes: 0,
-            num_samples: 0,
-            train_distribution: [0, 0],
-            constraints,
+            num_samples: 0.,
+            train_distribution: vec![],
+            constraints: constraints.clone(),
             cache_size: 0,
-            tree_error: 0,
+            tree_error: 0.,
+            depth_errors: vec![],
+            top_errors: vec![],
+            incumbents: vec![],
+            status: SearchStatus::Optimal,
+            lower_bound: 0.,
+            gap: 0.,
+            counters: SearchCounters::default(),
             duration: Duration::default(),
         };
 
-        Self {
-            heuristic,
-            custom_function,
+        let classifier = Self {
+            heuristic: options.heuristic.0,
+            custom_function: options.custom_function,
+            incumbent_callback: options.incumbent_callback,
+            progress_callback: options.progress_callback,
+            progress_interval: Duration::from_secs_f64(
+                options.progress_interval.unwrap_or(1.).max(0.),
+            ),
+            warm_start_file: options.warm_start_file,
+            cache_file: options.cache_file,
+            initial_tree: options.initial_tree,
             tree: Tree::new(),
+            depth_trees: vec![],
+            top_trees: vec![],
             constraints,
             statistics,
-        }
+        };
+        classifier.check_options()?;
+        Ok(classifier)
     }
 
     #[getter]
     fn statistics(&self, py: Python) -> PyResult<PyObject> {
-        Ok(self.statistics.into_py(py))
+        Ok(self.statistics.clone().into_py(py))
     }
 
     #[getter]
@@ -152,14 +231,229 @@ impl Dl85InternalClassifier {
         Ok(self.tree.clone().into_py(py))
     }
 
-    fn train(&mut self, input: PyReadonlyArrayDyn<f64>, target: PyReadonlyArrayDyn<f64>) {
+    #[getter]
+    fn depth_trees(&self, py: Python) -> PyResult<PyObject> {
+        let trees = self
+            .depth_trees
+            .iter()
+            .map(|tree| tree.clone().into_py(py))
+            .collect::<Vec<PyObject>>();
+        Ok(trees.into_py(py))
+    }
+
+    #[getter]
+    fn top_trees(&self, py: Python) -> PyResult<PyObject> {
+        let trees = self
+            .top_trees
+            .iter()
+            .map(|tree| tree.clone().into_py(py))
+            .collect::<Vec<PyObject>>();
+        Ok(trees.into_py(py))
+    }
+
+    fn train(
+        &mut self,
+        py: Python<'_>,
+        input: PyReadonlyArrayDyn<f64>,
+        target: PyReadonlyArrayDyn<f64>,
+        sample_weight: Option<PyReadonlyArrayDyn<f64>>,
+    ) -> PyResult<()> {
         let input = input.as_array().map(|a| *a as usize);
         let target = target.as_array().map(|a| *a as usize);
-        let dataset = BinaryDataset::load_from_numpy(&input, &target);
-        let formatted_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut dataset = BinaryDataset::load_from_numpy(&input, &target);
+        if let Some(weights) = sample_weight {
+            dataset.set_sample_weights(weights.as_array().iter().copied().collect())?;
+        }
+        self.fit(py, &dataset)
+    }
+
+    fn train_regression(
+        &mut self,
+        py: Python<'_>,
+        input: PyReadonlyArrayDyn<f64>,
+        target: PyReadonlyArrayDyn<f64>,
+        sample_weight: Option<PyReadonlyArrayDyn<f64>>,
+    ) -> PyResult<()> {
+        if !matches!(
+            self.constraints.discrepancy_strategy,
+            DiscrepancyStrategy::None
+        ) {
+            return Err(PyTreesError::Unsupported(
+                "Regression trees are only supported by DL85".to_string(),
+            )
+            .into());
+        }
+        let input = input.as_array().map(|a| *a as usize);
+        let labels = input.index_axis(Axis(1), 0).map(|_| 0usize).into_dyn();
+        let mut dataset = BinaryDataset::load_from_numpy(&input, &labels);
+        dataset.set_regression_targets(target.as_array().iter().copied().collect());
+        if let Some(weights) = sample_weight {
+            dataset.set_sample_weights(weights.as_array().iter().copied().collect())?;
+        }
+        self.fit(py, &dataset)
+    }
+}
+
+impl Dl85InternalClassifier {
+    // The options the searches would reject, checked before any training
+    fn check_options(&self) -> PyTreesResult<()> {
+        let constraints = &self.constraints;
+        let unsupported = |message: &str| Err(PyTreesError::Unsupported(message.to_string()));
+
+        // BEGIN STEP: Check the options of DL85
+        // Saving the cache would only fail once the search is over
+        if self.cache_file.is_some() {
+            if constraints.cover_cache {
+                return unsupported("The cover cache does not support warm starts");
+            }
+            if constraints.num_threads > 1 {
+                return unsupported("The parallel search does not support warm starts");
+            }
+        }
+        if constraints.num_threads > 1 && self.custom_function.is_some() {
+            // The workers would wait for the GIL the training thread holds
+            return unsupported("Custom functions are not supported by the parallel search");
+        }
+        if constraints.num_threads > 1 && (constraints.anytime || self.incumbent_callback.is_some())
+        {
+            return unsupported("The parallel search does not support the anytime mode");
+        }
+        if constraints.num_threads > 1 && self.progress_callback.is_some() {
+            return unsupported("The parallel search does not support progress callbacks");
+        }
+        if constraints.num_threads > 1 && constraints.memory_limit < <usize>::MAX {
+            // Each worker keeps its own cache besides the shared one
+            return unsupported("The parallel search does not support the memory limit");
+        }
+        if self.initial_tree.as_deref() == Some("lgdt") && constraints.max_leaves < <usize>::MAX {
+            return unsupported(
+                "The LGDT initial tree does not support the maximum number of leaves",
+            );
+        }
+        // END STEP: Check the options of DL85
+
+        // BEGIN STEP: Check the options of LDS-DL85
+        if matches!(constraints.discrepancy_strategy, DiscrepancyStrategy::None) {
+            return Ok(());
+        }
+        let cache_files = self.warm_start_file.is_some() || self.cache_file.is_some();
+        if constraints.cost_matrix.is_some() {
+            return unsupported("Cost matrices are only supported by DL85");
+        }
+        if constraints.leaf_penalty > 0. {
+            return unsupported("The leaf penalty is only supported by DL85");
+        }
+        if constraints.max_leaves < <usize>::MAX {
+            return unsupported("The maximum number of leaves is only supported by DL85");
+        }
+        if constraints.all_depths {
+            return unsupported("Searching all depths is only supported by DL85");
+        }
+        if constraints.anytime || self.incumbent_callback.is_some() {
+            return unsupported("The anytime mode is only supported by DL85");
+        }
+        if self.progress_callback.is_some() {
+            return unsupported("The progress callback is only supported by DL85");
+        }
+        if constraints.memory_limit < <usize>::MAX {
+            return unsupported("The memory limit is only supported by DL85");
+        }
+        if constraints.cover_cache {
+            return unsupported("The cover cache is only supported by DL85");
+        }
+        if cache_files {
+            return unsupported("Saving and loading the cache is only supported by DL85");
+        }
+        if constraints.num_threads > 1 {
+            return unsupported("The parallel search is only supported by DL85");
+        }
+        if self.initial_tree.is_some() {
+            return unsupported("The initial tree is only supported by DL85");
+        }
+        if constraints.top_trees > 0 {
+            return unsupported("The top trees are only supported by DL85");
+        }
+        // END STEP: Check the options of LDS-DL85
+        Ok(())
+    }
+
+    // Other Python threads run during the search, unless a custom function needs the GIL for
+    // each leaf
+    fn fit(&mut self, py: Python<'_>, dataset: &BinaryDataset) -> PyResult<()> {
+        match self.custom_function.is_some() {
+            true => self.solve(dataset),
+            false => py.allow_threads(|| self.solve(dataset)),
+        }
+    }
+
+    fn solve(&mut self, dataset: &BinaryDataset) -> PyResult<()> {
+        let formatted_data = RSparseBitsetStructure::format_input_data(dataset);
         let mut structure = RSparseBitsetStructure::new(&formatted_data);
+        let token = keyboard_interrupt_token();
+
+        let callback_error = match self.custom_function.clone() {
+            None => self.search(&mut structure, Misclassification, &token).1,
+            Some(function) => {
+                // The class supports are given to the function when no data type is set
+                let data = self
+                    .constraints
+                    .python_function_data
+                    .unwrap_or(PythonFunctionData::ClassSupports);
+                let objective = PythonObjective::new(function, data, token.clone());
+                let (objective, callback_error) = self.search(&mut structure, objective, &token);
+                if let Some(error) = objective.take_error() {
+                    return Err(error);
+                }
+                callback_error
+            }
+        };
+        if let Some(error) = callback_error {
+            return Err(error);
+        }
+        self.statistics.constraints.python_function_data = self.constraints.python_function_data;
+        Ok(())
+    }
 
-        let mut heuristic: Box<dyn Heuristic> = match self.heuristic {
+    // The options out of the constructor of DL85, their setters check the values
+    fn set_dl85_options<H, O>(&self, algorithm: &mut DL85<'_, H, Data, O>) -> PyTreesResult<()>
+    where
+        H: Heuristic + ?Sized,
+        O: LeafObjective,
+    {
+        let constraints = &self.constraints;
+        if let Some(costs) = &constraints.cost_matrix {
+            algorithm.set_cost_matrix(costs.clone())?;
+        }
+        algorithm.set_leaf_penalty(constraints.leaf_penalty)?;
+        if constraints.max_leaves < <usize>::MAX {
+            algorithm.set_max_leaves(constraints.max_leaves)?;
+        }
+        algorithm.set_all_depths(constraints.all_depths);
+        algorithm.set_anytime(constraints.anytime);
+        algorithm.set_cache_type(constraints.cache_type);
+        algorithm.set_cover_cache(constraints.cover_cache);
+        algorithm.set_num_threads(constraints.num_threads);
+        if constraints.top_trees > 0 {
+            algorithm.set_top_trees(constraints.top_trees)?;
+        }
+        if constraints.rashomon_bound < <f64>::MAX {
+            algorithm.set_rashomon_bound(constraints.rashomon_bound)?;
+        }
+        if constraints.memory_limit < <usize>::MAX {
+            algorithm.set_memory_limit(constraints.memory_limit)?;
+        }
+        Ok(())
+    }
+
+    // Returns the objective and the exception raised by the incumbent or progress callback, if any
+    fn search<O: LeafObjective + Sync>(
+        &mut self,
+        structure: &mut RSparseBitsetStructure,
+        objective: O,
+        token: &CancellationToken,
+    ) -> (O, Option<PyErr>) {
+        let callback_error = Arc::new(Mutex::new(None));
+        let mut heuristic: Box<dyn Heuristic + Sync> = match self.heuristic {
             SortHeuristic::InformationGain => Box::<InformationGain>::default(),
             SortHeuristic::InformationGainRatio => Box::<InformationGainRatio>::default(),
             SortHeuristic::GiniIndex => Box::<GiniIndex>::default(),
@@ -167,7 +461,7 @@ impl Dl85InternalClassifier {
         };
 
         if let DiscrepancyStrategy::None = self.constraints.discrepancy_strategy {
-            let mut algorithm: DL85<'_, _, Data> = DL85::new(
+            let mut algorithm: DL85<'_, _, Data, O> = DL85::new(
                 self.constraints.min_sup,
                 self.constraints.max_depth,
                 self.constraints.max_error,
@@ -179,15 +473,106 @@ impl Dl85InternalClassifier {
                 self.constraints.cache_init_size,
                 self.constraints.one_time_sort,
                 heuristic.as_mut(),
-                self.custom_function.clone(),
-                self.constraints.python_function_data,
+                objective,
             );
+            if let Err(error) = self.set_dl85_options(&mut algorithm) {
+                return (algorithm.objective, Some(error.into()));
+            }
+            // The parallel search polls the signals from the training thread and stops its workers
+            algorithm.set_cancellation_token(token.clone());
+            if let Some(callback) = self.incumbent_callback.clone() {
+                let callback_error = callback_error.clone();
+                // Called with the time in seconds, the error and the tree, returning False stops
+                algorithm.set_incumbent_callback(move |incumbent: &Incumbent| {
+                    Python::with_gil(|py| {
+                        let tree = incumbent.tree.clone().into_py(py);
+                        match callback
+                            .call1(py, (incumbent.time.as_secs_f64(), incumbent.error, tree))
+                            .and_then(|result| result.extract::<Option<bool>>(py))
+                        {
+                            Ok(keep_going) => keep_going.unwrap_or(true),
+                            Err(err) => {
+                                if let Ok(mut error) = callback_error.lock() {
+                                    *error = Some(err);
+                                }
+                                false
+                            }
+                        }
+                    })
+                });
+            }
+            if let Some(callback) = self.progress_callback.clone() {
+                let (callback_error, token) = (callback_error.clone(), token.clone());
+                // Called with the time in seconds, the root upper bound or None and the cache
+                // size, an exception cancels the search
+                algorithm.set_progress_callback(
+                    self.progress_interval,
+                    move |progress: &Progress| {
+                        Python::with_gil(|py| {
+                            let upper_bound =
+                                (progress.upper_bound < <f64>::MAX).then_some(progress.upper_bound);
+                            let arguments = (
+                                progress.time.as_secs_f64(),
+                                upper_bound,
+                                progress.cache_size,
+                            );
+                            if let Err(err) = callback.call1(py, arguments) {
+                                if let Ok(mut error) = callback_error.lock() {
+                                    error.get_or_insert(err);
+                                }
+                                token.cancel();
+                            }
+                        })
+                    },
+                );
+            }
+
+            if let Some(initial_tree) = &self.initial_tree {
+                let tree = match initial_tree.as_str() {
+                    "lgdt" => {
+                        let (min_sup, max_depth) =
+                            (self.constraints.min_sup, self.constraints.max_depth);
+                        LGDT::fit(structure, min_sup, max_depth, MurTree::fit)
+                    }
+                    json => match serde_json::from_str::<Tree<NodeData>>(json) {
+                        Ok(tree) => tree,
+                        Err(error) => {
+                            let message = format!("Invalid initial tree: {error}");
+                            return (algorithm.objective, Some(PyValueError::new_err(message)));
+                        }
+                    },
+                };
+                algorithm.set_initial_tree(tree);
+            }
+            if let Some(filename) = &self.warm_start_file {
+                if let Err(error) = algorithm.load_cache(filename) {
+                    return (algorithm.objective, Some(error.into()));
+                }
+            }
 
-            algorithm.fit(&mut structure);
-            self.tree = algorithm.tree.clone();
+            let result = match self.constraints.num_threads > 1 {
+                true => algorithm.fit_parallel(structure),
+                false => algorithm.fit(structure),
+            };
+            if let Err(error) = result {
+                return (algorithm.objective, Some(error.into()));
+            }
+            let mut error = callback_error
+                .lock()
+                .ok()
+                .and_then(|mut error| error.take());
+            if let Some(filename) = &self.cache_file {
+                if let Err(save_error) = algorithm.save_cache(structure, filename) {
+                    error.get_or_insert(save_error.into());
+                }
+            }
+            self.tree = algorithm.tree;
+            self.depth_trees = algorithm.depth_trees;
+            self.top_trees = algorithm.top_trees;
             self.statistics = algorithm.statistics;
+            (algorithm.objective, error)
         } else {
-            let mut algorithm: LDSDL85<'_, _, Data> = LDSDL85::new(
+            let mut algorithm: LDSDL85<'_, _, Data, O> = LDSDL85::new(
                 self.constraints.min_sup,
                 self.constraints.max_depth,
                 self.constraints.discrepancy_budget,
@@ -201,11 +586,15 @@ impl Dl85InternalClassifier {
                 self.constraints.cache_init_size,
                 self.constraints.one_time_sort,
                 heuristic.as_mut(),
+                objective,
             );
+            algorithm.set_cache_type(self.constraints.cache_type);
+            algorithm.set_cancellation_token(token.clone());
 
-            algorithm.fit(&mut structure);
-            self.tree = algorithm.tree.clone();
+            let error = algorithm.fit(structure).err().map(PyErr::from);
+            self.tree = algorithm.tree;
             self.statistics = algorithm.statistics;
+            (algorithm.objective, error)
         }
     }
 }
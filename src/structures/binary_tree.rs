@@ -1,14 +1,14 @@
 use crate::structures::structures_types::{Attribute, Index};
-use pyo3::{IntoPy, PyObject, Python};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct NodeData {
     // Specific data for decision trees
     pub(crate) test: Option<Attribute>,
-    pub(crate) error: usize,
+    pub(crate) error: f64,
     pub(crate) metric: Option<f64>,
     pub(crate) out: Option<usize>,
+    pub(crate) prediction: Option<f64>, // Mean target of the leaf for regression trees
 }
 
 impl Default for NodeData {
@@ -21,9 +21,10 @@ impl NodeData {
     pub fn new() -> NodeData {
         NodeData {
             test: None,
-            error: <usize>::MAX,
+            error: <f64>::MAX,
             metric: None,
             out: None,
+            prediction: None,
         }
     }
 }
@@ -47,7 +48,7 @@ impl<T> TreeNode<T> {
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tree<T> {
     tree: Vec<TreeNode<T>>,
 }
@@ -58,13 +59,6 @@ impl<T> Default for Tree<T> {
     }
 }
 
-impl IntoPy<PyObject> for Tree<NodeData> {
-    fn into_py(self, py: Python<'_>) -> PyObject {
-        let json = serde_json::to_string_pretty(&self).unwrap();
-        json.into_py(py)
-    }
-}
-
 impl<T> Tree<T> {
     pub fn new() -> Self {
         Tree { tree: Vec::new() }
@@ -218,7 +212,7 @@ mod binary_tree_test {
     #[test]
     fn create_node_data() {
         let data = NodeData::new();
-        assert_eq!(data.error, <usize>::MAX);
+        assert_eq!(data.error, <f64>::MAX);
         assert_eq!(data.test.is_none(), true);
         assert_eq!(data.out, None);
     }
@@ -3,8 +3,8 @@ use crate::dataset::data_trait::Dataset;
 use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
 use crate::structures::structure_trait::{BitsetTrait, Structure};
 use crate::structures::structures_types::{
-    Bitset, BitsetStackState, BitsetStructData, Index, Item, LeafInfo, Position, StateCollection,
-    Support,
+    Bitset, BitsetStackState, BitsetStructData, Index, Item, LeafInfo, Position,
+    RegressionStatistics, StateCollection, Support,
 };
 use pyo3::ffi::lenfunc;
 
@@ -13,6 +13,7 @@ pub struct BitsetStructure<'data> {
     inputs: &'data BitsetStructData,
     support: Support,
     labels_support: Vec<Support>,
+    regression: Option<RegressionStatistics>,
     num_attributes: usize,
     num_labels: usize,
     position: Position,
@@ -32,12 +33,12 @@ impl<'data> Structure for BitsetStructure<'data> {
         let support = Support::MAX;
         if label < self.num_labels {
             if let Some(state) = self.get_last_state() {
-                let mut count = 0;
+                let mut count = 0.;
                 let label_bitset = &self.inputs.targets[label];
                 for (i, label_chunk) in label_bitset.iter().enumerate() {
-                    count += (*label_chunk & state[i]).count_ones();
+                    count += self.inputs.count(*label_chunk & state[i], i);
                 }
-                return count as Support;
+                return count;
             }
         }
         support
@@ -51,26 +52,26 @@ impl<'data> Structure for BitsetStructure<'data> {
 
         if self.num_labels == 2 {
             if let Some(state) = self.get_last_state() {
-                let mut count = 0;
+                let mut count = 0.;
                 let label_bitset = &self.inputs.targets[0];
                 for (i, label_chunk) in label_bitset.iter().enumerate() {
-                    count += (*label_chunk & state[i]).count_ones();
+                    count += self.inputs.count(*label_chunk & state[i], i);
                 }
-                self.labels_support.push(count as Support);
+                self.labels_support.push(count);
                 let support = self.support();
-                self.labels_support.push(support - count as Support);
+                self.labels_support.push(support - count);
             }
             return &self.labels_support;
         }
 
         if let Some(state) = self.state.last() {
             for label in 0..self.num_labels {
-                let mut count = 0;
+                let mut count = 0.;
                 let label_bitset = &self.inputs.targets[label];
                 for (i, label_chunk) in label_bitset.iter().enumerate() {
-                    count += (*label_chunk & state[i]).count_ones();
+                    count += self.inputs.count(*label_chunk & state[i], i);
                 }
-                self.labels_support.push(count as Support);
+                self.labels_support.push(count);
             }
             return &self.labels_support;
         }
@@ -81,12 +82,13 @@ impl<'data> Structure for BitsetStructure<'data> {
         if self.support < Support::MAX {
             return self.support;
         }
-        self.support = 0;
+        self.support = 0.;
         if let Some(current_state) = self.get_last_state() {
             self.support = current_state
                 .iter()
-                .map(|long| long.count_ones())
-                .sum::<u32>() as Support;
+                .enumerate()
+                .map(|(i, long)| self.inputs.count(*long, i))
+                .sum::<Support>();
         }
 
         self.support
@@ -108,6 +110,7 @@ impl<'data> Structure for BitsetStructure<'data> {
             self.state.pop();
             self.support = Support::MAX;
             self.labels_support.clear();
+            self.regression = None;
         }
     }
 
@@ -122,13 +125,28 @@ impl<'data> Structure for BitsetStructure<'data> {
         let mut state = BitsetStackState::with_capacity(self.num_attributes);
         state.push(self.state[0].clone());
         self.state = state;
-        self.support = self.inputs.size as Support;
+        self.support = self.inputs.total_support();
         self.labels_support.clear();
+        self.regression = None;
     }
     fn get_position(&self) -> &Position {
         &self.position
     }
 
+    fn regression_statistics(&mut self) -> Option<RegressionStatistics> {
+        self.inputs.values.as_ref()?;
+        if self.regression.is_none() {
+            let mut stats = (0., 0., 0.);
+            if let Some(state) = self.get_last_state() {
+                for (i, word) in state.iter().enumerate() {
+                    self.inputs.accumulate_values(*word, i, &mut stats);
+                }
+            }
+            self.regression = Some(stats);
+        }
+        self.regression
+    }
+
     fn get_tids(&self) -> Vec<usize> {
         if self.position.is_empty() {
             return (0..self.inputs.size).collect();
@@ -151,6 +169,24 @@ impl<'data> Structure for BitsetStructure<'data> {
         }
         tids
     }
+
+    fn get_cover(&self) -> Bitset {
+        self.get_last_state_bitset()
+    }
+
+    fn cover_difference(&self, cover: &Bitset, data_in: bool) -> Support {
+        let mut count = 0.;
+        if let Some(state) = self.get_last_state() {
+            for (i, (val, cover_word)) in state.iter().zip(cover.iter()).enumerate() {
+                let diff = match data_in {
+                    true => val & !cover_word,
+                    false => cover_word & !val,
+                };
+                count += self.inputs.count(diff, i);
+            }
+        }
+        count
+    }
 }
 
 impl<'data> BitsetTrait for BitsetStructure<'data> {
@@ -171,7 +207,7 @@ impl<'data> BitsetTrait for BitsetStructure<'data> {
         }
 
         if left_index == right_index {
-            let mut error = <usize>::MAX;
+            let mut error = <f64>::MAX;
             if let Some(node) = tree.get_node(index) {
                 error = node.value.error;
             }
@@ -215,6 +251,7 @@ impl<'data> BitsetTrait for BitsetStructure<'data> {
         self.state.push(state.clone());
         self.support = Support::MAX;
         self.labels_support.clear();
+        self.regression = None;
     }
 }
 
@@ -250,11 +287,29 @@ impl<'data> BitsetStructure<'data> {
             targets[class][row_chunk] |= 1u64 << (tid % 64);
         }
 
+        let weights = data.get_sample_weights().map(|sample_weights| {
+            let mut weights = vec![0.; chunks * 64];
+            for (tid, weight) in sample_weights.iter().rev().enumerate() {
+                weights[(chunks - 1 - tid / 64) * 64 + tid % 64] = *weight;
+            }
+            weights
+        });
+
+        let values = data.get_regression_targets().map(|regression_targets| {
+            let mut values = vec![0.; chunks * 64];
+            for (tid, value) in regression_targets.iter().rev().enumerate() {
+                values[(chunks - 1 - tid / 64) * 64 + tid % 64] = *value;
+            }
+            values
+        });
+
         BitsetStructData {
             inputs,
             targets,
             chunks,
             size,
+            weights,
+            values,
         }
     }
 
@@ -277,8 +332,9 @@ impl<'data> BitsetStructure<'data> {
 
         BitsetStructure {
             inputs,
-            support: inputs.size as Support,
+            support: inputs.total_support(),
             labels_support: Vec::with_capacity(inputs.targets.len()),
+            regression: None,
             num_attributes: inputs.inputs.len(),
             num_labels: inputs.targets.len(),
             position: Vec::with_capacity(num_attributes),
@@ -300,10 +356,11 @@ impl<'data> BitsetStructure<'data> {
 
     fn pushing(&mut self, item: Item) {
         let mut new_state = Bitset::new();
-        self.support = 0;
+        self.support = 0.;
         self.labels_support.clear();
+        self.regression = None;
         for _ in 0..self.num_labels {
-            self.labels_support.push(0);
+            self.labels_support.push(0.);
         }
 
         if let Some(last_state) = self.state.last() {
@@ -320,19 +377,18 @@ impl<'data> BitsetStructure<'data> {
                     _ => last_state[i] & *long,
                 };
 
-                let word_count = word.count_ones() as Support;
+                let word_count = self.inputs.count(word, i);
                 self.support += word_count;
 
                 if self.num_labels == 2 {
                     let label_chunk = &self.inputs.targets[0][i];
-                    let zero_count = (word & label_chunk).count_ones() as Support;
+                    let zero_count = self.inputs.count(word & label_chunk, i);
                     self.labels_support[0] += zero_count;
                     self.labels_support[1] += word_count - zero_count;
                 } else {
                     for n in 0..self.num_labels {
                         let label_chunk = &self.inputs.targets[n][i];
-                        let label_count = (word & label_chunk).count_ones() as Support;
-                        self.labels_support[i] += label_count;
+                        self.labels_support[n] += self.inputs.count(word & label_chunk, i);
                     }
                 }
 
@@ -391,7 +447,7 @@ mod test_bitsets {
         let bitset_data = BitsetStructure::format_input_data(&dataset);
         let structure = BitsetStructure::new(&bitset_data);
 
-        assert_eq!(structure.support, 10);
+        assert_eq!(structure.support, 10.);
         assert_eq!(structure.position.is_empty(), true);
         assert_eq!(structure.num_labels(), 2);
         assert_eq!(structure.num_attributes(), 4);
@@ -410,7 +466,7 @@ mod test_bitsets {
 
         structure.push((3, 1));
         let s = structure.temp_push((2, 1));
-        assert_eq!(s, 2);
+        assert_eq!(s, 2.);
 
         structure.push((0, 0));
         structure.backtrack();
@@ -432,7 +488,7 @@ mod test_bitsets {
         let mut structure = BitsetStructure::new(&bitset_data);
 
         let num_attributes = bitset_data.inputs.len();
-        let expected_supports = [5usize, 5, 6, 6];
+        let expected_supports = [5., 5., 6., 6.];
 
         for i in 0..num_attributes {
             let support = structure.push((i, 0));
@@ -452,10 +508,10 @@ mod test_bitsets {
             structure.push(*item);
         }
 
-        assert_eq!(structure.support, 2);
-        assert_eq!(structure.label_support(0), 1);
-        assert_eq!(structure.label_support(1), 1);
-        assert_eq!(structure.labels_support().iter().eq([1, 1].iter()), true);
+        assert_eq!(structure.support, 2.);
+        assert_eq!(structure.label_support(0), 1.);
+        assert_eq!(structure.label_support(1), 1.);
+        assert_eq!(structure.labels_support().iter().eq([1., 1.].iter()), true);
     }
 
     #[test]
@@ -1841,13 +1897,37 @@ mod test_bitsets {
 
         structure.reset();
 
-        assert_eq!(structure.support(), 812);
+        assert_eq!(structure.support(), 812.);
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
     }
 
+    #[test]
+    fn weighted_supports() {
+        let mut dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        let weights = (0..dataset.train_size())
+            .map(|i| (i % 3) as f64 * 0.75)
+            .collect::<Vec<f64>>();
+        dataset.set_sample_weights(weights.clone()).unwrap();
+        let bitset_data = BitsetStructure::format_input_data(&dataset);
+        let mut structure = BitsetStructure::new(&bitset_data);
+
+        let (targets, rows) = dataset.get_train();
+        let mut expected = [0.; 2];
+        for (tid, row) in rows.iter().enumerate() {
+            if row[43] == 1 {
+                expected[targets[tid]] += weights[tid];
+            }
+        }
+
+        assert_eq!(structure.support(), weights.iter().sum::<f64>());
+        assert_eq!(structure.push((43, 1)), expected[0] + expected[1]);
+        assert_eq!(structure.labels_support(), expected);
+        assert_eq!(structure.label_support(1), expected[1]);
+    }
+
     #[test]
     fn see_tids() {
         let dataset = BinaryDataset::load("test_data/rsparse_dataset.txt", false, 0.0);
@@ -0,0 +1,163 @@
+use crate::structures::caching::hash_cache::HashCache;
+use crate::structures::caching::trie::{DataTrait, Trie, TrieNode};
+use crate::structures::structures_types::{Index, Item};
+
+/// Storage of the search results, an entry per itemset. The indexes of the entries stay valid
+/// until they are evicted.
+pub trait Cache<T: DataTrait> {
+    fn is_empty(&self) -> bool;
+
+    fn len(&self) -> usize;
+
+    /// Bounds the number of entries to what fits in `size` bytes, `evict` makes room once full.
+    fn set_memory_limit(&mut self, size: usize);
+
+    fn max_nodes(&self) -> usize;
+
+    fn is_full(&self) -> bool {
+        self.len() >= self.max_nodes()
+    }
+
+    fn hits(&self) -> usize;
+
+    fn misses(&self) -> usize;
+
+    fn add_root(&mut self, root: TrieNode<T>) -> Index;
+
+    fn get_root_index(&self) -> Index;
+
+    fn get_node(&self, index: Index) -> Option<&TrieNode<T>>;
+
+    fn get_node_mut(&mut self, index: Index) -> Option<&mut TrieNode<T>>;
+
+    fn find<'a, I: Iterator<Item = &'a Item> + Clone>(&self, itemset: I) -> Option<Index>;
+
+    /// Returns whether the entry holds no data yet, and its index.
+    fn find_or_create<'a, I: Iterator<Item = &'a Item> + Clone>(
+        &mut self,
+        itemset: I,
+    ) -> (bool, Index);
+
+    /// Items of the entry at `index`.
+    fn path(&self, index: Index) -> Vec<Item>;
+
+    /// Removes every entry out of `keep` and returns how many were freed, the kept entries keep
+    /// their index.
+    fn evict(&mut self, keep: &[Index]) -> usize;
+}
+
+/// The cache backend chosen at run time through `CacheType`.
+#[derive(Debug)]
+pub enum CacheBackend<T> {
+    Trie(Trie<T>),
+    Hash(HashCache<T>),
+}
+
+impl<T: DataTrait> Default for CacheBackend<T> {
+    fn default() -> Self {
+        Self::Trie(Trie::default())
+    }
+}
+
+impl<T: DataTrait> Cache<T> for CacheBackend<T> {
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Trie(cache) => cache.is_empty(),
+            Self::Hash(cache) => cache.is_empty(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Trie(cache) => cache.len(),
+            Self::Hash(cache) => cache.len(),
+        }
+    }
+
+    fn set_memory_limit(&mut self, size: usize) {
+        match self {
+            Self::Trie(cache) => cache.set_memory_limit(size),
+            Self::Hash(cache) => cache.set_memory_limit(size),
+        }
+    }
+
+    fn max_nodes(&self) -> usize {
+        match self {
+            Self::Trie(cache) => cache.max_nodes(),
+            Self::Hash(cache) => cache.max_nodes(),
+        }
+    }
+
+    fn hits(&self) -> usize {
+        match self {
+            Self::Trie(cache) => cache.hits(),
+            Self::Hash(cache) => cache.hits(),
+        }
+    }
+
+    fn misses(&self) -> usize {
+        match self {
+            Self::Trie(cache) => cache.misses(),
+            Self::Hash(cache) => cache.misses(),
+        }
+    }
+
+    fn add_root(&mut self, root: TrieNode<T>) -> Index {
+        match self {
+            Self::Trie(cache) => cache.add_root(root),
+            Self::Hash(cache) => cache.add_root(root),
+        }
+    }
+
+    fn get_root_index(&self) -> Index {
+        match self {
+            Self::Trie(cache) => cache.get_root_index(),
+            Self::Hash(cache) => cache.get_root_index(),
+        }
+    }
+
+    fn get_node(&self, index: Index) -> Option<&TrieNode<T>> {
+        match self {
+            Self::Trie(cache) => cache.get_node(index),
+            Self::Hash(cache) => cache.get_node(index),
+        }
+    }
+
+    fn get_node_mut(&mut self, index: Index) -> Option<&mut TrieNode<T>> {
+        match self {
+            Self::Trie(cache) => cache.get_node_mut(index),
+            Self::Hash(cache) => cache.get_node_mut(index),
+        }
+    }
+
+    fn find<'a, I: Iterator<Item = &'a Item> + Clone>(&self, itemset: I) -> Option<Index> {
+        match self {
+            Self::Trie(cache) => cache.find(itemset),
+            Self::Hash(cache) => cache.find(itemset),
+        }
+    }
+
+    fn find_or_create<'a, I: Iterator<Item = &'a Item> + Clone>(
+        &mut self,
+        itemset: I,
+    ) -> (bool, Index) {
+        match self {
+            Self::Trie(cache) => cache.find_or_create(itemset),
+            Self::Hash(cache) => cache.find_or_create(itemset),
+        }
+    }
+
+    fn path(&self, index: Index) -> Vec<Item> {
+        match self {
+            Self::Trie(cache) => cache.path(index),
+            Self::Hash(cache) => cache.path(index),
+        }
+    }
+
+    fn evict(&mut self, keep: &[Index]) -> usize {
+        match self {
+            Self::Trie(cache) => cache.evict(keep),
+            Self::Hash(cache) => cache.evict(keep),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::errors::{PyTreesError, PyTreesResult};
+use crate::structures::caching::cache_trait::Cache;
+use crate::structures::caching::trie::{DataTrait, Trie, TrieNode, MEMORY_SIZE};
+use crate::structures::structures_types::{mix, Index, Item, MAX_INT};
+use nohash_hasher::BuildNoHashHasher;
+use std::collections::HashMap;
+
+/// Cache holding an entry per itemset in a hash map. A lookup hashes the items once where the
+/// trie scans the children of each node on the path.
+#[derive(Debug)]
+pub struct HashCache<T> {
+    cache: Vec<TrieNode<T>>,
+    keys: Vec<Vec<Item>>, // Items of each entry, in the order they are looked up with
+    map: HashMap<u64, Index, BuildNoHashHasher<u64>>, // Collisions go to the next free hash
+    free: Vec<Index>,     // Slots of the evicted entries, reused before the cache grows
+    memory_limit: usize,  // Memory budget in bytes, <usize>::MAX when the cache is unbounded
+    key_slots: usize,     // Capacity of the keys, counted in the budget
+    hits: usize,
+    misses: usize,
+}
+
+impl<T: DataTrait> Default for HashCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: DataTrait> HashCache<T> {
+    pub fn new() -> Self {
+        Self::with_entries(0)
+    }
+
+    // The allocation is capped to 2GB, the cache can still grow past it
+    pub fn from_user_memory(size: usize) -> PyTreesResult<Self> {
+        let entries = size.min(MEMORY_SIZE) / Self::entry_size();
+        let mut cache = Self::new();
+        let reserved = cache.cache.try_reserve_exact(entries).is_ok()
+            && cache.keys.try_reserve_exact(entries).is_ok()
+            && cache.map.try_reserve(entries).is_ok();
+        match reserved {
+            true => Ok(cache),
+            false => Err(PyTreesError::Memory(format!(
+                "Cannot allocate a cache of {size} bytes"
+            ))),
+        }
+    }
+
+    pub fn with_capacity(features: usize, depth: usize) -> Self {
+        Self::with_entries(Trie::<T>::cache_size(features, depth))
+    }
+
+    fn with_entries(entries: usize) -> Self {
+        Self {
+            cache: Vec::with_capacity(entries),
+            keys: Vec::with_capacity(entries),
+            map: HashMap::with_capacity_and_hasher(entries, BuildNoHashHasher::default()),
+            free: Vec::new(),
+            memory_limit: <usize>::MAX,
+            key_slots: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // Bytes of an entry, without the items of its key
+    fn entry_size() -> usize {
+        std::mem::size_of::<TrieNode<T>>()
+            + std::mem::size_of::<Vec<Item>>()
+            + std::mem::size_of::<(u64, Index)>()
+    }
+
+    fn hash_items<'a, I: Iterator<Item = &'a Item>>(itemset: I) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325;
+        for item in itemset {
+            hash = mix(hash ^ item.0 as u64);
+            hash = mix(hash ^ item.1 as u64);
+        }
+        hash
+    }
+
+    // Hash the itemset is stored at or would be stored at, with its entry if it exists
+    fn slot<'a, I: Iterator<Item = &'a Item> + Clone>(&self, itemset: I) -> (u64, Option<Index>) {
+        let mut hash = Self::hash_items(itemset.clone());
+        loop {
+            match self.map.get(&hash) {
+                None => return (hash, None),
+                Some(index) if self.keys[*index].iter().eq(itemset.clone()) => {
+                    return (hash, Some(*index))
+                }
+                Some(_) => hash = hash.wrapping_add(1),
+            }
+        }
+    }
+
+    fn add_entry(&mut self, hash: u64, key: Vec<Item>, mut node: TrieNode<T>) -> Index {
+        node.is_entry = true;
+        self.key_slots += key.capacity();
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.cache[index] = node;
+                self.keys[index] = key;
+                index
+            }
+            None => {
+                self.cache.push(node);
+                self.keys.push(key);
+                self.cache.len() - 1
+            }
+        };
+        self.cache[index].index = index;
+        self.map.insert(hash, index);
+        index
+    }
+}
+
+impl<T: DataTrait> Cache<T> for HashCache<T> {
+    fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.cache.len() - self.free.len()
+    }
+
+    fn set_memory_limit(&mut self, size: usize) {
+        self.memory_limit = size;
+    }
+
+    // The entries that fit in the budget next to the keys allocated so far
+    fn max_nodes(&self) -> usize {
+        if self.memory_limit == <usize>::MAX {
+            return <usize>::MAX;
+        }
+        let keys_size = self.key_slots * std::mem::size_of::<Item>();
+        (self.memory_limit.saturating_sub(keys_size) / Self::entry_size()).max(1)
+    }
+
+    fn hits(&self) -> usize {
+        self.hits
+    }
+
+    fn misses(&self) -> usize {
+        self.misses
+    }
+
+    fn add_root(&mut self, root: TrieNode<T>) -> Index {
+        let (hash, _) = self.slot([].iter());
+        self.add_entry(hash, vec![], root)
+    }
+
+    fn get_root_index(&self) -> Index {
+        0
+    }
+
+    fn get_node(&self, index: Index) -> Option<&TrieNode<T>> {
+        self.cache.get(index)
+    }
+
+    fn get_node_mut(&mut self, index: Index) -> Option<&mut TrieNode<T>> {
+        self.cache.get_mut(index)
+    }
+
+    fn find<'a, I: Iterator<Item = &'a Item> + Clone>(&self, itemset: I) -> Option<Index> {
+        self.slot(itemset).1
+    }
+
+    fn find_or_create<'a, I: Iterator<Item = &'a Item> + Clone>(
+        &mut self,
+        itemset: I,
+    ) -> (bool, Index) {
+        let (hash, index) = self.slot(itemset.clone());
+        if let Some(index) = index {
+            self.hits += 1;
+            return (false, index);
+        }
+
+        self.misses += 1;
+        let key = itemset.cloned().collect::<Vec<Item>>();
+        let mut node = match key.last() {
+            Some(item) => TrieNode::new(T::create_on_item(item)),
+            None => TrieNode::new(T::new()),
+        };
+        node.item = key.last().copied().unwrap_or((MAX_INT, 0));
+        (true, self.add_entry(hash, key, node))
+    }
+
+    fn path(&self, index: Index) -> Vec<Item> {
+        self.keys[index].clone()
+    }
+
+    fn evict(&mut self, keep: &[Index]) -> usize {
+        let mut kept = vec![false; self.cache.len()];
+        kept[self.get_root_index()] = true;
+        for index in keep {
+            kept[*index] = true;
+        }
+        for index in self.free.iter() {
+            kept[*index] = true;
+        }
+
+        let mut evicted = 0;
+        for (index, node) in self.cache.iter_mut().enumerate() {
+            if !kept[index] {
+                node.is_entry = false;
+                self.key_slots -= self.keys[index].capacity();
+                self.keys[index] = Vec::new();
+                self.free.push(index);
+                evicted += 1;
+            }
+        }
+
+        // Removing entries breaks the chains of collisions, the map is built again
+        self.map.clear();
+        for index in 0..self.cache.len() {
+            if self.cache[index].is_entry {
+                let (hash, _) = self.slot(self.keys[index].iter());
+                self.map.insert(hash, index);
+            }
+        }
+        evicted
+    }
+}
@@ -1 +1,3 @@
+pub mod cache_trait;
+pub mod hash_cache;
 pub mod trie;
@@ -1,42 +1,50 @@
+use crate::errors::{PyTreesError, PyTreesResult};
+use crate::structures::caching::cache_trait::Cache;
 use crate::structures::structures_types::{Attribute, Depth, Index, Item, MAX_INT};
 use nohash_hasher::BuildNoHashHasher;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashMap};
 use std::slice::Iter;
 
-static MEMORY_SIZE: usize = 2_000_000_000; // 2GB
+pub(crate) static MEMORY_SIZE: usize = 2_000_000_000; // 2GB
 
 pub trait DataTrait {
     fn new() -> Self;
     fn create_on_item(item: &Item) -> Self;
-    fn get_node_error(&self) -> usize;
-    fn get_leaf_error(&self) -> usize;
-    fn set_node_error(&mut self, error: usize);
-    fn set_leaf_error(&mut self, error: usize);
+    fn get_node_error(&self) -> f64;
+    fn get_leaf_error(&self) -> f64;
+    fn set_node_error(&mut self, error: f64);
+    fn set_leaf_error(&mut self, error: f64);
     fn set_test(&mut self, test: Attribute);
     fn set_class(&mut self, class: usize);
     fn get_class(&self) -> usize;
-    fn get_lower_bound(&self) -> usize;
-    fn set_lower_bound(&mut self, lower_bound: usize);
+    fn set_prediction(&mut self, prediction: f64);
+    fn get_prediction(&self) -> Option<f64>;
+    fn get_lower_bound(&self) -> f64;
+    fn set_lower_bound(&mut self, lower_bound: f64);
     fn get_test(&self) -> Attribute;
     fn to_leaf(&mut self);
     fn is_leaf(&self) -> bool;
     fn set_as_leaf(&mut self);
     fn set_discrepancy(&mut self, discrepancy: usize);
     fn get_discrepancy(&self) -> usize;
+    fn set_left_leaves(&mut self, leaves: usize);
+    fn get_left_leaves(&self) -> usize;
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Data {
-    // TODO: Should use float ?
     pub test: Attribute,
     pub depth: Depth,
     pub current_discrepancy: usize,
-    pub error: usize,
-    pub error_as_leaf: usize,
-    pub lower_bound: usize,
+    pub error: f64,
+    pub error_as_leaf: f64,
+    pub lower_bound: f64,
     pub out: usize,
+    pub prediction: Option<f64>, // Leaf value of regression trees
     pub is_leaf: bool,
     pub metric: Option<f64>,
+    pub left_leaves: usize, // Leaves given to the left child under a leaves budget
 }
 
 impl Default for Data {
@@ -51,12 +59,14 @@ impl DataTrait for Data {
             test: MAX_INT,
             depth: 0,
             current_discrepancy: 0,
-            error: MAX_INT,
-            error_as_leaf: MAX_INT,
-            lower_bound: 0,
+            error: <f64>::MAX,
+            error_as_leaf: <f64>::MAX,
+            lower_bound: 0.,
             out: MAX_INT,
+            prediction: None,
             is_leaf: false,
             metric: None,
+            left_leaves: 0,
         }
     }
 
@@ -66,19 +76,19 @@ impl DataTrait for Data {
         data
     }
 
-    fn get_node_error(&self) -> usize {
+    fn get_node_error(&self) -> f64 {
         self.error
     }
 
-    fn get_leaf_error(&self) -> usize {
+    fn get_leaf_error(&self) -> f64 {
         self.error_as_leaf
     }
 
-    fn set_node_error(&mut self, error: usize) {
+    fn set_node_error(&mut self, error: f64) {
         self.error = error;
     }
 
-    fn set_leaf_error(&mut self, error: usize) {
+    fn set_leaf_error(&mut self, error: f64) {
         self.error_as_leaf = error;
     }
 
@@ -94,11 +104,19 @@ impl DataTrait for Data {
         self.out
     }
 
-    fn get_lower_bound(&self) -> usize {
+    fn set_prediction(&mut self, prediction: f64) {
+        self.prediction = Some(prediction);
+    }
+
+    fn get_prediction(&self) -> Option<f64> {
+        self.prediction
+    }
+
+    fn get_lower_bound(&self) -> f64 {
         self.lower_bound
     }
 
-    fn set_lower_bound(&mut self, lower_bound: usize) {
+    fn set_lower_bound(&mut self, lower_bound: f64) {
         self.lower_bound = lower_bound;
     }
 
@@ -126,15 +144,24 @@ impl DataTrait for Data {
     fn get_discrepancy(&self) -> usize {
         self.current_discrepancy
     }
+
+    fn set_left_leaves(&mut self, leaves: usize) {
+        self.left_leaves = leaves;
+    }
+
+    fn get_left_leaves(&self) -> usize {
+        self.left_leaves
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct TrieNode<T> {
-    // TODO: Add parent index
     pub item: Item,
     pub value: T,
     pub index: Index,
+    pub parent: Index,
     pub node_children: Vec<Index>,
+    pub is_entry: bool, // False for the nodes only created on the path to a longer itemset
 }
 
 impl<T> TrieNode<T> {
@@ -143,7 +170,9 @@ impl<T> TrieNode<T> {
             item: (MAX_INT, 0),
             value,
             index: 0,
+            parent: 0,
             node_children: Vec::new(),
+            is_entry: false,
         }
     }
 }
@@ -151,6 +180,11 @@ impl<T> TrieNode<T> {
 #[derive(Debug)]
 pub struct Trie<T> {
     cache: Vec<TrieNode<T>>,
+    free: Vec<Index>,    // Slots of the evicted nodes, reused before the cache grows
+    memory_limit: usize, // Memory budget in bytes, <usize>::MAX when the cache is unbounded
+    child_slots: usize,  // Capacity of the children vectors, counted in the budget
+    hits: usize,         // Calls of find_or_create that found the whole itemset
+    misses: usize,       // Calls of find_or_create that created an entry
 }
 
 impl<T: DataTrait> Default for Trie<T> {
@@ -163,21 +197,32 @@ impl<T: DataTrait> Trie<T> {
     pub fn new() -> Self {
         Self {
             cache: Vec::new(), // TODO : Find a better way to set the capacity
+            free: Vec::new(),
+            memory_limit: <usize>::MAX,
+            child_slots: 0,
+            hits: 0,
+            misses: 0,
         }
     }
 
     // Start :Implement a better way to set the capacity
 
-    pub fn from_user_memory(size: usize) -> Self {
-        if size > MEMORY_SIZE {
-            panic!("Memory size is too big! Only 2GB is allowed to start with.");
-        }
-
-        let vec_size = size / std::mem::size_of::<TrieNode<T>>();
+    // The allocation is capped to 2GB, the cache can still grow past it
+    pub fn from_user_memory(size: usize) -> PyTreesResult<Self> {
+        let vec_size = size.min(MEMORY_SIZE) / std::mem::size_of::<TrieNode<T>>();
+        let mut cache = Vec::new();
+        cache.try_reserve_exact(vec_size).map_err(|_| {
+            PyTreesError::Memory(format!("Cannot allocate a cache of {size} bytes"))
+        })?;
 
-        Self {
-            cache: Vec::with_capacity(vec_size),
-        }
+        Ok(Self {
+            cache,
+            free: Vec::new(),
+            memory_limit: <usize>::MAX,
+            child_slots: 0,
+            hits: 0,
+            misses: 0,
+        })
     }
 
     pub fn with_capacity(features: usize, depth: usize) -> Self {
@@ -185,6 +230,11 @@ impl<T: DataTrait> Trie<T> {
 
         Self {
             cache: Vec::with_capacity(cache_size),
+            free: Vec::new(),
+            memory_limit: <usize>::MAX,
+            child_slots: 0,
+            hits: 0,
+            misses: 0,
         }
     }
 
@@ -196,7 +246,7 @@ impl<T: DataTrait> Trie<T> {
         (n - r + 1..=n).product::<u64>() / Self::factorial(r)
     }
 
-    fn cache_size(features: usize, depth: usize) -> usize {
+    pub(crate) fn cache_size(features: usize, depth: usize) -> usize {
         let mut size = 0;
         for i in 1..depth {
             size += Self::count_combinations(features as u64, i as u64) * 2u64.pow(i as u32);
@@ -213,25 +263,22 @@ impl<T: DataTrait> Trie<T> {
 
     // End : Implement a better way to set the capacity
 
-    pub fn is_empty(&self) -> bool {
-        self.cache.is_empty()
-    }
-
-    pub fn len(&self) -> usize {
-        self.cache.len()
-    }
-
-    fn resize(&mut self) {
-        // TODO: Implement Log resize method
-        unimplemented!()
-    }
-
     // Begin : Index based methods
 
     pub fn add_node(&mut self, parent: Index, mut node: TrieNode<T>) -> Index {
-        node.index = self.cache.len();
-        self.cache.push(node);
-        let position = self.cache.len() - 1;
+        node.parent = parent;
+        let position = match self.free.pop() {
+            Some(position) => {
+                node.index = position;
+                self.cache[position] = node;
+                position
+            }
+            None => {
+                node.index = self.cache.len();
+                self.cache.push(node);
+                self.cache.len() - 1
+            }
+        };
         if position == 0 {
             return position;
         }
@@ -239,35 +286,89 @@ impl<T: DataTrait> Trie<T> {
         position
     }
 
-    pub fn add_root(&mut self, root: TrieNode<T>) -> Index {
-        self.add_node(0, root)
+    // End : Index based methods
+
+    // NodeIndex : Get Iterator
+    fn children(&self, index: Index) -> Iter<'_, Index> {
+        self.cache[index].node_children.iter()
     }
 
-    pub fn get_root_index(&self) -> Index {
-        0
+    fn add_child(&mut self, parent: Index, child_index: Index) {
+        let children = &mut self.cache[parent].node_children;
+        let capacity = children.capacity();
+        children.push(child_index);
+        self.child_slots += children.capacity() - capacity;
     }
 
-    pub fn get_node(&self, index: Index) -> Option<&TrieNode<T>> {
-        self.cache.get(index)
+    // Start: Cache Exploration based on Itemset
+    fn create_cache_entry(&mut self, parent: Index, item: &Item) -> Index {
+        let data = T::create_on_item(item);
+        let mut node = TrieNode::new(data);
+        node.item = *item;
+        self.add_node(parent, node)
     }
 
-    pub fn get_node_mut(&mut self, index: Index) -> Option<&mut TrieNode<T>> {
-        self.cache.get_mut(index)
+    pub fn update<'a, I: Iterator<Item = &'a Item> + Clone>(&mut self, itemset: I, data: T) {
+        let index = self.find(itemset);
+        if let Some(node_index) = index {
+            if let Some(node) = self.get_node_mut(node_index) {
+                node.value = data;
+            }
+        }
     }
 
-    // End : Index based methods
+    // End: Cache Exploration based on Itemset
+}
 
-    // NodeIndex : Get Iterator
-    fn children(&self, index: Index) -> Iter<'_, Index> {
-        self.cache[index].node_children.iter()
+impl<T: DataTrait> Cache<T> for Trie<T> {
+    fn is_empty(&self) -> bool {
+        self.cache.is_empty()
     }
 
-    fn add_child(&mut self, parent: Index, child_index: Index) {
-        self.cache[parent].node_children.push(child_index);
+    fn len(&self) -> usize {
+        self.cache.len() - self.free.len()
     }
 
-    // Start: Cache Exploration based on Itemset
-    pub fn find<'a, I: Iterator<Item = &'a (usize, usize)>>(&self, itemset: I) -> Option<Index> {
+    fn set_memory_limit(&mut self, size: usize) {
+        self.memory_limit = size;
+    }
+
+    // The nodes that fit in the budget next to the children vectors allocated so far
+    fn max_nodes(&self) -> usize {
+        if self.memory_limit == <usize>::MAX {
+            return <usize>::MAX;
+        }
+        let children_size = self.child_slots * std::mem::size_of::<Index>();
+        (self.memory_limit.saturating_sub(children_size) / std::mem::size_of::<TrieNode<T>>())
+            .max(1)
+    }
+
+    fn hits(&self) -> usize {
+        self.hits
+    }
+
+    fn misses(&self) -> usize {
+        self.misses
+    }
+
+    fn add_root(&mut self, mut root: TrieNode<T>) -> Index {
+        root.is_entry = true;
+        self.add_node(0, root)
+    }
+
+    fn get_root_index(&self) -> Index {
+        0
+    }
+
+    fn get_node(&self, index: Index) -> Option<&TrieNode<T>> {
+        self.cache.get(index)
+    }
+
+    fn get_node_mut(&mut self, index: Index) -> Option<&mut TrieNode<T>> {
+        self.cache.get_mut(index)
+    }
+
+    fn find<'a, I: Iterator<Item = &'a Item> + Clone>(&self, itemset: I) -> Option<Index> {
         let mut index = self.get_root_index();
         for item in itemset {
             let children = self.children(index);
@@ -285,10 +386,13 @@ impl<T: DataTrait> Trie<T> {
                 return None;
             }
         }
-        Some(index)
+        match self.cache[index].is_entry {
+            true => Some(index),
+            false => None,
+        }
     }
 
-    pub fn find_or_create<'a, I: Iterator<Item = &'a (usize, usize)>>(
+    fn find_or_create<'a, I: Iterator<Item = &'a Item> + Clone>(
         &mut self,
         itemset: I,
     ) -> (bool, Index) {
@@ -311,25 +415,72 @@ impl<T: DataTrait> Trie<T> {
                 index = self.create_cache_entry(index, item);
             }
         }
+        // A node created on the path to a longer itemset holds no data yet
+        if !self.cache[index].is_entry {
+            self.cache[index].is_entry = true;
+            new = true;
+        }
+        match new {
+            true => self.misses += 1,
+            false => self.hits += 1,
+        }
 
         (new, index)
     }
 
-    fn create_cache_entry(&mut self, parent: Index, item: &Item) -> Index {
-        let data = T::create_on_item(item);
-        let mut node = TrieNode::new(data);
-        node.item = *item;
-        self.add_node(parent, node)
-    }
+    fn path(&self, index: Index) -> Vec<Item> {
+        let mut path = vec![];
+        let mut index = index;
+        while index != self.get_root_index() {
+            path.push(self.cache[index].item);
+            index = self.cache[index].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn evict(&mut self, keep: &[Index]) -> usize {
+        // None when evicted, Some(true) for the kept entries and Some(false) for their path
+        let mut kept: Vec<Option<bool>> = vec![None; self.cache.len()];
+        kept[self.get_root_index()] = Some(false);
+        for index in keep {
+            kept[*index] = Some(true);
+            let mut ancestor = *index;
+            while ancestor != self.get_root_index() {
+                ancestor = self.cache[ancestor].parent;
+                kept[ancestor].get_or_insert(false);
+            }
+        }
+        let mut is_free = vec![false; self.cache.len()];
+        for index in self.free.iter() {
+            is_free[*index] = true;
+        }
 
-    pub fn update<'a, I: Iterator<Item = &'a (usize, usize)>>(&mut self, itemset: I, data: T) {
-        let index = self.find(itemset);
-        if let Some(node_index) = index {
-            if let Some(node) = self.get_node_mut(node_index) {
-                node.value = data;
+        let mut evicted = 0;
+        for (index, node) in self.cache.iter_mut().enumerate() {
+            if is_free[index] {
+                continue;
+            }
+            match kept[index] {
+                None => {
+                    self.child_slots -= node.node_children.capacity();
+                    node.node_children = Vec::new();
+                    node.is_entry = false;
+                    self.free.push(index);
+                    evicted += 1;
+                }
+                Some(is_entry) => {
+                    if !is_entry && index != 0 {
+                        node.value = T::create_on_item(&node.item);
+                        node.is_entry = false;
+                    }
+                    let capacity = node.node_children.capacity();
+                    node.node_children.retain(|child| kept[*child].is_some());
+                    node.node_children.shrink_to_fit();
+                    self.child_slots -= capacity - node.node_children.capacity();
+                }
             }
         }
+        evicted
     }
-
-    // End: Cache Exploration based on Itemset
 }
@@ -1,6 +1,6 @@
 use crate::dataset::data_trait::Dataset;
 use crate::structures::structure_trait::Structure;
-use crate::structures::structures_types::{Attribute, DoublePointerData, Item, Position, Support};
+use crate::structures::structures_types::{DoublePointerData, Item, Position, Support};
 use search_trail::{
     BoolManager, ReversibleBool, ReversibleUsize, SaveAndRestore, StateManager, UsizeManager,
 };
@@ -41,10 +41,10 @@ impl<'data> Structure for DoublePointerStructure<'data> {
     fn label_support(&self, label: usize) -> Support {
         let is_left = self.manager.get_bool(self.is_left);
         let (start, end) = self.get_borders();
-        let mut support = 0;
+        let mut support = 0.;
         for tid in self.tids[start..end].iter() {
             if self.input.target[*tid] == label {
-                support += 1;
+                support += 1.;
             }
         }
         support
@@ -57,7 +57,7 @@ impl<'data> Structure for DoublePointerStructure<'data> {
 
         self.labels_support.clear();
         for label in 0..self.num_labels {
-            self.labels_support.push(0);
+            self.labels_support.push(0.);
         }
 
         // Getting the concerned border for the current state
@@ -66,22 +66,22 @@ impl<'data> Structure for DoublePointerStructure<'data> {
         // Looping over elements between the borders in tid vector
         for tid in self.tids[start..end].iter() {
             let label = self.input.target[*tid];
-            self.labels_support[label] += 1;
+            self.labels_support[label] += 1.;
         }
         &self.labels_support
     }
 
     fn support(&mut self) -> Support {
-        if !self.support == Support::MAX {
+        if self.support == 0. {
             return self.support;
         }
         let is_left = self.manager.get_bool(self.is_left);
         let (start, end) = self.get_borders();
         if start >= end {
-            self.support = 0;
-            return 0;
+            self.support = 0.;
+            return 0.;
         }
-        self.support = end - start;
+        self.support = (end - start) as Support;
         self.support
     }
 
@@ -110,7 +110,7 @@ impl<'data> Structure for DoublePointerStructure<'data> {
 
     fn temp_push(&mut self, item: Item) -> Support {
         let statue_value = self.pushing(item);
-        statue_value.4
+        statue_value.4 as Support
     }
 
     fn reset(&mut self) {
@@ -179,7 +179,7 @@ impl<'data> DoublePointerStructure<'data> {
         Self {
             input,
             tids,
-            support,
+            support: support as Support,
             num_labels: input.num_labels,
             num_attributes: input.num_attributes,
             labels_support: Vec::with_capacity(input.num_labels),
@@ -235,7 +235,7 @@ impl<'data> DoublePointerStructure<'data> {
         self.manager.set_usize(self.state[1], state_value.1);
         self.manager.set_usize(self.state[2], state_value.2);
         self.manager.set_bool(self.is_left, state_value.3);
-        self.support = state_value.4;
+        self.support = state_value.4 as Support;
     }
 
     fn get_borders(&self) -> (usize, usize) {
@@ -309,10 +309,10 @@ mod test_double_pointer {
         let dataset = BinaryDataset::load("test_data/small_.txt", false, 0.0);
         let bitset_data = DoublePointerStructure::format_input_data(&dataset);
         let mut structure = DoublePointerStructure::new(&bitset_data);
-        assert_eq!(structure.support(), 10);
-        assert_eq!(structure.label_support(0), 5);
-        assert_eq!(structure.label_support(1), 5);
-        assert_eq!(structure.labels_support(), &[5, 5]);
+        assert_eq!(structure.support(), 10.);
+        assert_eq!(structure.label_support(0), 5.);
+        assert_eq!(structure.label_support(1), 5.);
+        assert_eq!(structure.labels_support(), &[5., 5.]);
     }
 
     #[test]
@@ -336,10 +336,10 @@ mod test_double_pointer {
         }
         assert_eq!(state_value, [0, 3, 4]);
 
-        assert_eq!(structure.support(), 3);
-        assert_eq!(structure.label_support(0), 1);
-        assert_eq!(structure.label_support(1), 2);
-        assert_eq!(structure.labels_support(), &[1, 2]);
+        assert_eq!(structure.support(), 3.);
+        assert_eq!(structure.label_support(0), 1.);
+        assert_eq!(structure.label_support(1), 2.);
+        assert_eq!(structure.labels_support(), &[1., 2.]);
     }
 
     #[test]
@@ -357,10 +357,10 @@ mod test_double_pointer {
         assert_eq!(structure.tids, [2, 1, 3, 0]);
 
         let mut support = structure.support();
-        assert_eq!(support, 1);
-        assert_eq!(structure.label_support(0), 0);
-        assert_eq!(structure.label_support(1), 1);
-        assert_eq!(structure.labels_support(), &[0, 1]);
+        assert_eq!(support, 1.);
+        assert_eq!(structure.label_support(0), 0.);
+        assert_eq!(structure.label_support(1), 1.);
+        assert_eq!(structure.labels_support(), &[0., 1.]);
         let state = structure.get_state();
 
         structure.push((2, 0));
@@ -369,10 +369,10 @@ mod test_double_pointer {
         support = structure.support();
         let state = structure.get_state();
 
-        assert_eq!(support, 1);
-        assert_eq!(structure.label_support(0), 0);
-        assert_eq!(structure.label_support(1), 1);
-        assert_eq!(structure.labels_support(), &[0, 1]);
+        assert_eq!(support, 1.);
+        assert_eq!(structure.label_support(0), 0.);
+        assert_eq!(structure.label_support(1), 1.);
+        assert_eq!(structure.labels_support(), &[0., 1.]);
     }
 
     #[test]
@@ -387,15 +387,15 @@ mod test_double_pointer {
         let item = (1, 1);
         let support = structure.push(item);
 
-        assert_eq!(support, 2);
-        assert_eq!(structure.label_support(0), 1);
-        assert_eq!(structure.label_support(1), 1);
-        assert_eq!(structure.labels_support(), &[1, 1]);
+        assert_eq!(support, 2.);
+        assert_eq!(structure.label_support(0), 1.);
+        assert_eq!(structure.label_support(1), 1.);
+        assert_eq!(structure.labels_support(), &[1., 1.]);
 
         let item = (2, 0);
         let support = structure.push(item);
 
-        assert_eq!(support, 1);
+        assert_eq!(support, 1.);
     }
 
     #[test]
@@ -406,17 +406,17 @@ mod test_double_pointer {
         let num_attributes = structure.num_attributes();
 
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
-        assert_eq!(structure.temp_push((43, 1)), 26);
+        assert_eq!(structure.temp_push((43, 1)), 26.);
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
-        assert_eq!(structure.temp_push((43, 0)), 786);
+        assert_eq!(structure.temp_push((43, 0)), 786.);
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
     }
@@ -429,7 +429,7 @@ mod test_double_pointer {
 
         structure.push((3, 1));
         let s = structure.temp_push((2, 1));
-        assert_eq!(s, 2);
+        assert_eq!(s, 2.);
 
         structure.push((0, 0));
         structure.backtrack();
@@ -437,7 +437,7 @@ mod test_double_pointer {
         let expected_position = [(3usize, 1usize)];
 
         assert_eq!(structure.position.iter().eq(expected_position.iter()), true);
-        assert_eq!(structure.support, <usize>::MAX);
+        assert_eq!(structure.support, <f64>::MAX);
     }
     #[test]
     fn moving_on_step_and_backtrack() {
@@ -446,7 +446,7 @@ mod test_double_pointer {
         let mut structure = DoublePointerStructure::new(&bitset_data);
 
         let num_attributes = bitset_data.inputs.len();
-        let expected_supports = [5usize, 5, 6, 6];
+        let expected_supports = [5., 5., 6., 6.];
 
         for i in 0..num_attributes {
             let support = structure.push((i, 0));
@@ -1,7 +1,7 @@
 use crate::dataset::data_trait::Dataset;
 use crate::structures::structure_trait::Structure;
 use crate::structures::structures_types::{
-    HBSStackState, HBSState, HorizontalData, Item, Position, Support,
+    Bitset, HBSStackState, HBSState, HorizontalData, Item, Position, Support,
 };
 
 #[derive(Clone)]
@@ -28,7 +28,7 @@ impl<'data> Structure for HorizontalBinaryStructure<'data> {
         let mut support = Support::MAX;
         if label < self.num_labels {
             if let Some(state) = self.get_last_state() {
-                support = state[label].len();
+                support = state[label].len() as Support;
             }
         }
         support
@@ -41,19 +41,19 @@ impl<'data> Structure for HorizontalBinaryStructure<'data> {
         self.labels_support.clear();
         if let Some(state) = self.state.last() {
             for label_state in state.iter() {
-                self.labels_support.push(label_state.len());
+                self.labels_support.push(label_state.len() as Support);
             }
         }
         &self.labels_support
     }
 
-    fn support(&mut self) -> usize {
+    fn support(&mut self) -> Support {
         if self.support < Support::MAX {
             return self.support;
         }
         let mut support = 0;
         if let Some(last) = self.get_last_state() {
-            self.support = last.iter().map(|rows| rows.len()).sum();
+            self.support = last.iter().map(|rows| rows.len() as Support).sum();
         }
         self.support
     }
@@ -89,7 +89,7 @@ impl<'data> Structure for HorizontalBinaryStructure<'data> {
         state.push(self.state[0].clone());
         self.position = Vec::with_capacity(self.num_attributes);
         self.state = state;
-        self.support = self.input.iter().map(|label| label.len()).sum::<Support>();
+        self.support = self.input.iter().map(|label| label.len() as Support).sum();
         self.labels_support.clear();
     }
     fn get_position(&self) -> &Position {
@@ -105,6 +105,23 @@ impl<'data> Structure for HorizontalBinaryStructure<'data> {
             .cloned()
             .collect()
     }
+
+    fn get_cover(&self) -> Bitset {
+        // Transactions are indexed per label, so each label gets its own offset in the cover
+        let size = self.input.iter().map(|label| label.len()).sum::<usize>();
+        let mut cover = vec![0u64; size / 64 + 1];
+        if let Some(state) = self.get_last_state() {
+            let mut offset = 0;
+            for (label, label_state) in state.iter().enumerate() {
+                for transaction in label_state {
+                    let tid = offset + *transaction;
+                    cover[tid / 64] |= 1u64 << (tid % 64);
+                }
+                offset += self.input[label].len();
+            }
+        }
+        cover
+    }
 }
 
 impl<'data> HorizontalBinaryStructure<'data> {
@@ -135,7 +152,7 @@ impl<'data> HorizontalBinaryStructure<'data> {
 
         let mut structure = HorizontalBinaryStructure {
             input: inputs,
-            support: size,
+            support: size as Support,
             labels_support: Vec::with_capacity(inputs.len()),
             num_labels: inputs.len(),
             num_attributes: inputs[0][0].len(),
@@ -152,10 +169,10 @@ impl<'data> HorizontalBinaryStructure<'data> {
 
     fn pushing(&mut self, item: Item) {
         let mut new_state = HBSState::new();
-        self.support = 0;
+        self.support = 0.;
         self.labels_support.clear();
         for i in 0..self.num_labels {
-            self.labels_support.push(0);
+            self.labels_support.push(0.);
         }
         if let Some(last) = self.state.last() {
             for (i, label_state) in last.iter().enumerate() {
@@ -166,8 +183,8 @@ impl<'data> HorizontalBinaryStructure<'data> {
                         label_transactions.push(*transaction);
                     }
                 }
-                self.support += label_transactions.len();
-                self.labels_support[i] = label_transactions.len();
+                self.support += label_transactions.len() as Support;
+                self.labels_support[i] = label_transactions.len() as Support;
                 new_state.push(label_transactions);
             }
         }
@@ -195,8 +212,8 @@ mod test_horizontal_binary_structure {
         assert_eq!(data_structure.num_labels(), 2);
         assert_eq!(data_structure.state.iter().eq(state.iter()), true);
         assert_eq!(data_structure.input.iter().eq(input.iter()), true);
-        assert_eq!(data_structure.label_support(0), 2);
-        assert_eq!(data_structure.label_support(1), 2);
+        assert_eq!(data_structure.label_support(0), 2.);
+        assert_eq!(data_structure.label_support(1), 2.);
     }
 
     #[test]
@@ -209,9 +226,9 @@ mod test_horizontal_binary_structure {
 
         data_structure.push((0, 0));
         assert_eq!(data_structure.position.iter().eq(position.iter()), true);
-        assert_eq!(data_structure.support, 3);
-        assert_eq!(data_structure.label_support(0), 1);
-        assert_eq!(data_structure.label_support(1), 2);
+        assert_eq!(data_structure.support, 3.);
+        assert_eq!(data_structure.label_support(0), 1.);
+        assert_eq!(data_structure.label_support(1), 2.);
 
         let state = data_structure.get_last_state();
         if let Some(state) = state {
@@ -231,9 +248,9 @@ mod test_horizontal_binary_structure {
         data_structure.push((0, 1));
         assert_eq!(data_structure.position.len(), 2);
         assert_eq!(data_structure.position.iter().eq(position.iter()), true);
-        assert_eq!(data_structure.support, 1);
-        assert_eq!(data_structure.label_support(0), 1);
-        assert_eq!(data_structure.label_support(1), 0);
+        assert_eq!(data_structure.support, 1.);
+        assert_eq!(data_structure.label_support(0), 1.);
+        assert_eq!(data_structure.label_support(1), 0.);
         let state = data_structure.get_last_state();
         if let Some(state) = state {
             assert_eq!(state.iter().eq(real_state.iter()), true);
@@ -243,9 +260,9 @@ mod test_horizontal_binary_structure {
         let position = [position[0]];
         assert_eq!(data_structure.position.len(), 1);
         assert_eq!(data_structure.position.iter().eq(position.iter()), true);
-        assert_eq!(data_structure.support(), 2);
-        assert_eq!(data_structure.label_support(0), 2);
-        assert_eq!(data_structure.label_support(1), 0);
+        assert_eq!(data_structure.support(), 2.);
+        assert_eq!(data_structure.label_support(0), 2.);
+        assert_eq!(data_structure.label_support(1), 0.);
 
         let real_state = vec![vec![0usize, 1], vec![]];
 
@@ -29,7 +29,7 @@ impl<'data> Structure for RawBinaryStructure<'data> {
         if label < self.num_labels {
             let train = self.input.get_train();
             if let Some(state) = self.get_last_state() {
-                support = state.iter().filter(|x| train.0[**x] == label).count();
+                support = state.iter().filter(|x| train.0[**x] == label).count() as Support;
             }
         }
         support
@@ -51,7 +51,7 @@ impl<'data> Structure for RawBinaryStructure<'data> {
             return self.support;
         }
         if let Some(last) = self.get_last_state() {
-            self.support = last.len();
+            self.support = last.len() as Support;
         }
 
         self.support
@@ -87,7 +87,7 @@ impl<'data> Structure for RawBinaryStructure<'data> {
         state.push(self.state[0].clone());
         self.state = state;
         self.position = Vec::with_capacity(self.num_attributes);
-        self.support = self.input.size();
+        self.support = self.input.size() as Support;
         self.labels_support.clear();
     }
 
@@ -123,10 +123,10 @@ impl<'data> RawBinaryStructure<'data> {
 
     fn pushing(&mut self, item: Item) {
         let mut new_state = vec![];
-        self.support = 0;
+        self.support = 0.;
         self.labels_support.clear();
         for label in 0..self.num_labels {
-            self.labels_support.push(0);
+            self.labels_support.push(0.);
         }
         if let Some(last) = self.state.last() {
             let inputs = &self.input.get_train().1;
@@ -134,8 +134,8 @@ impl<'data> RawBinaryStructure<'data> {
             for tid in last {
                 if inputs[*tid][item.0] == item.1 {
                     new_state.push(*tid);
-                    self.support += 1;
-                    self.labels_support[target[*tid]] += 1;
+                    self.support += 1.;
+                    self.labels_support[target[*tid]] += 1.;
                 }
             }
         }
@@ -167,9 +167,9 @@ mod test_raw_binary_structure {
 
         data_structure.push((0, 0));
         assert_eq!(data_structure.position.iter().eq(position.iter()), true);
-        assert_eq!(data_structure.support, 3);
-        assert_eq!(data_structure.label_support(0), 1);
-        assert_eq!(data_structure.label_support(1), 2);
+        assert_eq!(data_structure.support, 3.);
+        assert_eq!(data_structure.label_support(0), 1.);
+        assert_eq!(data_structure.label_support(1), 2.);
 
         let state = data_structure.get_last_state();
         if let Some(state) = state {
@@ -188,9 +188,9 @@ mod test_raw_binary_structure {
         data_structure.push((0, 1));
         assert_eq!(data_structure.position.len(), 2);
         assert_eq!(data_structure.position.iter().eq(position.iter()), true);
-        assert_eq!(data_structure.support(), 1);
-        assert_eq!(data_structure.label_support(0), 1);
-        assert_eq!(data_structure.label_support(1), 0);
+        assert_eq!(data_structure.support(), 1.);
+        assert_eq!(data_structure.label_support(0), 1.);
+        assert_eq!(data_structure.label_support(1), 0.);
         let state = data_structure.get_last_state();
         if let Some(state) = state {
             assert_eq!(state.iter().eq(real_state.iter()), true);
@@ -200,9 +200,9 @@ mod test_raw_binary_structure {
         let position = [position[0]];
         assert_eq!(data_structure.position.len(), 1);
         assert_eq!(data_structure.position.iter().eq(position.iter()), true);
-        assert_eq!(data_structure.support(), 2);
-        assert_eq!(data_structure.label_support(0), 2);
-        assert_eq!(data_structure.label_support(1), 0);
+        assert_eq!(data_structure.support(), 2.);
+        assert_eq!(data_structure.label_support(0), 2.);
+        assert_eq!(data_structure.label_support(1), 0.);
 
         let real_state = vec![0, 1];
 
@@ -1,12 +1,10 @@
-use crate::algorithms::dl85_utils::slb::Similarity;
 use crate::dataset::data_trait::Dataset;
 use crate::structures::binary_tree::{NodeData, Tree, TreeNode};
 use crate::structures::bitsets_structure::BitsetStructure;
-use crate::structures::caching::trie::DataTrait;
-use crate::structures::structure_trait::{BitsetTrait, Structure};
+use crate::structures::structure_trait::{hash_cover_word, BitsetTrait, Structure};
 use crate::structures::structures_types::{
-    Bitset, BitsetStackState, BitsetStructData, Index, Item, LeafInfo, Position, StateCollection,
-    Support,
+    Bitset, BitsetStackState, BitsetStructData, Index, Item, LeafInfo, Position,
+    RegressionStatistics, StateCollection, Support,
 };
 
 #[derive(Clone)]
@@ -14,6 +12,7 @@ pub struct RSparseBitsetStructure<'data> {
     inputs: &'data BitsetStructData,
     support: Support,
     labels_support: Vec<Support>,
+    regression: Option<RegressionStatistics>,
     num_labels: usize,
     num_attributes: usize,
     position: Position,
@@ -38,17 +37,17 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
 
         if label < self.num_labels {
             if let Some(limit) = self.limit.last() {
-                let mut count = 0;
+                let mut count = 0.;
                 if *limit >= 0 {
                     let label_bitset = &self.inputs.targets[label];
                     for i in 0..(*limit + 1) as usize {
                         let cursor = self.index[i];
                         if let Some(val) = state[cursor].last() {
-                            count += (label_bitset[cursor] & val).count_ones()
+                            count += self.inputs.count(label_bitset[cursor] & val, cursor)
                         }
                     }
                 }
-                return count as Support;
+                return count;
             }
         }
         support
@@ -63,39 +62,38 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
 
         self.labels_support.clear();
         for _ in 0..self.num_labels {
-            self.labels_support.push(0);
+            self.labels_support.push(0.);
         }
 
         if let Some(limit) = self.limit.last() {
             if self.num_labels == 2 {
                 if *limit >= 0 {
                     let label_bitset = &self.inputs.targets[0];
-                    let mut count = 0;
+                    let mut count = 0.;
                     for i in 0..(*limit + 1) as usize {
                         let cursor = self.index[i];
                         if let Some(val) = state[cursor].last() {
-                            count += (label_bitset[cursor] & val).count_ones()
+                            count += self.inputs.count(label_bitset[cursor] & val, cursor)
                         }
                     }
-                    self.labels_support[0] = count as Support;
-                    self.labels_support[1] = self.support() - count as Support;
+                    self.labels_support[0] = count;
+                    self.labels_support[1] = self.support() - count;
                 }
                 return &self.labels_support;
             }
 
             for label in 0..self.num_labels {
-                let mut count = 0;
+                let mut count = 0.;
                 if *limit >= 0 {
                     let label_bitset = &self.inputs.targets[label];
                     for i in 0..(*limit + 1) as usize {
                         let cursor = self.index[i];
                         if let Some(val) = state[cursor].last() {
-                            let word = label_bitset[cursor] & val;
-                            count += (label_bitset[cursor] & val).count_ones()
+                            count += self.inputs.count(label_bitset[cursor] & val, cursor)
                         }
                     }
                 }
-                self.labels_support[label] = count as Support;
+                self.labels_support[label] = count;
             }
             return &self.labels_support;
         }
@@ -103,17 +101,17 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
     }
 
     fn support(&mut self) -> Support {
-        if !self.support == Support::MAX {
+        if self.support == 0. {
             return self.support;
         }
         let state = &self.state;
-        self.support = 0;
+        self.support = 0.;
         if let Some(limit) = self.limit.last() {
             if *limit >= 0 {
                 for i in 0..(*limit + 1) as usize {
                     let cursor = self.index[i];
                     if let Some(val) = state[cursor].last() {
-                        self.support += val.count_ones() as Support;
+                        self.support += self.inputs.count(*val, cursor);
                     }
                 }
             }
@@ -146,13 +144,14 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
             }
             self.support = Support::MAX;
             self.labels_support.clear();
+            self.regression = None;
             // self.support();
         }
     }
 
     fn temp_push(&mut self, item: Item) -> Support {
         // TODO: Change this to avoid recomputing the support & labels support
-        let mut support = 0;
+        let mut support = 0.;
 
         if let Some(limit) = self.limit.last() {
             let mut limit = *limit;
@@ -166,8 +165,7 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
                             0 => val & !feature_vec[cursor],
                             _ => val & feature_vec[cursor],
                         };
-                        let word_count = word.count_ones() as Support;
-                        support += word_count;
+                        support += self.inputs.count(word, cursor);
                     }
                 }
             }
@@ -185,13 +183,31 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
             .map(|stack| vec![stack[0]])
             .collect::<Vec<Bitset>>();
         self.state = state;
-        self.support = self.inputs.size as Support;
+        self.support = self.inputs.total_support();
         self.labels_support.clear();
+        self.regression = None;
     }
     fn get_position(&self) -> &Position {
         &self.position
     }
 
+    fn regression_statistics(&mut self) -> Option<RegressionStatistics> {
+        self.inputs.values.as_ref()?;
+        if self.regression.is_none() {
+            let mut stats = (0., 0., 0.);
+            if let Some(limit) = self.limit.last() {
+                for i in 0..(*limit + 1) as usize {
+                    let cursor = self.index[i];
+                    if let Some(word) = self.state[cursor].last() {
+                        self.inputs.accumulate_values(*word, cursor, &mut stats);
+                    }
+                }
+            }
+            self.regression = Some(stats);
+        }
+        self.regression
+    }
+
     fn get_tids(&self) -> Vec<usize> {
         if self.position.is_empty() {
             return (0..self.inputs.size).collect::<Vec<usize>>();
@@ -217,6 +233,48 @@ impl<'data> Structure for RSparseBitsetStructure<'data> {
         }
         tids
     }
+
+    fn get_cover(&self) -> Bitset {
+        self.get_last_state_bitset()
+    }
+
+    fn cover_difference(&self, cover: &Bitset, data_in: bool) -> Support {
+        let limit = self.get_current_limit();
+        let mut count = 0.;
+        match data_in {
+            true => {
+                if limit >= 0 {
+                    for cursor in self.index.iter().take(limit as usize + 1) {
+                        let val = *self.state[*cursor].last().unwrap_or(&0);
+                        count += self.inputs.count(val & !cover[*cursor], *cursor);
+                    }
+                }
+            }
+            false => {
+                let current = self.get_last_state_bitset();
+                for (cursor, (cover_word, val)) in cover.iter().zip(current.iter()).enumerate() {
+                    count += self.inputs.count(cover_word & !val, cursor);
+                }
+            }
+        }
+        count
+    }
+
+    // Only the active words of the state are read
+    fn cover_hash(&self) -> (u64, u64) {
+        let mut hash = (0, 0);
+        let limit = self.get_current_limit();
+        if limit >= 0 {
+            for cursor in self.index.iter().take(limit as usize + 1) {
+                hash_cover_word(
+                    &mut hash,
+                    *cursor,
+                    *self.state[*cursor].last().unwrap_or(&0),
+                );
+            }
+        }
+        hash
+    }
 }
 
 impl<'data> BitsetTrait for RSparseBitsetStructure<'data> {
@@ -237,7 +295,7 @@ impl<'data> BitsetTrait for RSparseBitsetStructure<'data> {
         }
 
         if left_index == right_index {
-            let mut error = <usize>::MAX;
+            let mut error = <f64>::MAX;
             if let Some(node) = tree.get_node(index) {
                 error = node.value.error;
             }
@@ -284,6 +342,7 @@ impl<'data> BitsetTrait for RSparseBitsetStructure<'data> {
         self.state = new_state;
         self.support = Support::MAX;
         self.labels_support.clear();
+        self.regression = None;
     }
 }
 
@@ -318,8 +377,9 @@ impl<'data> RSparseBitsetStructure<'data> {
 
         let mut structure = RSparseBitsetStructure {
             inputs,
-            support: inputs.size as Support,
+            support: inputs.total_support(),
             labels_support: Vec::with_capacity(inputs.targets.len()),
+            regression: None,
             num_labels: inputs.targets.len(),
             num_attributes,
             position: Vec::with_capacity(num_attributes),
@@ -339,10 +399,11 @@ impl<'data> RSparseBitsetStructure<'data> {
     }
 
     fn pushing(&mut self, item: Item) {
-        self.support = 0;
+        self.support = 0.;
         self.labels_support.clear();
+        self.regression = None;
         for _ in 0..self.num_labels {
-            self.labels_support.push(0);
+            self.labels_support.push(0.);
         }
 
         if let Some(limit) = self.limit.last() {
@@ -366,18 +427,18 @@ impl<'data> RSparseBitsetStructure<'data> {
                                 break;
                             }
                         } else {
-                            let word_count = word.count_ones() as Support;
+                            let word_count = self.inputs.count(word, cursor);
                             self.support += word_count;
                             if self.num_labels == 2 {
                                 let label_val = &self.inputs.targets[0][cursor];
-                                let zero_count = (label_val & word).count_ones() as Support;
+                                let zero_count = self.inputs.count(label_val & word, cursor);
                                 self.labels_support[0] += zero_count;
                                 self.labels_support[1] += (word_count - zero_count);
                             } else {
                                 for j in 0..self.num_labels {
                                     let label_val = &self.inputs.targets[j][cursor];
                                     self.labels_support[j] +=
-                                        (label_val & word).count_ones() as Support;
+                                        self.inputs.count(label_val & word, cursor);
                                 }
                             }
 
@@ -417,35 +478,6 @@ impl<'data> RSparseBitsetStructure<'data> {
         self.limit.last().copied().unwrap_or(-1)
     }
 
-    pub fn difference<T: DataTrait>(&self, similarity: &Similarity<T>, data_in: bool) -> usize {
-        let struc_limit = self.get_current_limit();
-
-        let limit = match data_in {
-            true => struc_limit,
-            false => similarity.limit,
-        };
-
-        let index = match data_in {
-            true => &self.index,
-            false => &similarity.index,
-        };
-        let mut count = 0;
-        if limit >= 0 {
-            for cursor in index.iter().take(limit as usize + 1) {
-                let val = match struc_limit == -1 {
-                    true => 0,
-                    false => *self.state[*cursor].last().unwrap_or(&0),
-                };
-                let diff = match data_in {
-                    true => val & !similarity.state[*cursor],
-                    false => similarity.state[*cursor] & !val,
-                };
-                count += diff.count_ones();
-            }
-            return count as usize;
-        }
-        0
-    }
     // End : Methods to evaluate the structure fo similarity lower bound
 }
 
@@ -482,10 +514,10 @@ mod test_rsparse_bitset {
         let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
         let mut structure = RSparseBitsetStructure::new(&bitset_data);
 
-        let expected_support = 192;
+        let expected_support = 192.;
         assert_eq!(structure.support, expected_support);
 
-        let expected_label_supports = [64usize, 128];
+        let expected_label_supports = [64., 128.];
         assert_eq!(structure.label_support(0), expected_label_supports[0]);
         assert_eq!(structure.label_support(1), expected_label_supports[1]);
     }
@@ -498,35 +530,35 @@ mod test_rsparse_bitset {
 
         let support = structure.push((0, 1));
 
-        assert_eq!(support, 128);
+        assert_eq!(support, 128.);
         if let Some(limit) = structure.limit.last() {
             assert_eq!(*limit, 1);
         }
 
         assert_eq!(structure.index.iter().eq([0, 2, 1].iter()), true);
-        assert_eq!(structure.label_support(1), 128);
-        assert_eq!(structure.label_support(0), 0);
+        assert_eq!(structure.label_support(1), 128.);
+        assert_eq!(structure.label_support(0), 0.);
 
         let support = structure.push((1, 0));
 
-        assert_eq!(support, 64);
+        assert_eq!(support, 64.);
         if let Some(limit) = structure.limit.last() {
             assert_eq!(*limit, 0);
         }
         assert_eq!(structure.index.iter().eq([0, 2, 1].iter()), true);
-        assert_eq!(structure.label_support(1), 64);
-        assert_eq!(structure.label_support(0), 0);
+        assert_eq!(structure.label_support(1), 64.);
+        assert_eq!(structure.label_support(0), 0.);
 
         structure.backtrack();
 
-        assert_eq!(structure.support(), 128);
+        assert_eq!(structure.support(), 128.);
         if let Some(limit) = structure.limit.last() {
             assert_eq!(*limit, 1);
         }
 
         assert_eq!(structure.index.iter().eq([0, 2, 1].iter()), true);
-        assert_eq!(structure.label_support(1), 128);
-        assert_eq!(structure.label_support(0), 0);
+        assert_eq!(structure.label_support(1), 128.);
+        assert_eq!(structure.label_support(0), 0.);
     }
 
     #[test]
@@ -537,15 +569,15 @@ mod test_rsparse_bitset {
         let num_attributes = structure.num_attributes();
 
         let support = structure.push((0, 1));
-        assert_eq!(support, 1);
-        assert_eq!(structure.label_support(0), 1);
-        assert_eq!(structure.label_support(1), 0);
-        assert_eq!(structure.labels_support().iter().eq([1, 0].iter()), true);
+        assert_eq!(support, 1.);
+        assert_eq!(structure.label_support(0), 1.);
+        assert_eq!(structure.label_support(1), 0.);
+        assert_eq!(structure.labels_support().iter().eq([1., 0.].iter()), true);
 
         let support = structure.push((1, 1));
         assert_eq!(structure.is_empty(), true);
-        assert_eq!(structure.label_support(0), 0);
-        assert_eq!(structure.label_support(1), 0);
+        assert_eq!(structure.label_support(0), 0.);
+        assert_eq!(structure.label_support(1), 0.);
 
         structure.push((2, 1));
 
@@ -556,10 +588,10 @@ mod test_rsparse_bitset {
 
         structure.backtrack();
         assert_eq!(structure.limit.iter().eq([0, 0].iter()), true);
-        assert_eq!(structure.support(), 1);
-        assert_eq!(structure.label_support(0), 1);
-        assert_eq!(structure.label_support(1), 0);
-        assert_eq!(structure.labels_support().iter().eq([1, 0].iter()), true);
+        assert_eq!(structure.support(), 1.);
+        assert_eq!(structure.label_support(0), 1.);
+        assert_eq!(structure.label_support(1), 0.);
+        assert_eq!(structure.labels_support().iter().eq([1., 0.].iter()), true);
     }
 
     #[test]
@@ -574,9 +606,9 @@ mod test_rsparse_bitset {
 
         structure.reset();
 
-        assert_eq!(structure.support(), 812);
+        assert_eq!(structure.support(), 812.);
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
     }
@@ -589,21 +621,75 @@ mod test_rsparse_bitset {
         let num_attributes = structure.num_attributes();
 
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
-        assert_eq!(structure.temp_push((43, 1)), 26);
+        assert_eq!(structure.temp_push((43, 1)), 26.);
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
-        assert_eq!(structure.temp_push((43, 0)), 786);
+        assert_eq!(structure.temp_push((43, 0)), 786.);
         assert_eq!(
-            structure.labels_support().iter().eq([187, 625].iter()),
+            structure.labels_support().iter().eq([187., 625.].iter()),
             true
         );
     }
 
+    #[test]
+    fn weighted_supports() {
+        let mut dataset = BinaryDataset::load("test_data/small.txt", false, 0.0);
+        dataset.set_sample_weights(vec![2., 1., 3., 1.]).unwrap();
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+
+        assert_eq!(structure.support(), 7.);
+        assert_eq!(structure.labels_support(), [3., 4.]);
+
+        assert_eq!(structure.push((1, 1)), 2.);
+        assert_eq!(structure.labels_support(), [1., 1.]);
+        structure.backtrack();
+
+        assert_eq!(structure.temp_push((0, 0)), 5.);
+        assert_eq!(structure.label_support(1), 4.);
+    }
+
+    #[test]
+    fn regression_statistics() {
+        let mut dataset = BinaryDataset::load("test_data/small.txt", false, 0.0);
+        assert!(
+            RSparseBitsetStructure::new(&RSparseBitsetStructure::format_input_data(&dataset))
+                .regression_statistics()
+                .is_none()
+        );
+
+        dataset.set_regression_targets(vec![1., 2., 3., 4.]);
+        dataset.set_sample_weights(vec![1., 2., 1., 1.]).unwrap();
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+        assert_eq!(structure.regression_statistics(), Some((5., 12., 34.)));
+
+        structure.push((1, 1));
+        assert_eq!(structure.regression_statistics(), Some((3., 8., 24.)));
+        structure.backtrack();
+        assert_eq!(structure.regression_statistics(), Some((5., 12., 34.)));
+    }
+
+    #[test]
+    fn weights_act_as_multiplicities() {
+        let mut dataset = BinaryDataset::load("test_data/anneal.txt", false, 0.0);
+        dataset
+            .set_sample_weights(vec![2.; dataset.train_size()])
+            .unwrap();
+        let bitset_data = RSparseBitsetStructure::format_input_data(&dataset);
+        let mut structure = RSparseBitsetStructure::new(&bitset_data);
+
+        assert_eq!(structure.support(), 1624.);
+        assert_eq!(structure.labels_support(), [374., 1250.]);
+        assert_eq!(structure.temp_push((43, 1)), 52.);
+        assert_eq!(structure.temp_push((43, 0)), 1572.);
+    }
+
     #[test]
     fn see_tids() {
         let dataset = BinaryDataset::load("test_data/rsparse_dataset.txt", false, 0.0);
@@ -1,5 +1,19 @@
 use crate::structures::binary_tree::{NodeData, Tree};
-use crate::structures::structures_types::{Bitset, Index, Item, LeafInfo, Position, Support};
+use crate::structures::structures_types::{
+    mix, Bitset, Index, Item, LeafInfo, Position, RegressionStatistics, Support,
+};
+
+/// Adds a word of the cover to its hash. The words are summed so they can come in any order,
+/// the empty ones change nothing.
+pub(crate) fn hash_cover_word(hash: &mut (u64, u64), chunk: usize, word: u64) {
+    if word != 0 {
+        let chunk_hash = mix(chunk as u64);
+        hash.0 = hash.0.wrapping_add(mix(chunk_hash ^ word));
+        hash.1 = hash
+            .1
+            .wrapping_add(mix(chunk_hash.rotate_left(32) ^ mix(word)));
+    }
+}
 
 pub trait Structure {
     fn num_attributes(&self) -> usize;
@@ -22,6 +36,52 @@ pub trait Structure {
     }
 
     fn get_tids(&self) -> Vec<usize>;
+
+    /// Count, sum and sum of squares of the regression targets in the current cover.
+    /// None when the structure holds no regression targets.
+    fn regression_statistics(&mut self) -> Option<RegressionStatistics> {
+        None
+    }
+
+    // Start : Methods used by the similarity lower bound
+
+    fn get_cover(&self) -> Bitset {
+        let mut cover = Bitset::new();
+        for tid in self.get_tids() {
+            let chunk = tid / 64;
+            if cover.len() <= chunk {
+                cover.resize(chunk + 1, 0);
+            }
+            cover[chunk] |= 1u64 << (tid % 64);
+        }
+        cover
+    }
+
+    fn cover_difference(&self, cover: &Bitset, data_in: bool) -> Support {
+        let current = self.get_cover();
+        let mut count = 0.;
+        for i in 0..current.len().max(cover.len()) {
+            let current_word = current.get(i).copied().unwrap_or(0);
+            let cover_word = cover.get(i).copied().unwrap_or(0);
+            let diff = match data_in {
+                true => current_word & !cover_word,
+                false => cover_word & !current_word,
+            };
+            count += diff.count_ones() as Support;
+        }
+        count
+    }
+
+    // End : Methods used by the similarity lower bound
+
+    /// 128 bits hash of the current cover, the itemsets selecting the same transactions share it.
+    fn cover_hash(&self) -> (u64, u64) {
+        let mut hash = (0, 0);
+        for (chunk, word) in self.get_cover().iter().enumerate() {
+            hash_cover_word(&mut hash, chunk, *word);
+        }
+        hash
+    }
 }
 
 pub trait BitsetTrait {
@@ -1,9 +1,19 @@
-pub type Support = usize;
+pub type Support = f64; // Number of transactions, summed weights with sample weights
 pub type Depth = usize;
 pub type Attribute = usize;
 pub type Item = (Attribute, usize);
 pub type Position = Vec<Item>;
 pub static MAX_INT: usize = <usize>::MAX;
+pub type RegressionStatistics = (Support, f64, f64); // (count, sum, sum of squares)
+pub type CostMatrix = Vec<Vec<f64>>; // costs[true class][predicted class]
+
+// Finalizer of splitmix64, spreads the bits of the value over the whole hash
+pub(crate) fn mix(value: u64) -> u64 {
+    let mut value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
 
 // Horizontal data structure type
 pub type HorizontalData = Vec<Vec<Vec<usize>>>;
@@ -19,6 +29,55 @@ pub struct BitsetStructData {
     pub(crate) targets: BitsetMatrix,
     pub(crate) chunks: usize,
     pub(crate) size: usize,
+    pub(crate) weights: Option<Vec<f64>>, // Indexed by chunk * 64 + bit
+    pub(crate) values: Option<Vec<f64>>,  // Regression targets, indexed as the weights
+}
+
+impl BitsetStructData {
+    /// Support of the transactions set in `word`, weighted when sample weights are given.
+    pub(crate) fn count(&self, word: u64, chunk: usize) -> Support {
+        match &self.weights {
+            None => word.count_ones() as Support,
+            Some(weights) => {
+                let mut word = word;
+                let mut count = 0.;
+                while word != 0 {
+                    let bit = word.trailing_zeros() as usize;
+                    count += weights[chunk * 64 + bit];
+                    word &= word - 1;
+                }
+                count
+            }
+        }
+    }
+
+    /// Adds the (weighted) count, sum and sum of squares of the targets set in `word` to `stats`.
+    pub(crate) fn accumulate_values(
+        &self,
+        word: u64,
+        chunk: usize,
+        stats: &mut RegressionStatistics,
+    ) {
+        if let Some(values) = &self.values {
+            let mut word = word;
+            while word != 0 {
+                let index = chunk * 64 + word.trailing_zeros() as usize;
+                let weight = self.weights.as_ref().map_or(1., |weights| weights[index]);
+                let value = values[index];
+                stats.0 += weight;
+                stats.1 += weight * value;
+                stats.2 += weight * value * value;
+                word &= word - 1;
+            }
+        }
+    }
+
+    pub(crate) fn total_support(&self) -> Support {
+        match &self.weights {
+            None => self.size as Support,
+            Some(weights) => weights.iter().sum(),
+        }
+    }
 }
 
 pub type BitsetStackState = Vec<Bitset>;
@@ -40,5 +99,5 @@ pub struct LeafInfo {
     pub(crate) index: Index,
     pub(crate) position: Position,
     pub(crate) bitset: Bitset,
-    pub(crate) error: usize,
+    pub(crate) error: f64,
 }
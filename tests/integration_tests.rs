@@ -1,6 +1,7 @@
 use paste::paste;
 use pytrees::algorithms::algorithm_trait::{Algorithm, Basic};
 use pytrees::algorithms::dl85::DL85;
+use pytrees::algorithms::dl85_utils::leaf_objective::Misclassification;
 use pytrees::algorithms::dl85_utils::structs_enums::{
     BranchingType, CacheInit, LowerBoundHeuristic, Specialization,
 };
@@ -28,7 +29,7 @@ macro_rules! integration_tests_lgdt {
                  fn [<lgdt_ $name _ $algo _raw_ $name _minsup_ $minsup _depth_ $depth>]() {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let mut structure = RawBinaryStructure::new(&data);
-                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup, $depth, $algo), $value);
+                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup as Support, $depth, $algo), $value as f64);
                 }
 
                 #[test]
@@ -36,7 +37,7 @@ macro_rules! integration_tests_lgdt {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let horizontal_data = HorizontalBinaryStructure::format_input_data(&data);
                     let mut structure = HorizontalBinaryStructure::new(&horizontal_data);
-                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup, $depth, $algo), $value);
+                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup as Support, $depth, $algo), $value as f64);
                 }
 
 
@@ -45,7 +46,7 @@ macro_rules! integration_tests_lgdt {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let horizontal_data = BitsetStructure::format_input_data(&data);
                     let mut structure = BitsetStructure::new(&horizontal_data);
-                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup, $depth, $algo), $value);
+                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup as Support, $depth, $algo), $value as f64);
                 }
 
                 #[test]
@@ -53,7 +54,7 @@ macro_rules! integration_tests_lgdt {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let horizontal_data = RSparseBitsetStructure::format_input_data(&data);
                     let mut structure = RSparseBitsetStructure::new(&horizontal_data);
-                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup, $depth, $algo), $value);
+                    assert_eq!(solve_instance_lgdt(&mut structure, $minsup as Support, $depth, $algo), $value as f64);
                 }
             }
         )*
@@ -68,7 +69,7 @@ macro_rules! integration_tests_idk {
                  fn [<idk_ $name _ $algo _raw_ $name _minsup_ $minsup>]() {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let mut structure = RawBinaryStructure::new(&data);
-                    assert_eq!(solve_instance_idk(&mut structure, $minsup, $algo), $value);
+                    assert_eq!(solve_instance_idk(&mut structure, $minsup as Support, $algo), $value as f64);
                 }
 
                 #[test]
@@ -76,7 +77,7 @@ macro_rules! integration_tests_idk {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let horizontal_data = HorizontalBinaryStructure::format_input_data(&data);
                     let mut structure = HorizontalBinaryStructure::new(&horizontal_data);
-                    assert_eq!(solve_instance_idk(&mut structure, $minsup, $algo), $value);
+                    assert_eq!(solve_instance_idk(&mut structure, $minsup as Support, $algo), $value as f64);
                 }
 
 
@@ -85,7 +86,7 @@ macro_rules! integration_tests_idk {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let horizontal_data = BitsetStructure::format_input_data(&data);
                     let mut structure = BitsetStructure::new(&horizontal_data);
-                    assert_eq!(solve_instance_idk(&mut structure, $minsup, $algo), $value);
+                    assert_eq!(solve_instance_idk(&mut structure, $minsup as Support, $algo), $value as f64);
                 }
 
                 #[test]
@@ -93,7 +94,7 @@ macro_rules! integration_tests_idk {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let horizontal_data = RSparseBitsetStructure::format_input_data(&data);
                     let mut structure = RSparseBitsetStructure::new(&horizontal_data);
-                    assert_eq!(solve_instance_idk(&mut structure, $minsup, $algo), $value);
+                    assert_eq!(solve_instance_idk(&mut structure, $minsup as Support, $algo), $value as f64);
                 }
             }
         )*
@@ -109,8 +110,28 @@ macro_rules! integration_tests_dl85 {
                     let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
                     let sparse_data = RSparseBitsetStructure::format_input_data(&data);
                     let mut structure = RSparseBitsetStructure::new(&sparse_data);
-                    for error in solve_instance_dl85(&mut structure, $minsup, $maxdepth) {
-                        assert_eq!(error, $value);
+                    for error in solve_instance_dl85(&mut structure, $minsup as Support, $maxdepth) {
+                        assert_eq!(error, $value as f64);
+                    }
+                }
+
+                #[test]
+                 fn [<dl85_bitset_ $name _minsup_ $minsup _maxdepth_ $maxdepth >]() {
+                    let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
+                    let bitset_data = BitsetStructure::format_input_data(&data);
+                    let mut structure = BitsetStructure::new(&bitset_data);
+                    for error in solve_instance_dl85(&mut structure, $minsup as Support, $maxdepth) {
+                        assert_eq!(error, $value as f64);
+                    }
+                }
+
+                #[test]
+                 fn [<dl85_horizontal_ $name _minsup_ $minsup _maxdepth_ $maxdepth >]() {
+                    let data = BinaryDataset::load(&format!("test_data/{}.txt", stringify!($name)), false, 0.0);
+                    let horizontal_data = HorizontalBinaryStructure::format_input_data(&data);
+                    let mut structure = HorizontalBinaryStructure::new(&horizontal_data);
+                    for error in solve_instance_dl85(&mut structure, $minsup as Support, $maxdepth) {
+                        assert_eq!(error, $value as f64);
                     }
                 }
 
@@ -119,7 +140,7 @@ macro_rules! integration_tests_dl85 {
     }
 }
 
-fn solve_instance_lgdt<S>(structure: &mut S, minsup: Support, depth: Depth, algo: &str) -> usize
+fn solve_instance_lgdt<S>(structure: &mut S, minsup: Support, depth: Depth, algo: &str) -> f64
 where
     S: Structure,
 {
@@ -130,7 +151,7 @@ where
     LGDT::get_tree_error(&tree)
 }
 
-fn solve_instance_idk<S>(structure: &mut S, minsup: Support, algo: &str) -> usize
+fn solve_instance_idk<S>(structure: &mut S, minsup: Support, algo: &str) -> f64
 where
     S: Structure,
 {
@@ -141,12 +162,11 @@ where
     LGDT::get_tree_error(&tree)
 }
 
-fn solve_instance_dl85(
-    structure: &mut RSparseBitsetStructure,
-    minsup: Support,
-    max_depth: Depth,
-) -> Vec<usize> {
-    let mut heuristic: Box<dyn Heuristic> = Box::new(NoHeuristic::default());
+fn solve_instance_dl85<S>(structure: &mut S, minsup: Support, max_depth: Depth) -> Vec<f64>
+where
+    S: Structure,
+{
+    let mut heuristic: Box<dyn Heuristic> = Box::new(NoHeuristic);
     let mut errors = vec![];
 
     let specializations = vec![Specialization::None, Specialization::Murtree];
@@ -159,7 +179,7 @@ fn solve_instance_dl85(
                 let mut algo: DL85<'_, _, Data> = DL85::new(
                     minsup,
                     max_depth,
-                    <usize>::MAX,
+                    <f64>::MAX,
                     300,
                     *spec,
                     *lower_bound,
@@ -168,8 +188,9 @@ fn solve_instance_dl85(
                     0,
                     true,
                     heuristic.as_mut(),
+                    Misclassification,
                 );
-                algo.fit(structure);
+                algo.fit(structure).unwrap();
                 errors.push(algo.statistics.tree_error);
                 structure.reset();
             }